@@ -4,26 +4,105 @@ multiversx_sc::derive_imports!();
 use fixed_supply_token::FixedSupplyToken;
 use math::weighted_average_round_up;
 use mergeable::Mergeable;
+use multiversx_sc::codec::{
+    EncodeErrorHandler, NestedDecodeInput, TopDecodeInput, TopEncodeOutput,
+};
 
-use crate::Epoch;
-
-#[derive(
-    ManagedVecItem,
-    TopEncode,
-    TopDecode,
-    NestedEncode,
-    NestedDecode,
-    TypeAbi,
-    Clone,
-    PartialEq,
-    Debug,
-)]
+use crate::{Epoch, Percent};
+
+/// `unlock_epoch` and `reward_boost_percent` are only set for positions locked
+/// in exchange for a reward boost. They are left out of the top encoding when unset,
+/// so the attributes of unlocked positions keep their original format.
+#[derive(ManagedVecItem, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Debug)]
 pub struct FarmTokenAttributes<M: ManagedTypeApi> {
     pub reward_per_share: BigUint<M>,
     pub entering_epoch: Epoch,
     pub compounded_reward: BigUint<M>,
     pub current_farm_amount: BigUint<M>,
     pub original_owner: ManagedAddress<M>,
+    pub unlock_epoch: Epoch,
+    pub reward_boost_percent: Percent,
+}
+
+impl<M: ManagedTypeApi> FarmTokenAttributes<M> {
+    #[inline]
+    pub fn is_locked(&self) -> bool {
+        self.unlock_epoch != 0 || self.reward_boost_percent != 0
+    }
+
+    /// An expired lock accrues no further boost, so the position is unlocked
+    /// once its rewards were paid. The farm passes in the current epoch.
+    pub fn clear_expired_lock(&mut self, current_epoch: Epoch) {
+        if self.is_locked() && current_epoch >= self.unlock_epoch {
+            self.unlock_epoch = 0;
+            self.reward_boost_percent = 0;
+        }
+    }
+}
+
+impl<M: ManagedTypeApi> TopEncode for FarmTokenAttributes<M> {
+    fn top_encode_or_handle_err<O, H>(&self, output: O, h: H) -> Result<(), H::HandledErr>
+    where
+        O: TopEncodeOutput,
+        H: EncodeErrorHandler,
+    {
+        let mut buffer = output.start_nested_encode();
+        self.reward_per_share
+            .dep_encode_or_handle_err(&mut buffer, h)?;
+        self.entering_epoch
+            .dep_encode_or_handle_err(&mut buffer, h)?;
+        self.compounded_reward
+            .dep_encode_or_handle_err(&mut buffer, h)?;
+        self.current_farm_amount
+            .dep_encode_or_handle_err(&mut buffer, h)?;
+        self.original_owner
+            .dep_encode_or_handle_err(&mut buffer, h)?;
+        if self.is_locked() {
+            self.unlock_epoch.dep_encode_or_handle_err(&mut buffer, h)?;
+            self.reward_boost_percent
+                .dep_encode_or_handle_err(&mut buffer, h)?;
+        }
+        output.finalize_nested_encode(buffer);
+
+        Result::Ok(())
+    }
+}
+
+impl<M: ManagedTypeApi> TopDecode for FarmTokenAttributes<M> {
+    fn top_decode<I>(input: I) -> Result<Self, DecodeError>
+    where
+        I: TopDecodeInput,
+    {
+        let mut buffer = input.into_nested_buffer();
+        let reward_per_share = BigUint::dep_decode(&mut buffer)?;
+        let entering_epoch = Epoch::dep_decode(&mut buffer)?;
+        let compounded_reward = BigUint::dep_decode(&mut buffer)?;
+        let current_farm_amount = BigUint::dep_decode(&mut buffer)?;
+        let original_owner = ManagedAddress::dep_decode(&mut buffer)?;
+
+        let (unlock_epoch, reward_boost_percent) = if !buffer.is_depleted() {
+            (
+                Epoch::dep_decode(&mut buffer)?,
+                Percent::dep_decode(&mut buffer)?,
+            )
+        } else {
+            (0, 0)
+        };
+
+        if !buffer.is_depleted() {
+            return Result::Err(DecodeError::INPUT_TOO_LONG);
+        }
+
+        Result::Ok(FarmTokenAttributes {
+            reward_per_share,
+            entering_epoch,
+            compounded_reward,
+            current_farm_amount,
+            original_owner,
+            unlock_epoch,
+            reward_boost_percent,
+        })
+    }
 }
 
 impl<M: ManagedTypeApi> FixedSupplyToken<M> for FarmTokenAttributes<M> {
@@ -45,14 +124,19 @@ impl<M: ManagedTypeApi> FixedSupplyToken<M> for FarmTokenAttributes<M> {
             compounded_reward: new_compounded_reward,
             current_farm_amount: new_current_farm_amount,
             original_owner: self.original_owner,
+            unlock_epoch: self.unlock_epoch,
+            reward_boost_percent: self.reward_boost_percent,
         }
     }
 }
 
 impl<M: ManagedTypeApi> Mergeable<M> for FarmTokenAttributes<M> {
+    /// Only positions with the same lock can be merged, as the boost
+    /// of each position is paid until its own unlock epoch
     #[inline]
-    fn can_merge_with(&self, _other: &Self) -> bool {
-        true
+    fn can_merge_with(&self, other: &Self) -> bool {
+        self.unlock_epoch == other.unlock_epoch
+            && self.reward_boost_percent == other.reward_boost_percent
     }
 
     fn merge_with(&mut self, other: Self) {
@@ -106,6 +106,17 @@ pub trait FarmContract {
         }
     }
 
+    /// Called right before `calculate_rewards` when rewards are paid out.
+    /// Rewards that are not covered by the aggregated rewards must be minted
+    /// and added to the reserve here.
+    fn mint_extra_rewards(
+        _sc: &Self::FarmSc,
+        _farm_token_amount: &BigUint<<Self::FarmSc as ContractBase>::Api>,
+        _token_attributes: &Self::AttributesType,
+        _storage_cache: &mut StorageCache<Self::FarmSc>,
+    ) {
+    }
+
     fn calculate_rewards(
         _sc: &Self::FarmSc,
         _caller: &ManagedAddress<<Self::FarmSc as ContractBase>::Api>,
@@ -135,19 +146,22 @@ pub trait FarmContract {
             compounded_reward: BigUint::zero(),
             current_farm_amount: farming_token_amount,
             original_owner: caller,
+            unlock_epoch: 0,
+            reward_boost_percent: 0,
         };
 
         attributes.into()
     }
 
     fn create_claim_rewards_initial_attributes(
-        _sc: &Self::FarmSc,
+        sc: &Self::FarmSc,
         caller: ManagedAddress<<Self::FarmSc as ContractBase>::Api>,
         first_token_attributes: Self::AttributesType,
         current_reward_per_share: BigUint<<Self::FarmSc as ContractBase>::Api>,
     ) -> Self::AttributesType {
-        let initial_attributes: FarmTokenAttributes<<Self::FarmSc as ContractBase>::Api> =
+        let mut initial_attributes: FarmTokenAttributes<<Self::FarmSc as ContractBase>::Api> =
             first_token_attributes.into();
+        initial_attributes.clear_expired_lock(sc.blockchain().get_block_epoch());
 
         let net_current_farm_amount = initial_attributes.get_total_supply();
         let new_attributes = FarmTokenAttributes {
@@ -156,6 +170,8 @@ pub trait FarmContract {
             compounded_reward: initial_attributes.compounded_reward,
             current_farm_amount: net_current_farm_amount,
             original_owner: caller,
+            unlock_epoch: initial_attributes.unlock_epoch,
+            reward_boost_percent: initial_attributes.reward_boost_percent,
         };
 
         new_attributes.into()
@@ -168,10 +184,12 @@ pub trait FarmContract {
         current_reward_per_share: BigUint<<Self::FarmSc as ContractBase>::Api>,
         reward: &BigUint<<Self::FarmSc as ContractBase>::Api>,
     ) -> Self::AttributesType {
-        let initial_attributes: FarmTokenAttributes<<Self::FarmSc as ContractBase>::Api> =
+        let mut initial_attributes: FarmTokenAttributes<<Self::FarmSc as ContractBase>::Api> =
             first_token_attributes.into();
 
         let current_epoch = sc.blockchain().get_block_epoch();
+        initial_attributes.clear_expired_lock(current_epoch);
+
         let new_pos_compounded_reward = initial_attributes.compounded_reward + reward;
         let new_pos_current_farm_amount = initial_attributes.current_farm_amount + reward;
         let new_attributes = FarmTokenAttributes {
@@ -180,6 +198,8 @@ pub trait FarmContract {
             compounded_reward: new_pos_compounded_reward,
             current_farm_amount: new_pos_current_farm_amount,
             original_owner: caller,
+            unlock_epoch: initial_attributes.unlock_epoch,
+            reward_boost_percent: initial_attributes.reward_boost_percent,
         };
 
         new_attributes.into()
@@ -72,6 +72,13 @@ pub trait BaseClaimRewardsModule:
             .clone()
             .into_part(farm_token_amount);
 
+        FC::mint_extra_rewards(
+            self,
+            farm_token_amount,
+            &token_attributes,
+            &mut storage_cache,
+        );
+
         let reward = FC::calculate_rewards(
             self,
             &caller,
@@ -35,6 +35,21 @@ pub trait BaseEnterFarmModule:
         caller: ManagedAddress,
         payments: PaymentsVec<Self::Api>,
     ) -> InternalEnterFarmResult<Self, FC::AttributesType> {
+        self.enter_farm_base_with_attributes::<FC, _>(caller, payments, |attributes| attributes)
+    }
+
+    /// `update_attributes` is applied to the attributes of the new position,
+    /// before it is merged with the additional farm tokens
+    fn enter_farm_base_with_attributes<FC, F>(
+        &self,
+        caller: ManagedAddress,
+        payments: PaymentsVec<Self::Api>,
+        update_attributes: F,
+    ) -> InternalEnterFarmResult<Self, FC::AttributesType>
+    where
+        FC: FarmContract<FarmSc = Self>,
+        F: FnOnce(FC::AttributesType) -> FC::AttributesType,
+    {
         let mut storage_cache = StorageCache::new(self);
         self.validate_contract_state(storage_cache.contract_state, &storage_cache.farm_token_id);
 
@@ -68,7 +83,7 @@ pub trait BaseEnterFarmModule:
             storage_cache.reward_per_share.clone(),
         );
         let new_farm_token = self.merge_and_create_token(
-            base_attributes,
+            update_attributes(base_attributes),
             &enter_farm_context.additional_farm_tokens,
             &farm_token_mapper,
         );
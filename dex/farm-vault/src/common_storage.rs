@@ -0,0 +1,113 @@
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait CommonStorageModule {
+    /// Moves the given token amount to the leftovers, which are used on the next compound
+    fn add_leftover(&self, payment: &EsdtTokenPayment) {
+        if payment.amount > 0 {
+            self.leftover(&payment.token_identifier)
+                .update(|leftover| *leftover += &payment.amount);
+        }
+    }
+
+    /// Keeps the farm's additional token and campaign rewards paid to the vault,
+    /// which belong to the share holders and are paid out pro rata on withdraw
+    fn add_extra_rewards(&self, payments: &ManagedVec<EsdtTokenPayment>) {
+        for payment in payments {
+            if payment.amount == 0 {
+                continue;
+            }
+
+            let _ = self
+                .extra_reward_tokens()
+                .insert(payment.token_identifier.clone());
+            self.extra_reward_amount(&payment.token_identifier)
+                .update(|amount| *amount += &payment.amount);
+        }
+    }
+
+    fn take_extra_rewards_share(
+        &self,
+        shares_amount: &BigUint,
+        share_supply: &BigUint,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        let mut payments = ManagedVec::new();
+        let mut extra_reward_tokens = self.extra_reward_tokens();
+        let token_ids: ManagedVec<TokenIdentifier> = extra_reward_tokens.iter().collect();
+        for token_id in &token_ids {
+            let amount_mapper = self.extra_reward_amount(&token_id);
+            let total_amount = amount_mapper.get();
+            let amount = if shares_amount >= share_supply {
+                total_amount.clone()
+            } else {
+                &total_amount * shares_amount / share_supply
+            };
+            if amount == 0 {
+                continue;
+            }
+
+            if amount == total_amount {
+                amount_mapper.clear();
+                let _ = extra_reward_tokens.swap_remove(&token_id);
+            } else {
+                amount_mapper.set(&total_amount - &amount);
+            }
+            payments.push(EsdtTokenPayment::new(token_id, 0, amount));
+        }
+
+        payments
+    }
+
+    #[view(getTotalLpAmount)]
+    fn get_total_lp_amount(&self) -> BigUint {
+        let position_mapper = self.farm_position();
+        if position_mapper.is_empty() {
+            return BigUint::zero();
+        }
+
+        position_mapper.get().amount
+    }
+
+    #[view(getPairAddress)]
+    #[storage_mapper("pair_address")]
+    fn pair_address(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getFarmAddress)]
+    #[storage_mapper("farm_address")]
+    fn farm_address(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getLpTokenId)]
+    #[storage_mapper("lp_token_id")]
+    fn lp_token_id(&self) -> SingleValueMapper<TokenIdentifier>;
+
+    #[view(getRewardTokenId)]
+    #[storage_mapper("reward_token_id")]
+    fn reward_token_id(&self) -> SingleValueMapper<TokenIdentifier>;
+
+    /// The pair token that is not the farm reward token
+    #[view(getOtherTokenId)]
+    #[storage_mapper("other_token_id")]
+    fn other_token_id(&self) -> SingleValueMapper<TokenIdentifier>;
+
+    #[storage_mapper("reward_token_is_first")]
+    fn reward_token_is_first(&self) -> SingleValueMapper<bool>;
+
+    /// The single farm position holding all the deposited LP tokens
+    #[view(getFarmPosition)]
+    #[storage_mapper("farm_position")]
+    fn farm_position(&self) -> SingleValueMapper<EsdtTokenPayment>;
+
+    #[view(getExtraRewardTokens)]
+    #[storage_mapper("extra_reward_tokens")]
+    fn extra_reward_tokens(&self) -> UnorderedSetMapper<TokenIdentifier>;
+
+    /// The farm's extra rewards held for the share holders
+    #[view(getExtraRewardAmount)]
+    #[storage_mapper("extra_reward_amount")]
+    fn extra_reward_amount(&self, token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    /// Token amounts not yet added to the farm position, e.g. dust from adding liquidity
+    #[view(getLeftover)]
+    #[storage_mapper("leftover")]
+    fn leftover(&self, token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+}
@@ -0,0 +1,96 @@
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait CompoundModule:
+    crate::common_storage::CommonStorageModule
+    + crate::farm_interactions::FarmInteractionsModule
+    + crate::pair_interactions::PairInteractionsModule
+{
+    #[only_owner]
+    #[endpoint(addKeepers)]
+    fn add_keepers(&self, keepers: MultiValueEncoded<ManagedAddress>) {
+        let mut keepers_mapper = self.keepers();
+        for keeper in keepers {
+            let _ = keepers_mapper.insert(keeper);
+        }
+    }
+
+    #[only_owner]
+    #[endpoint(removeKeepers)]
+    fn remove_keepers(&self, keepers: MultiValueEncoded<ManagedAddress>) {
+        let mut keepers_mapper = self.keepers();
+        for keeper in keepers {
+            let _ = keepers_mapper.swap_remove(&keeper);
+        }
+    }
+
+    /// Claims the farm rewards, swaps half of them for the other pair token,
+    /// adds liquidity and merges the new farm position into the vault's position.
+    /// Only keepers may call it, as they choose the slippage limits.
+    /// Returns the amount of LP tokens added to the farm position.
+    #[endpoint]
+    fn compound(
+        &self,
+        swap_amount_out_min: BigUint,
+        first_token_amount_min: BigUint,
+        second_token_amount_min: BigUint,
+    ) -> BigUint {
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.keepers().contains(&caller),
+            "Only keepers may compound"
+        );
+
+        let position_mapper = self.farm_position();
+        require!(!position_mapper.is_empty(), "Nothing to compound");
+
+        let claim_result = self.call_claim_rewards(position_mapper.get());
+        position_mapper.set(claim_result.farm_token);
+        self.add_extra_rewards(&claim_result.extra_rewards);
+
+        let reward_token_id = self.reward_token_id().get();
+        let other_token_id = self.other_token_id().get();
+        let mut reward_amount =
+            claim_result.rewards.amount + self.leftover(&reward_token_id).take();
+        let mut other_amount = self.leftover(&other_token_id).take();
+        require!(reward_amount > 0, "No rewards to compound");
+
+        let swap_amount = &reward_amount / 2u32;
+        if swap_amount > 0 {
+            let swap_payment =
+                EsdtTokenPayment::new(reward_token_id.clone(), 0, swap_amount.clone());
+            let swap_result = self.call_swap_fixed_input(
+                swap_payment,
+                other_token_id.clone(),
+                swap_amount_out_min,
+            );
+            reward_amount -= swap_amount;
+            other_amount += swap_result.amount;
+        }
+
+        let reward_payment = EsdtTokenPayment::new(reward_token_id.clone(), 0, reward_amount);
+        let other_payment = EsdtTokenPayment::new(other_token_id, 0, other_amount);
+        let (first_payment, second_payment) = if self.reward_token_is_first().get() {
+            (reward_payment, other_payment)
+        } else {
+            (other_payment, reward_payment)
+        };
+        let add_liq_result = self.call_add_liquidity(
+            first_payment,
+            second_payment,
+            first_token_amount_min,
+            second_token_amount_min,
+        );
+        self.add_leftover(&add_liq_result.first_token_leftover);
+        self.add_leftover(&add_liq_result.second_token_leftover);
+
+        let lp_amount = add_liq_result.lp_tokens_received.amount.clone();
+        self.add_to_farm_position(add_liq_result.lp_tokens_received);
+
+        lp_amount
+    }
+
+    #[view(getKeepers)]
+    #[storage_mapper("keepers")]
+    fn keepers(&self) -> UnorderedSetMapper<ManagedAddress>;
+}
@@ -0,0 +1,118 @@
+multiversx_sc::imports!();
+
+use farm::{
+    base_functions::{ClaimRewardsResultType, DoubleMultiPayment},
+    EnterFarmResultType, ExitFarmWithPartialPosResultType, ProxyTrait as _,
+};
+
+pub struct FarmResultWrapper<M: ManagedTypeApi> {
+    pub farm_token: EsdtTokenPayment<M>,
+    pub rewards: EsdtTokenPayment<M>,
+}
+
+pub struct ClaimRewardsResultWrapper<M: ManagedTypeApi> {
+    pub farm_token: EsdtTokenPayment<M>,
+    pub rewards: EsdtTokenPayment<M>,
+    pub extra_rewards: ManagedVec<M, EsdtTokenPayment<M>>,
+}
+
+pub struct ExitFarmResultWrapper<M: ManagedTypeApi> {
+    pub farming_tokens: EsdtTokenPayment<M>,
+    pub rewards: EsdtTokenPayment<M>,
+    pub extra_rewards: ManagedVec<M, EsdtTokenPayment<M>>,
+}
+
+#[multiversx_sc::module]
+pub trait FarmInteractionsModule: crate::common_storage::CommonStorageModule {
+    /// Enters the farm with the given LP tokens and merges the new farm token
+    /// into the vault's farm position
+    fn add_to_farm_position(&self, lp_tokens: EsdtTokenPayment) {
+        let enter_result = self.call_enter_farm(lp_tokens);
+        self.add_leftover(&enter_result.rewards);
+
+        let position_mapper = self.farm_position();
+        if position_mapper.is_empty() {
+            position_mapper.set(enter_result.farm_token);
+            return;
+        }
+
+        let mut farm_tokens = ManagedVec::new();
+        farm_tokens.push(position_mapper.get());
+        farm_tokens.push(enter_result.farm_token);
+        let merge_result = self.call_merge_farm_tokens(farm_tokens);
+        self.add_leftover(&merge_result.rewards);
+
+        position_mapper.set(merge_result.farm_token);
+    }
+
+    fn call_enter_farm(&self, farming_tokens: EsdtTokenPayment) -> FarmResultWrapper<Self::Api> {
+        let farm_address = self.farm_address().get();
+        let raw_result: EnterFarmResultType<Self::Api> = self
+            .farm_contract_proxy(farm_address)
+            .enter_farm_endpoint(OptionalValue::<ManagedAddress>::None)
+            .with_esdt_transfer(farming_tokens)
+            .execute_on_dest_context();
+        let (farm_token, rewards) = raw_result.into_tuple();
+
+        FarmResultWrapper {
+            farm_token,
+            rewards,
+        }
+    }
+
+    fn call_merge_farm_tokens(
+        &self,
+        farm_tokens: ManagedVec<EsdtTokenPayment>,
+    ) -> FarmResultWrapper<Self::Api> {
+        let farm_address = self.farm_address().get();
+        let raw_result: DoubleMultiPayment<Self::Api> = self
+            .farm_contract_proxy(farm_address)
+            .merge_farm_tokens_endpoint(OptionalValue::<ManagedAddress>::None)
+            .with_multi_token_transfer(farm_tokens)
+            .execute_on_dest_context();
+        let (farm_token, rewards) = raw_result.into_tuple();
+
+        FarmResultWrapper {
+            farm_token,
+            rewards,
+        }
+    }
+
+    fn call_claim_rewards(
+        &self,
+        farm_token: EsdtTokenPayment,
+    ) -> ClaimRewardsResultWrapper<Self::Api> {
+        let farm_address = self.farm_address().get();
+        let raw_result: ClaimRewardsResultType<Self::Api> = self
+            .farm_contract_proxy(farm_address)
+            .claim_rewards_endpoint(OptionalValue::<ManagedAddress>::None)
+            .with_esdt_transfer(farm_token)
+            .execute_on_dest_context();
+        let (farm_token, rewards, extra_rewards) = raw_result.into_tuple();
+
+        ClaimRewardsResultWrapper {
+            farm_token,
+            rewards,
+            extra_rewards,
+        }
+    }
+
+    fn call_exit_farm(&self, farm_token: EsdtTokenPayment) -> ExitFarmResultWrapper<Self::Api> {
+        let farm_address = self.farm_address().get();
+        let raw_result: ExitFarmWithPartialPosResultType<Self::Api> = self
+            .farm_contract_proxy(farm_address)
+            .exit_farm_endpoint(OptionalValue::<ManagedAddress>::None)
+            .with_esdt_transfer(farm_token)
+            .execute_on_dest_context();
+        let (farming_tokens, rewards, extra_rewards) = raw_result.into_tuple();
+
+        ExitFarmResultWrapper {
+            farming_tokens,
+            rewards,
+            extra_rewards,
+        }
+    }
+
+    #[proxy]
+    fn farm_contract_proxy(&self, to: ManagedAddress) -> farm::Proxy<Self::Api>;
+}
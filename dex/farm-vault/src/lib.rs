@@ -0,0 +1,149 @@
+#![no_std]
+
+multiversx_sc::imports!();
+
+pub mod common_storage;
+pub mod compound;
+pub mod farm_interactions;
+pub mod pair_interactions;
+pub mod vault_share;
+
+use config::ProxyTrait as _;
+use pair::read_pair_storage;
+
+pub type WithdrawResultType<M> =
+    MultiValue3<EsdtTokenPayment<M>, EsdtTokenPayment<M>, ManagedVec<M, EsdtTokenPayment<M>>>;
+
+/// Holds the users' LP tokens in a single farm position and periodically compounds
+/// the farm rewards back into it. Users own fungible vault shares, which are
+/// redeemed for a proportional part of the position and of the farm's extra rewards
+/// paid to the vault.
+#[multiversx_sc::contract]
+pub trait FarmVault:
+    common_storage::CommonStorageModule
+    + compound::CompoundModule
+    + farm_interactions::FarmInteractionsModule
+    + pair_interactions::PairInteractionsModule
+    + vault_share::VaultShareModule
+    + read_pair_storage::ReadPairStorageModule
+    + token_send::TokenSendModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+{
+    /// The farm's farming token must be the pair's LP token,
+    /// and its reward token must be one of the pair tokens.
+    #[init]
+    fn init(&self, pair_address: ManagedAddress, farm_address: ManagedAddress) {
+        require!(
+            self.blockchain().is_smart_contract(&pair_address),
+            "Invalid pair address"
+        );
+        require!(
+            self.blockchain().is_smart_contract(&farm_address),
+            "Invalid farm address"
+        );
+
+        let lp_token_id = self.get_lp_token_id_mapper(pair_address.clone()).get();
+        let farming_token_id: TokenIdentifier = self
+            .farm_contract_proxy(farm_address.clone())
+            .farming_token_id()
+            .execute_on_dest_context();
+        require!(farming_token_id == lp_token_id, "Farm is not for this pair");
+
+        let first_token_id = self.get_first_token_id_mapper(pair_address.clone()).get();
+        let second_token_id = self.get_second_token_id_mapper(pair_address.clone()).get();
+        let reward_token_id: TokenIdentifier = self
+            .farm_contract_proxy(farm_address.clone())
+            .reward_token_id()
+            .execute_on_dest_context();
+        let other_token_id = if reward_token_id == first_token_id {
+            self.reward_token_is_first().set(true);
+            second_token_id
+        } else if reward_token_id == second_token_id {
+            self.reward_token_is_first().set(false);
+            first_token_id
+        } else {
+            sc_panic!("Reward token is not a pair token");
+        };
+
+        self.pair_address().set(pair_address);
+        self.farm_address().set(farm_address);
+        self.lp_token_id().set(lp_token_id);
+        self.reward_token_id().set(reward_token_id);
+        self.other_token_id().set(other_token_id);
+    }
+
+    #[upgrade]
+    fn upgrade(&self) {}
+
+    /// Deposits LP tokens into the farm position, in exchange for vault shares
+    #[payable("*")]
+    #[endpoint]
+    fn deposit(&self) -> EsdtTokenPayment {
+        let payment = self.call_value().single_esdt();
+        require!(
+            payment.token_identifier == self.lp_token_id().get() && payment.token_nonce == 0,
+            "Invalid payment token"
+        );
+        require!(payment.amount > 0, "Invalid payment amount");
+        require!(!self.share_token().is_empty(), "Share token not issued");
+
+        let shares_amount = self.get_shares_for_lp_amount(&payment.amount);
+        require!(shares_amount > 0, "Deposit too small");
+
+        self.add_to_farm_position(payment);
+
+        let caller = self.blockchain().get_caller();
+        let shares = self.mint_shares(shares_amount);
+        self.send_payment_non_zero(&caller, &shares);
+
+        shares
+    }
+
+    /// Burns the shares and exits the farm with the corresponding part of the position.
+    /// Returns the LP tokens, the farm rewards not yet compounded for that part
+    /// and the shares' part of the farm's extra rewards held by the vault.
+    #[payable("*")]
+    #[endpoint]
+    fn withdraw(&self) -> WithdrawResultType<Self::Api> {
+        let payment = self.call_value().single_esdt();
+        self.share_token()
+            .require_same_token(&payment.token_identifier);
+        require!(payment.amount > 0, "Invalid payment amount");
+
+        let lp_amount = self.get_lp_amount_for_shares(payment.amount.clone());
+        require!(lp_amount > 0, "Withdraw amount too small");
+
+        let share_supply = self.share_token_supply().get();
+        self.burn_shares(&payment.amount);
+
+        let position_mapper = self.farm_position();
+        let mut farm_position = position_mapper.get();
+        let exit_payment = EsdtTokenPayment::new(
+            farm_position.token_identifier.clone(),
+            farm_position.token_nonce,
+            lp_amount.clone(),
+        );
+        farm_position.amount -= &lp_amount;
+        if farm_position.amount == 0 {
+            position_mapper.clear();
+        } else {
+            position_mapper.set(farm_position);
+        }
+
+        let exit_result = self.call_exit_farm(exit_payment);
+        self.add_extra_rewards(&exit_result.extra_rewards);
+        let extra_rewards = self.take_extra_rewards_share(&payment.amount, &share_supply);
+
+        let caller = self.blockchain().get_caller();
+        self.send_payment_non_zero(&caller, &exit_result.farming_tokens);
+        self.send_payment_non_zero(&caller, &exit_result.rewards);
+        self.send_multiple_tokens_if_not_zero(&caller, &extra_rewards);
+
+        (
+            exit_result.farming_tokens,
+            exit_result.rewards,
+            extra_rewards,
+        )
+            .into()
+    }
+}
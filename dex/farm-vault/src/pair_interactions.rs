@@ -0,0 +1,80 @@
+multiversx_sc::imports!();
+
+use pair::pair_actions::{
+    add_liq::ProxyTrait as _,
+    common_result_types::{AddLiquidityResultType, SwapTokensFixedInputResultType},
+    swap::ProxyTrait as _,
+};
+
+pub struct AddLiquidityResultWrapper<M: ManagedTypeApi> {
+    pub lp_tokens_received: EsdtTokenPayment<M>,
+    pub first_token_leftover: EsdtTokenPayment<M>,
+    pub second_token_leftover: EsdtTokenPayment<M>,
+}
+
+#[multiversx_sc::module]
+pub trait PairInteractionsModule: crate::common_storage::CommonStorageModule {
+    fn call_swap_fixed_input(
+        &self,
+        payment: EsdtTokenPayment,
+        token_out: TokenIdentifier,
+        amount_out_min: BigUint,
+    ) -> EsdtTokenPayment {
+        let pair_address = self.pair_address().get();
+        let result: SwapTokensFixedInputResultType<Self::Api> = self
+            .pair_contract_proxy(pair_address)
+            .swap_tokens_fixed_input(
+                token_out,
+                amount_out_min,
+                OptionalValue::<ManagedAddress>::None,
+            )
+            .with_esdt_transfer(payment)
+            .execute_on_dest_context();
+
+        result
+    }
+
+    /// The payments must be given in the pair's token order
+    fn call_add_liquidity(
+        &self,
+        first_payment: EsdtTokenPayment,
+        second_payment: EsdtTokenPayment,
+        first_token_amount_min: BigUint,
+        second_token_amount_min: BigUint,
+    ) -> AddLiquidityResultWrapper<Self::Api> {
+        let first_token_amount_desired = first_payment.amount.clone();
+        let second_token_amount_desired = second_payment.amount.clone();
+
+        let mut all_token_payments = ManagedVec::new();
+        all_token_payments.push(first_payment);
+        all_token_payments.push(second_payment);
+
+        let pair_address = self.pair_address().get();
+        let raw_result: AddLiquidityResultType<Self::Api> = self
+            .pair_contract_proxy(pair_address)
+            .add_liquidity(first_token_amount_min, second_token_amount_min)
+            .with_multi_token_transfer(all_token_payments)
+            .execute_on_dest_context();
+        let (lp_tokens_received, first_tokens_used, second_tokens_used) = raw_result.into_tuple();
+
+        let first_token_leftover = EsdtTokenPayment::new(
+            first_tokens_used.token_identifier,
+            0,
+            first_token_amount_desired - first_tokens_used.amount,
+        );
+        let second_token_leftover = EsdtTokenPayment::new(
+            second_tokens_used.token_identifier,
+            0,
+            second_token_amount_desired - second_tokens_used.amount,
+        );
+
+        AddLiquidityResultWrapper {
+            lp_tokens_received,
+            first_token_leftover,
+            second_token_leftover,
+        }
+    }
+
+    #[proxy]
+    fn pair_contract_proxy(&self, to: ManagedAddress) -> pair::Proxy<Self::Api>;
+}
@@ -0,0 +1,70 @@
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait VaultShareModule:
+    crate::common_storage::CommonStorageModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+{
+    #[only_owner]
+    #[payable("EGLD")]
+    #[endpoint(issueShareToken)]
+    fn issue_share_token(
+        &self,
+        token_display_name: ManagedBuffer,
+        token_ticker: ManagedBuffer,
+        num_decimals: usize,
+    ) {
+        let payment_amount = self.call_value().egld_value().clone_value();
+        self.share_token().issue_and_set_all_roles(
+            payment_amount,
+            token_display_name,
+            token_ticker,
+            num_decimals,
+            None,
+        );
+    }
+
+    /// The first depositor gets shares one to one, afterwards shares are minted
+    /// proportionally to the LP tokens already held in the farm position
+    fn get_shares_for_lp_amount(&self, lp_amount: &BigUint) -> BigUint {
+        let share_supply = self.share_token_supply().get();
+        let total_lp_amount = self.get_total_lp_amount();
+        if share_supply == 0 || total_lp_amount == 0 {
+            return lp_amount.clone();
+        }
+
+        lp_amount * &share_supply / total_lp_amount
+    }
+
+    #[view(getLpAmountForShares)]
+    fn get_lp_amount_for_shares(&self, shares_amount: BigUint) -> BigUint {
+        let share_supply = self.share_token_supply().get();
+        if share_supply == 0 {
+            return BigUint::zero();
+        }
+
+        let total_lp_amount = self.get_total_lp_amount();
+        shares_amount * total_lp_amount / share_supply
+    }
+
+    fn mint_shares(&self, amount: BigUint) -> EsdtTokenPayment {
+        self.share_token_supply()
+            .update(|supply| *supply += &amount);
+
+        self.share_token().mint(amount)
+    }
+
+    fn burn_shares(&self, amount: &BigUint) {
+        self.share_token_supply().update(|supply| *supply -= amount);
+
+        self.share_token().burn(amount);
+    }
+
+    #[view(getShareTokenId)]
+    #[storage_mapper("share_token")]
+    fn share_token(&self) -> FungibleTokenMapper;
+
+    #[view(getShareTokenSupply)]
+    #[storage_mapper("share_token_supply")]
+    fn share_token_supply(&self) -> SingleValueMapper<BigUint>;
+}
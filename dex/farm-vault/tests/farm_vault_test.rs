@@ -0,0 +1,292 @@
+#![allow(deprecated)]
+
+use config::ConfigModule;
+use energy_query::EnergyQueryModule;
+use farm::{additional_rewards::AdditionalRewardsModule, Farm};
+use farm_token::FarmTokenModule;
+use farm_vault::{
+    common_storage::CommonStorageModule, compound::CompoundModule, vault_share::VaultShareModule,
+    FarmVault,
+};
+use multiversx_sc::{
+    storage::mappers::StorageTokenWrapper,
+    types::{Address, EsdtLocalRole, ManagedAddress, MultiValueEncoded},
+};
+use multiversx_sc_scenario::{
+    managed_address, managed_biguint, managed_token_id, rust_biguint,
+    whitebox_legacy::{BlockchainStateWrapper, TxTokenTransfer},
+    DebugApi,
+};
+use pair::{
+    config::ConfigModule as PairConfigModule, pair_actions::add_liq::AddLiquidityModule, Pair,
+};
+use pausable::{PausableModule, State};
+
+static MEX_TOKEN_ID: &[u8] = b"MEX-123456";
+static WEGLD_TOKEN_ID: &[u8] = b"WEGLD-123456";
+static LP_TOKEN_ID: &[u8] = b"LPTOK-123456";
+static FARM_TOKEN_ID: &[u8] = b"FARM-123456";
+static SHARE_TOKEN_ID: &[u8] = b"VAULT-123456";
+static PARTNER_TOKEN_ID: &[u8] = b"PARTNER-123456";
+const DIV_SAFETY: u64 = 1_000_000_000_000;
+const PER_BLOCK_REWARD_AMOUNT: u64 = 1_000;
+const USER_BALANCE: u64 = 1_000_000_000;
+
+#[test]
+fn farm_vault_deposit_compound_withdraw_test() {
+    DebugApi::dummy();
+    let rust_zero = rust_biguint!(0);
+    let mut b_mock = BlockchainStateWrapper::new();
+    let owner = b_mock.create_user_account(&rust_zero);
+    let user = b_mock.create_user_account(&rust_zero);
+    let keeper = b_mock.create_user_account(&rust_zero);
+
+    let pair_wrapper =
+        b_mock.create_sc_account(&rust_zero, Some(&owner), pair::contract_obj, "pair");
+    b_mock
+        .execute_tx(&owner, &pair_wrapper, &rust_zero, |sc| {
+            sc.init(
+                managed_token_id!(MEX_TOKEN_ID),
+                managed_token_id!(WEGLD_TOKEN_ID),
+                managed_address!(&owner),
+                managed_address!(&owner),
+                300,
+                50,
+                ManagedAddress::zero(),
+                MultiValueEncoded::new(),
+            );
+            sc.lp_token_identifier()
+                .set(&managed_token_id!(LP_TOKEN_ID));
+            sc.state().set(State::Active);
+        })
+        .assert_ok();
+    b_mock.set_esdt_local_roles(
+        pair_wrapper.address_ref(),
+        LP_TOKEN_ID,
+        &[EsdtLocalRole::Mint, EsdtLocalRole::Burn],
+    );
+
+    let energy_factory_wrapper = b_mock.create_sc_account(
+        &rust_zero,
+        Some(&owner),
+        energy_factory_mock::contract_obj,
+        "energy_factory.wasm",
+    );
+
+    let farm_wrapper =
+        b_mock.create_sc_account(&rust_zero, Some(&owner), farm::contract_obj, "farm.wasm");
+    b_mock
+        .execute_tx(&owner, &farm_wrapper, &rust_zero, |sc| {
+            sc.init(
+                managed_token_id!(MEX_TOKEN_ID),
+                managed_token_id!(LP_TOKEN_ID),
+                managed_biguint!(DIV_SAFETY),
+                managed_address!(&Address::zero()),
+                managed_address!(&owner),
+                MultiValueEncoded::new(),
+            );
+            sc.farm_token()
+                .set_token_id(managed_token_id!(FARM_TOKEN_ID));
+            sc.per_block_reward_amount()
+                .set(&managed_biguint!(PER_BLOCK_REWARD_AMOUNT));
+            sc.state().set(State::Active);
+            sc.produce_rewards_enabled().set(true);
+            sc.set_energy_factory_address(managed_address!(energy_factory_wrapper.address_ref()));
+        })
+        .assert_ok();
+    b_mock.set_esdt_local_roles(
+        farm_wrapper.address_ref(),
+        FARM_TOKEN_ID,
+        &[
+            EsdtLocalRole::NftCreate,
+            EsdtLocalRole::NftAddQuantity,
+            EsdtLocalRole::NftBurn,
+        ],
+    );
+    b_mock.set_esdt_local_roles(
+        farm_wrapper.address_ref(),
+        MEX_TOKEN_ID,
+        &[EsdtLocalRole::Mint],
+    );
+
+    // 100 partner tokens per block, on top of the farm rewards
+    b_mock.set_esdt_balance(&owner, PARTNER_TOKEN_ID, &rust_biguint!(10_000));
+    b_mock
+        .execute_tx(&owner, &farm_wrapper, &rust_zero, |sc| {
+            sc.add_additional_reward_token(
+                managed_token_id!(PARTNER_TOKEN_ID),
+                managed_biguint!(100),
+            );
+        })
+        .assert_ok();
+    b_mock
+        .execute_esdt_transfer(
+            &owner,
+            &farm_wrapper,
+            PARTNER_TOKEN_ID,
+            0,
+            &rust_biguint!(10_000),
+            |sc| {
+                sc.deposit_additional_rewards();
+            },
+        )
+        .assert_ok();
+
+    let vault_wrapper = b_mock.create_sc_account(
+        &rust_zero,
+        Some(&owner),
+        farm_vault::contract_obj,
+        "farm-vault.wasm",
+    );
+    b_mock
+        .execute_tx(&owner, &vault_wrapper, &rust_zero, |sc| {
+            sc.init(
+                managed_address!(pair_wrapper.address_ref()),
+                managed_address!(farm_wrapper.address_ref()),
+            );
+            assert_eq!(sc.other_token_id().get(), managed_token_id!(WEGLD_TOKEN_ID));
+
+            sc.share_token()
+                .set_token_id(managed_token_id!(SHARE_TOKEN_ID));
+
+            let mut keepers = MultiValueEncoded::new();
+            keepers.push(managed_address!(&keeper));
+            sc.add_keepers(keepers);
+        })
+        .assert_ok();
+    b_mock.set_esdt_local_roles(
+        vault_wrapper.address_ref(),
+        SHARE_TOKEN_ID,
+        &[EsdtLocalRole::Mint, EsdtLocalRole::Burn],
+    );
+
+    // user adds liquidity, then deposits the LP tokens into the vault
+    b_mock.set_esdt_balance(&user, MEX_TOKEN_ID, &rust_biguint!(USER_BALANCE));
+    b_mock.set_esdt_balance(&user, WEGLD_TOKEN_ID, &rust_biguint!(USER_BALANCE));
+    let payments = [
+        TxTokenTransfer {
+            token_identifier: MEX_TOKEN_ID.to_vec(),
+            nonce: 0,
+            value: rust_biguint!(USER_BALANCE),
+        },
+        TxTokenTransfer {
+            token_identifier: WEGLD_TOKEN_ID.to_vec(),
+            nonce: 0,
+            value: rust_biguint!(USER_BALANCE),
+        },
+    ];
+    b_mock
+        .execute_esdt_multi_transfer(&user, &pair_wrapper, &payments, |sc| {
+            let _ = sc.add_liquidity(managed_biguint!(1), managed_biguint!(1));
+        })
+        .assert_ok();
+
+    let lp_amount = USER_BALANCE - 1_000;
+    b_mock.check_esdt_balance(&user, LP_TOKEN_ID, &rust_biguint!(lp_amount));
+
+    b_mock
+        .execute_esdt_transfer(
+            &user,
+            &vault_wrapper,
+            LP_TOKEN_ID,
+            0,
+            &rust_biguint!(lp_amount),
+            |sc| {
+                let shares = sc.deposit();
+                assert_eq!(shares.amount, managed_biguint!(lp_amount));
+            },
+        )
+        .assert_ok();
+    b_mock.check_esdt_balance(&user, SHARE_TOKEN_ID, &rust_biguint!(lp_amount));
+
+    // only keepers may compound
+    b_mock
+        .execute_tx(&user, &vault_wrapper, &rust_zero, |sc| {
+            let _ = sc.compound(
+                managed_biguint!(1),
+                managed_biguint!(1),
+                managed_biguint!(1),
+            );
+        })
+        .assert_user_error("Only keepers may compound");
+
+    // 10 blocks of rewards, half swapped to WEGLD and added back as liquidity
+    b_mock.set_block_nonce(10);
+    let mut compounded_lp_amount = 0;
+    b_mock
+        .execute_tx(&keeper, &vault_wrapper, &rust_zero, |sc| {
+            let lp_added = sc.compound(
+                managed_biguint!(1),
+                managed_biguint!(1),
+                managed_biguint!(1),
+            );
+            compounded_lp_amount = lp_added.to_u64().unwrap();
+        })
+        .assert_ok();
+    assert!(compounded_lp_amount > 0);
+
+    b_mock
+        .execute_query(&vault_wrapper, |sc| {
+            assert_eq!(
+                sc.get_total_lp_amount(),
+                managed_biguint!(lp_amount + compounded_lp_amount)
+            );
+            assert_eq!(sc.share_token_supply().get(), managed_biguint!(lp_amount));
+        })
+        .assert_ok();
+
+    // the partner tokens paid to the vault on compound are kept for the share holders
+    b_mock
+        .execute_query(&vault_wrapper, |sc| {
+            assert_eq!(
+                sc.extra_reward_amount(&managed_token_id!(PARTNER_TOKEN_ID))
+                    .get(),
+                managed_biguint!(999)
+            );
+        })
+        .assert_ok();
+
+    // after the farm's minimum farming epochs, the user exits with half of the position
+    // and gets half of the partner tokens
+    b_mock.set_block_epoch(10);
+    let half_shares = lp_amount / 2;
+    b_mock
+        .execute_esdt_transfer(
+            &user,
+            &vault_wrapper,
+            SHARE_TOKEN_ID,
+            0,
+            &rust_biguint!(half_shares),
+            |sc| {
+                let (_, _, extra_rewards) = sc.withdraw().into_tuple();
+                assert_eq!(extra_rewards.len(), 1);
+                assert_eq!(extra_rewards.get(0).amount, managed_biguint!(499));
+            },
+        )
+        .assert_ok();
+    b_mock.check_esdt_balance(&user, PARTNER_TOKEN_ID, &rust_biguint!(499));
+
+    // then exits with all the remaining LP tokens and partner tokens
+    b_mock
+        .execute_esdt_transfer(
+            &user,
+            &vault_wrapper,
+            SHARE_TOKEN_ID,
+            0,
+            &rust_biguint!(lp_amount - half_shares),
+            |sc| {
+                let _ = sc.withdraw();
+                assert!(sc.farm_position().is_empty());
+                assert!(sc.extra_reward_tokens().is_empty());
+            },
+        )
+        .assert_ok();
+    b_mock.check_esdt_balance(&user, PARTNER_TOKEN_ID, &rust_biguint!(999));
+
+    b_mock.check_esdt_balance(
+        &user,
+        LP_TOKEN_ID,
+        &rust_biguint!(lp_amount + compounded_lp_amount),
+    );
+    b_mock.check_esdt_balance(&user, SHARE_TOKEN_ID, &rust_zero);
+}
@@ -0,0 +1,46 @@
+// Code generated by the multiversx-sc build system. DO NOT EDIT.
+
+////////////////////////////////////////////////////
+////////////////// AUTO-GENERATED //////////////////
+////////////////////////////////////////////////////
+
+// Init:                                 1
+// Upgrade:                              1
+// Endpoints:                           20
+// Async Callback:                       1
+// Total number of exported functions:  23
+
+#![no_std]
+
+multiversx_sc_wasm_adapter::allocator!();
+multiversx_sc_wasm_adapter::panic_handler!();
+
+multiversx_sc_wasm_adapter::endpoints! {
+    farm_vault
+    (
+        init => init
+        upgrade => upgrade
+        deposit => deposit
+        withdraw => withdraw
+        getTotalLpAmount => get_total_lp_amount
+        getPairAddress => pair_address
+        getFarmAddress => farm_address
+        getLpTokenId => lp_token_id
+        getRewardTokenId => reward_token_id
+        getOtherTokenId => other_token_id
+        getFarmPosition => farm_position
+        getExtraRewardTokens => extra_reward_tokens
+        getExtraRewardAmount => extra_reward_amount
+        getLeftover => leftover
+        addKeepers => add_keepers
+        removeKeepers => remove_keepers
+        compound => compound
+        getKeepers => keepers
+        issueShareToken => issue_share_token
+        getLpAmountForShares => get_lp_amount_for_shares
+        getShareTokenId => share_token
+        getShareTokenSupply => share_token_supply
+    )
+}
+
+multiversx_sc_wasm_adapter::async_callback! { farm_vault }
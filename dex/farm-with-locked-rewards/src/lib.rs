@@ -32,6 +32,10 @@ pub trait Farm:
     + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
     + farm::base_functions::BaseFunctionsModule
     + farm::exit_penalty::ExitPenaltyModule
+    + farm::additional_rewards::AdditionalRewardsModule
+    + farm::emission_schedule::EmissionScheduleModule
+    + farm::reward_campaigns::RewardCampaignsModule
+    + farm::lock_boosts::LockBoostsModule
     + farm_base_impl::base_farm_init::BaseFarmInitModule
     + farm_base_impl::base_farm_validation::BaseFarmValidationModule
     + farm_base_impl::enter_farm::BaseEnterFarmModule
@@ -130,6 +134,7 @@ pub trait Farm:
         let claim_rewards_result = self.claim_rewards::<NoMintWrapper<Self>>(orig_caller.clone());
 
         self.send_payment_non_zero(&caller, &claim_rewards_result.new_farm_token);
+        self.send_multiple_tokens_if_not_zero(&caller, &claim_rewards_result.extra_rewards);
 
         let rewards_payment = claim_rewards_result.rewards;
         let locked_rewards_payment = self.send_to_lock_contract_non_zero(
@@ -139,7 +144,12 @@ pub trait Farm:
             orig_caller,
         );
 
-        (claim_rewards_result.new_farm_token, locked_rewards_payment).into()
+        (
+            claim_rewards_result.new_farm_token,
+            locked_rewards_payment,
+            claim_rewards_result.extra_rewards,
+        )
+            .into()
     }
 
     #[payable("*")]
@@ -161,6 +171,7 @@ pub trait Farm:
 
         let rewards = exit_farm_result.rewards;
         self.send_payment_non_zero(&caller, &exit_farm_result.farming_tokens);
+        self.send_multiple_tokens_if_not_zero(&caller, &exit_farm_result.extra_rewards);
 
         let locked_rewards_payment = self.send_to_lock_contract_non_zero(
             rewards.token_identifier.clone(),
@@ -171,7 +182,12 @@ pub trait Farm:
 
         self.clear_user_energy_if_needed(&orig_caller);
 
-        (exit_farm_result.farming_tokens, locked_rewards_payment).into()
+        (
+            exit_farm_result.farming_tokens,
+            locked_rewards_payment,
+            exit_farm_result.extra_rewards,
+        )
+            .into()
     }
 
     #[payable("*")]
@@ -272,25 +288,41 @@ pub trait Farm:
         self.boosted_yields_rewards_percentage().set(percentage);
     }
 
+    /// Returns the rewards of the given position: the farm reward token first,
+    /// followed by the position's share of the additional token and campaign rewards
     #[view(calculateRewardsForGivenPosition)]
     fn calculate_rewards_for_given_position(
         &self,
         user: ManagedAddress,
         farm_token_amount: BigUint,
         attributes: FarmTokenAttributes<Self::Api>,
-    ) -> BigUint {
+    ) -> MultiValueEncoded<EsdtTokenPayment> {
         self.require_queried();
 
         let mut storage_cache = StorageCache::new(self);
         NoMintWrapper::<Self>::generate_aggregated_rewards(self, &mut storage_cache);
 
-        NoMintWrapper::<Self>::calculate_rewards(
+        let rewards = NoMintWrapper::<Self>::calculate_rewards(
             self,
             &user,
             &farm_token_amount,
             &attributes,
             &storage_cache,
-        )
+        );
+
+        let mut result = MultiValueEncoded::new();
+        result.push(EsdtTokenPayment::new(
+            storage_cache.reward_token_id.clone(),
+            0,
+            rewards,
+        ));
+        for extra_reward in
+            &self.calculate_extra_rewards_for_given_position(&user, &farm_token_amount)
+        {
+            result.push(extra_reward);
+        }
+
+        result
     }
 
     fn send_to_lock_contract_non_zero(
@@ -331,6 +363,10 @@ where
         sc: &Self::FarmSc,
         storage_cache: &mut StorageCache<Self::FarmSc>,
     ) {
+        sc.generate_additional_rewards();
+        sc.generate_campaign_rewards();
+        sc.snapshot_reward_per_share(&storage_cache.reward_per_share);
+
         let total_reward = Self::mint_per_block_rewards(sc, &storage_cache.reward_token_id);
         if total_reward > 0u64 {
             storage_cache.reward_reserve += &total_reward;
@@ -360,6 +396,21 @@ where
         )
     }
 
+    fn increase_user_farm_position(
+        sc: &Self::FarmSc,
+        user: &ManagedAddress<<Self::FarmSc as ContractBase>::Api>,
+        increase_farm_position_amount: &BigUint<<Self::FarmSc as ContractBase>::Api>,
+    ) {
+        Wrapper::<T>::increase_user_farm_position(sc, user, increase_farm_position_amount)
+    }
+
+    fn decrease_user_farm_position(
+        sc: &Self::FarmSc,
+        farm_position: &EsdtTokenPayment<<Self::FarmSc as ContractBase>::Api>,
+    ) {
+        Wrapper::<T>::decrease_user_farm_position(sc, farm_position)
+    }
+
     fn get_exit_penalty(
         sc: &Self::FarmSc,
         total_exit_amount: &BigUint<<Self::FarmSc as ContractBase>::Api>,
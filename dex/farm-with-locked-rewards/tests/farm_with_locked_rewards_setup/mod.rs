@@ -55,6 +55,8 @@ pub struct RawFarmTokenAttributes {
     pub compounded_reward_bytes: Vec<u8>,
     pub current_farm_amount_bytes: Vec<u8>,
     pub original_owner_bytes: [u8; 32],
+    pub unlock_epoch: Epoch,
+    pub reward_boost_percent: u64,
 }
 
 pub struct FarmSetup<FarmObjBuilder, EnergyFactoryBuilder>
@@ -330,6 +332,8 @@ where
                 .as_slice()
                 .to_vec(),
             original_owner_bytes: attributes.original_owner.to_byte_array(),
+            unlock_epoch: attributes.unlock_epoch,
+            reward_boost_percent: attributes.reward_boost_percent,
         };
 
         self.b_mock
@@ -349,6 +353,8 @@ where
                         multiversx_sc::types::ManagedAddress::<DebugApi>::new_from_bytes(
                             &raw_attributes.original_owner_bytes,
                         ),
+                    unlock_epoch: raw_attributes.unlock_epoch,
+                    reward_boost_percent: raw_attributes.reward_boost_percent,
                 };
 
                 let result_managed = sc.calculate_rewards_for_given_position(
@@ -356,7 +362,7 @@ where
                     managed_biguint!(farm_token_amount),
                     attributes_managed,
                 );
-                result = result_managed.to_u64().unwrap();
+                result = result_managed.to_vec().get(0).amount.to_u64().unwrap();
             })
             .assert_ok();
 
@@ -381,7 +387,7 @@ where
                 farm_token_nonce,
                 &rust_biguint!(farm_token_amount),
                 |sc| {
-                    let (out_farm_token, out_reward_token) = sc
+                    let (out_farm_token, out_reward_token, _) = sc
                         .claim_rewards_endpoint(OptionalValue::Some(managed_address!(user)))
                         .into_tuple();
                     assert_eq!(
@@ -41,6 +41,8 @@ fn farm_with_no_boost_no_proxy_test() {
         compounded_reward: managed_biguint!(0),
         current_farm_amount: managed_biguint!(first_farm_token_amount),
         original_owner: managed_address!(&first_user),
+        unlock_epoch: 0,
+        reward_boost_percent: 0,
     };
     let first_rewards_amt =
         farm_setup.calculate_rewards(&first_user, first_farm_token_amount, first_attributes);
@@ -54,6 +56,8 @@ fn farm_with_no_boost_no_proxy_test() {
         compounded_reward: managed_biguint!(0),
         current_farm_amount: managed_biguint!(second_farm_token_amount),
         original_owner: managed_address!(&second_user),
+        unlock_epoch: 0,
+        reward_boost_percent: 0,
     };
     let second_rewards_amt =
         farm_setup.calculate_rewards(&second_user, second_farm_token_amount, second_attributes);
@@ -262,6 +266,8 @@ fn total_farm_position_claim_with_locked_rewards_test() {
             entering_epoch: 2,
             current_farm_amount: managed_biguint!(farm_in_amount),
             original_owner: managed_address!(&first_user),
+            unlock_epoch: 0,
+            reward_boost_percent: 0,
         }),
     );
 
@@ -276,6 +282,8 @@ fn total_farm_position_claim_with_locked_rewards_test() {
             entering_epoch: 2,
             current_farm_amount: managed_biguint!(farm_in_amount),
             original_owner: managed_address!(&first_user),
+            unlock_epoch: 0,
+            reward_boost_percent: 0,
         }),
     );
 
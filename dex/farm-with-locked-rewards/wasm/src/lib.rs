@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           66
+// Endpoints:                           93
 // Async Callback:                       1
-// Total number of exported functions:  69
+// Total number of exported functions:  96
 
 #![no_std]
 
@@ -65,6 +65,33 @@ multiversx_sc_wasm_adapter::endpoints! {
         getPenaltyPercent => penalty_percent
         getMinimumFarmingEpoch => minimum_farming_epochs
         getBurnGasLimit => burn_gas_limit
+        addAdditionalRewardToken => add_additional_reward_token
+        setAdditionalRewardPerBlockAmount => set_additional_reward_per_block_amount
+        depositAdditionalRewards => deposit_additional_rewards
+        getAdditionalRewardTokens => additional_reward_tokens
+        getAdditionalRewardPerBlockAmount => additional_reward_per_block_amount
+        getAdditionalRewardLastBlockNonce => additional_reward_last_block_nonce
+        getAdditionalRewardCapacity => additional_reward_capacity
+        getAdditionalRewardReserve => additional_reward_reserve
+        getAdditionalRewardPerShare => additional_reward_per_share
+        getTotalCountedFarmPosition => total_counted_farm_position
+        getUserCountedFarmPosition => user_counted_farm_position
+        getUserAdditionalRewards => user_additional_rewards
+        getEmissionSchedule => emission_schedule
+        getLastRewardTimestamp => last_reward_timestamp
+        addCampaignRewardToken => add_campaign_reward_token
+        removeCampaignRewardToken => remove_campaign_reward_token
+        setCampaignMinDeposit => set_campaign_min_deposit
+        createRewardCampaign => create_reward_campaign
+        withdrawCampaignLeftover => withdraw_campaign_leftover
+        getCampaignRewardTokens => campaign_reward_tokens
+        getCampaignMinDeposit => campaign_min_deposit
+        getLiveRewardCampaigns => live_reward_campaigns
+        getEndedRewardCampaigns => ended_reward_campaigns
+        getRewardCampaign => reward_campaign
+        getUserCampaignRewards => user_campaign_rewards
+        setLockBoostOptions => set_lock_boost_options
+        getLockBoostOptions => lock_boost_options
         getPairContractManagedAddress => pair_contract_address
         collectUndistributedBoostedRewards => collect_undistributed_boosted_rewards
         getBoostedYieldsRewardsPercentage => boosted_yields_rewards_percentage
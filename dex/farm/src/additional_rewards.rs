@@ -0,0 +1,245 @@
+multiversx_sc::imports!();
+
+use common_errors::{ERROR_BAD_INPUT_TOKEN, ERROR_PARAMETERS, ERROR_ZERO_AMOUNT};
+use common_structs::{Nonce, PaymentsVec};
+
+pub const MAX_ADDITIONAL_REWARD_TOKENS: usize = 5;
+
+/// Reward tokens distributed on top of the main farm reward token, e.g. partner tokens.
+/// They are not minted, but paid out from the amounts deposited through
+/// `depositAdditionalRewards`, each at its own per block rate.
+///
+/// Unlike the main rewards, they are tracked per user, based on the user's total farm
+/// position, so the farm token attributes stay unchanged. Only the positions counted through
+/// `sync_user_counted_farm_position` take part, so positions that never changed since the
+/// module was introduced start earning once their owner interacts with the farm.
+#[multiversx_sc::module]
+pub trait AdditionalRewardsModule:
+    config::ConfigModule
+    + farm_token::FarmTokenModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+{
+    #[endpoint(addAdditionalRewardToken)]
+    fn add_additional_reward_token(&self, token_id: TokenIdentifier, per_block_amount: BigUint) {
+        self.require_caller_has_admin_permissions();
+        require!(token_id.is_valid_esdt_identifier(), ERROR_BAD_INPUT_TOKEN);
+        require!(
+            token_id != self.reward_token_id().get() && token_id != self.farming_token_id().get(),
+            ERROR_BAD_INPUT_TOKEN
+        );
+
+        let mut tokens_mapper = self.additional_reward_tokens();
+        require!(
+            tokens_mapper.len() < MAX_ADDITIONAL_REWARD_TOKENS,
+            ERROR_PARAMETERS
+        );
+        require!(tokens_mapper.insert(token_id.clone()), ERROR_PARAMETERS);
+
+        let current_block_nonce = self.blockchain().get_block_nonce();
+        self.additional_reward_last_block_nonce(&token_id)
+            .set(current_block_nonce);
+        self.additional_reward_per_block_amount(&token_id)
+            .set(per_block_amount);
+    }
+
+    #[endpoint(setAdditionalRewardPerBlockAmount)]
+    fn set_additional_reward_per_block_amount(
+        &self,
+        token_id: TokenIdentifier,
+        per_block_amount: BigUint,
+    ) {
+        self.require_caller_has_admin_permissions();
+        require!(
+            self.additional_reward_tokens().contains(&token_id),
+            ERROR_BAD_INPUT_TOKEN
+        );
+
+        self.generate_additional_rewards();
+
+        self.additional_reward_per_block_amount(&token_id)
+            .set(per_block_amount);
+    }
+
+    /// Funds the distribution of one of the additional reward tokens. Anyone can deposit.
+    #[payable("*")]
+    #[endpoint(depositAdditionalRewards)]
+    fn deposit_additional_rewards(&self) {
+        let payment = self.call_value().single_esdt();
+        require!(
+            self.additional_reward_tokens()
+                .contains(&payment.token_identifier)
+                && payment.token_nonce == 0,
+            ERROR_BAD_INPUT_TOKEN
+        );
+        require!(payment.amount > 0, ERROR_ZERO_AMOUNT);
+
+        self.generate_additional_rewards();
+
+        self.additional_reward_capacity(&payment.token_identifier)
+            .update(|capacity| *capacity += payment.amount);
+    }
+
+    /// Distributes the additional rewards produced since the last update. Rewards are only
+    /// produced while there are counted farm positions, and never exceed the deposited amounts.
+    /// Calling it more than once in the same block has no further effect.
+    fn generate_additional_rewards(&self) {
+        let total_counted_farm_position = self.total_counted_farm_position().get();
+        let division_safety_constant = self.division_safety_constant().get();
+        let current_block_nonce = self.blockchain().get_block_nonce();
+        for token_id in self.additional_reward_tokens().iter() {
+            let last_block_nonce_mapper = self.additional_reward_last_block_nonce(&token_id);
+            let last_block_nonce = last_block_nonce_mapper.get();
+            if current_block_nonce <= last_block_nonce {
+                continue;
+            }
+
+            last_block_nonce_mapper.set(current_block_nonce);
+            if total_counted_farm_position == 0 {
+                continue;
+            }
+
+            let capacity_mapper = self.additional_reward_capacity(&token_id);
+            let capacity = capacity_mapper.get();
+            let per_block_amount = self.additional_reward_per_block_amount(&token_id).get();
+            let mut total_reward = per_block_amount * (current_block_nonce - last_block_nonce);
+            if total_reward > capacity {
+                total_reward = capacity.clone();
+            }
+            if total_reward == 0 {
+                continue;
+            }
+
+            capacity_mapper.set(capacity - &total_reward);
+            self.additional_reward_reserve(&token_id)
+                .update(|reserve| *reserve += &total_reward);
+            self.additional_reward_per_share(&token_id).update(|rps| {
+                *rps += total_reward * &division_safety_constant / &total_counted_farm_position
+            });
+        }
+    }
+
+    /// Accrues the user's additional rewards for their counted farm position.
+    /// Must be called before every change of the user's total farm position.
+    fn update_user_additional_rewards(&self, user: &ManagedAddress) {
+        let tokens_mapper = self.additional_reward_tokens();
+        if tokens_mapper.is_empty() {
+            return;
+        }
+
+        self.generate_additional_rewards();
+
+        let user_farm_position = self.user_counted_farm_position(user).get();
+        let division_safety_constant = self.division_safety_constant().get();
+        for token_id in tokens_mapper.iter() {
+            let reward_per_share = self.additional_reward_per_share(&token_id).get();
+            let user_rps_mapper = self.user_additional_reward_per_share(user, &token_id);
+            let user_reward_per_share = user_rps_mapper.get();
+            if reward_per_share <= user_reward_per_share {
+                continue;
+            }
+
+            if user_farm_position > 0 {
+                let reward = &user_farm_position * &(&reward_per_share - &user_reward_per_share)
+                    / &division_safety_constant;
+                self.user_additional_rewards(user, &token_id)
+                    .update(|rewards| *rewards += reward);
+            }
+
+            user_rps_mapper.set(reward_per_share);
+        }
+    }
+
+    /// Counts the user's current total farm position for the additional token and campaign rewards.
+    /// Must be called after every change of the user's total farm position.
+    fn sync_user_counted_farm_position(&self, user: &ManagedAddress) {
+        let user_farm_position = self.user_total_farm_position(user).get();
+        let counted_position_mapper = self.user_counted_farm_position(user);
+        let counted_position = counted_position_mapper.get();
+        if user_farm_position == counted_position {
+            return;
+        }
+
+        self.total_counted_farm_position().update(|total| {
+            *total -= &counted_position;
+            *total += &user_farm_position;
+        });
+        counted_position_mapper.set(user_farm_position);
+    }
+
+    /// Accrues and takes out all the user's additional rewards, without sending them
+    fn claim_additional_rewards(&self, user: &ManagedAddress) -> PaymentsVec<Self::Api> {
+        self.update_user_additional_rewards(user);
+
+        let mut payments = PaymentsVec::new();
+        for token_id in self.additional_reward_tokens().iter() {
+            let reward = self.user_additional_rewards(user, &token_id).take();
+            if reward == 0 {
+                continue;
+            }
+
+            self.additional_reward_reserve(&token_id)
+                .update(|reserve| *reserve -= &reward);
+            payments.push(EsdtTokenPayment::new(token_id, 0, reward));
+        }
+
+        payments
+    }
+
+    #[view(getAdditionalRewardTokens)]
+    #[storage_mapper("additional_reward_tokens")]
+    fn additional_reward_tokens(&self) -> UnorderedSetMapper<TokenIdentifier>;
+
+    #[view(getAdditionalRewardPerBlockAmount)]
+    #[storage_mapper("additional_reward_per_block_amount")]
+    fn additional_reward_per_block_amount(
+        &self,
+        token_id: &TokenIdentifier,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getAdditionalRewardLastBlockNonce)]
+    #[storage_mapper("additional_reward_last_block_nonce")]
+    fn additional_reward_last_block_nonce(
+        &self,
+        token_id: &TokenIdentifier,
+    ) -> SingleValueMapper<Nonce>;
+
+    /// Deposited amount not yet distributed
+    #[view(getAdditionalRewardCapacity)]
+    #[storage_mapper("additional_reward_capacity")]
+    fn additional_reward_capacity(&self, token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    /// Distributed amount not yet claimed
+    #[view(getAdditionalRewardReserve)]
+    #[storage_mapper("additional_reward_reserve")]
+    fn additional_reward_reserve(&self, token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    #[view(getAdditionalRewardPerShare)]
+    #[storage_mapper("additional_reward_per_share")]
+    fn additional_reward_per_share(&self, token_id: &TokenIdentifier)
+        -> SingleValueMapper<BigUint>;
+
+    #[view(getTotalCountedFarmPosition)]
+    #[storage_mapper("total_counted_farm_position")]
+    fn total_counted_farm_position(&self) -> SingleValueMapper<BigUint>;
+
+    #[view(getUserCountedFarmPosition)]
+    #[storage_mapper("user_counted_farm_position")]
+    fn user_counted_farm_position(&self, user: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("user_additional_reward_per_share")]
+    fn user_additional_reward_per_share(
+        &self,
+        user: &ManagedAddress,
+        token_id: &TokenIdentifier,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getUserAdditionalRewards)]
+    #[storage_mapper("user_additional_rewards")]
+    fn user_additional_rewards(
+        &self,
+        user: &ManagedAddress,
+        token_id: &TokenIdentifier,
+    ) -> SingleValueMapper<BigUint>;
+}
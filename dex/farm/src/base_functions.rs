@@ -7,38 +7,47 @@ multiversx_sc::derive_imports!();
 use core::marker::PhantomData;
 
 use common_errors::ERROR_ZERO_AMOUNT;
-use common_structs::FarmTokenAttributes;
+use common_structs::{Epoch, FarmTokenAttributes, PaymentsVec};
 use contexts::storage_cache::StorageCache;
 
 use farm_base_impl::base_traits_impl::{DefaultFarmWrapper, FarmContract};
 
-use crate::{exit_penalty, MAX_PERCENT};
+use crate::{
+    additional_rewards,
+    emission_schedule::{self, EmissionSegment},
+    exit_penalty, lock_boosts, reward_campaigns, MAX_PERCENT,
+};
 
 pub type DoubleMultiPayment<M> = MultiValue2<EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
-pub type ClaimRewardsResultType<M> = DoubleMultiPayment<M>;
-pub type ExitFarmResultType<M> = DoubleMultiPayment<M>;
+/// The main payments, followed by the additional token and campaign rewards
+pub type ClaimRewardsResultType<M> =
+    MultiValue3<EsdtTokenPayment<M>, EsdtTokenPayment<M>, PaymentsVec<M>>;
+pub type ExitFarmResultType<M> =
+    MultiValue3<EsdtTokenPayment<M>, EsdtTokenPayment<M>, PaymentsVec<M>>;
 
 pub const DEFAULT_FARM_POSITION_MIGRATION_NONCE: u64 = 1;
 
 pub struct ClaimRewardsResultWrapper<M: ManagedTypeApi> {
     pub new_farm_token: EsdtTokenPayment<M>,
     pub rewards: EsdtTokenPayment<M>,
+    pub extra_rewards: PaymentsVec<M>,
 }
 
 pub struct ExitFarmResultWrapper<M: ManagedTypeApi> {
     pub farming_tokens: EsdtTokenPayment<M>,
     pub rewards: EsdtTokenPayment<M>,
+    pub extra_rewards: PaymentsVec<M>,
 }
 
 impl<M: ManagedTypeApi> Into<ClaimRewardsResultType<M>> for ClaimRewardsResultWrapper<M> {
     fn into(self) -> ClaimRewardsResultType<M> {
-        (self.new_farm_token, self.rewards).into()
+        (self.new_farm_token, self.rewards, self.extra_rewards).into()
     }
 }
 
 impl<M: ManagedTypeApi> Into<ExitFarmResultType<M>> for ExitFarmResultWrapper<M> {
     fn into(self) -> ExitFarmResultType<M> {
-        (self.farming_tokens, self.rewards).into()
+        (self.farming_tokens, self.rewards, self.extra_rewards).into()
     }
 }
 
@@ -53,6 +62,10 @@ pub trait BaseFunctionsModule:
     + events::EventsModule
     + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
     + exit_penalty::ExitPenaltyModule
+    + additional_rewards::AdditionalRewardsModule
+    + emission_schedule::EmissionScheduleModule
+    + reward_campaigns::RewardCampaignsModule
+    + lock_boosts::LockBoostsModule
     + farm_base_impl::base_farm_init::BaseFarmInitModule
     + farm_base_impl::base_farm_validation::BaseFarmValidationModule
     + farm_base_impl::enter_farm::BaseEnterFarmModule
@@ -73,9 +86,29 @@ pub trait BaseFunctionsModule:
     fn enter_farm<FC: FarmContract<FarmSc = Self>>(
         &self,
         caller: ManagedAddress,
+    ) -> EsdtTokenPayment {
+        self.enter_farm_with_lock::<FC>(caller, None)
+    }
+
+    /// The new position is locked for `opt_lock_epochs`, if given,
+    /// which must be one of the lock boost options
+    fn enter_farm_with_lock<FC: FarmContract<FarmSc = Self>>(
+        &self,
+        caller: ManagedAddress,
+        opt_lock_epochs: Option<Epoch>,
     ) -> EsdtTokenPayment {
         let payments = self.call_value().all_esdt_transfers().clone_value();
-        let base_enter_farm_result = self.enter_farm_base::<FC>(caller.clone(), payments);
+        let base_enter_farm_result =
+            self.enter_farm_base_with_attributes::<FC, _>(caller.clone(), payments, |attributes| {
+                let lock_epochs = match opt_lock_epochs {
+                    Some(lock_epochs) => lock_epochs,
+                    None => return attributes,
+                };
+
+                let mut attributes: FarmTokenAttributes<Self::Api> = attributes.into();
+                self.lock_farm_position(&mut attributes, lock_epochs);
+                attributes.into()
+            });
 
         self.set_farm_supply_for_current_week(
             &base_enter_farm_result.storage_cache.farm_token_supply,
@@ -115,9 +148,12 @@ pub trait BaseFunctionsModule:
             base_claim_rewards_result.storage_cache,
         );
 
+        let extra_rewards = self.claim_extra_rewards(&caller);
+
         ClaimRewardsResultWrapper {
             new_farm_token: output_farm_token_payment,
             rewards: rewards_payment,
+            extra_rewards,
         }
     }
 
@@ -153,6 +189,13 @@ pub trait BaseFunctionsModule:
         payment: EsdtTokenPayment,
     ) -> ExitFarmResultWrapper<Self::Api> {
         let base_exit_farm_result = self.exit_farm_base::<FC>(caller.clone(), payment);
+        let exited_attributes: FarmTokenAttributes<Self::Api> = base_exit_farm_result
+            .context
+            .farm_token
+            .attributes
+            .clone()
+            .into();
+        self.require_farm_position_unlocked(&exited_attributes);
 
         let mut farming_token_payment = base_exit_farm_result.farming_token_payment;
         let reward_payment = base_exit_farm_result.reward_payment;
@@ -176,9 +219,12 @@ pub trait BaseFunctionsModule:
             base_exit_farm_result.storage_cache,
         );
 
+        let extra_rewards = self.claim_extra_rewards(&caller);
+
         ExitFarmResultWrapper {
             farming_tokens: farming_token_payment,
             rewards: reward_payment,
+            extra_rewards,
         }
     }
 
@@ -218,8 +264,10 @@ pub trait BaseFunctionsModule:
         }
 
         if migrated_amount > 0 {
+            self.update_user_extra_rewards(caller);
             self.user_total_farm_position(caller)
                 .update(|total_farm_position| *total_farm_position += &migrated_amount);
+            self.sync_user_counted_farm_position(caller);
         }
 
         migrated_amount
@@ -230,6 +278,8 @@ pub trait BaseFunctionsModule:
             return;
         }
 
+        self.update_user_extra_rewards(caller);
+
         let user_total_farm_position_mapper = self.user_total_farm_position(caller);
         let mut user_total_farm_position = user_total_farm_position_mapper.get();
 
@@ -239,6 +289,8 @@ pub trait BaseFunctionsModule:
         } else {
             user_total_farm_position_mapper.clear();
         }
+
+        self.sync_user_counted_farm_position(caller);
     }
 
     fn end_produce_rewards<FC: FarmContract<FarmSc = Self>>(&self) {
@@ -257,6 +309,75 @@ pub trait BaseFunctionsModule:
         self.per_block_reward_amount().set(&per_block_amount);
     }
 
+    /// Passing `None` clears the schedule, so the per block reward amount is used again
+    fn replace_emission_schedule<FC: FarmContract<FarmSc = Self>>(
+        &self,
+        schedule_opt: Option<ManagedVec<EmissionSegment<Self::Api>>>,
+    ) {
+        let mut storage = StorageCache::new(self);
+        FC::generate_aggregated_rewards(self, &mut storage);
+
+        match schedule_opt {
+            Some(schedule) => self.set_emission_schedule(schedule),
+            None => self.emission_schedule().clear(),
+        }
+    }
+
+    /// Accrues the user's additional token and campaign rewards
+    fn update_user_extra_rewards(&self, user: &ManagedAddress) {
+        self.update_user_additional_rewards(user);
+        self.update_user_campaign_rewards(user);
+    }
+
+    /// Accrues and takes out the user's additional token and campaign rewards, without sending them
+    fn claim_extra_rewards(&self, user: &ManagedAddress) -> PaymentsVec<Self::Api> {
+        let mut extra_rewards = self.claim_additional_rewards(user);
+        extra_rewards.append_vec(self.claim_campaign_rewards(user));
+
+        extra_rewards
+    }
+
+    /// Returns the share of the user's pending additional token and campaign rewards
+    /// that corresponds to the given amount of farm tokens, out of the user's total farm position.
+    /// Extra rewards are accrued per user, so claiming any position pays out all of them.
+    fn calculate_extra_rewards_for_given_position(
+        &self,
+        user: &ManagedAddress,
+        farm_token_amount: &BigUint,
+    ) -> PaymentsVec<Self::Api> {
+        self.update_user_extra_rewards(user);
+
+        let user_farm_position = self.user_total_farm_position(user).get();
+        let position_share = |user_reward: BigUint| {
+            if farm_token_amount >= &user_farm_position {
+                user_reward
+            } else {
+                user_reward * farm_token_amount / &user_farm_position
+            }
+        };
+
+        let mut extra_rewards = PaymentsVec::new();
+        for token_id in self.additional_reward_tokens().iter() {
+            let user_reward = self.user_additional_rewards(user, &token_id).get();
+            extra_rewards.push(EsdtTokenPayment::new(
+                token_id,
+                0,
+                position_share(user_reward),
+            ));
+        }
+        for campaign_id in &self.get_claimable_reward_campaigns() {
+            let token_id = self.reward_campaign(campaign_id).get().token_id;
+            let user_reward = self.user_campaign_rewards(user, campaign_id).get();
+            extra_rewards.push(EsdtTokenPayment::new(
+                token_id,
+                0,
+                position_share(user_reward),
+            ));
+        }
+
+        extra_rewards
+    }
+
     fn require_queried(&self) {
         let caller = self.blockchain().get_caller();
         let sc_address = self.blockchain().get_sc_address();
@@ -300,10 +421,30 @@ where
     type FarmSc = T;
     type AttributesType = FarmTokenAttributes<<Self::FarmSc as ContractBase>::Api>;
 
+    fn mint_per_block_rewards(
+        sc: &Self::FarmSc,
+        token_id: &TokenIdentifier<<Self::FarmSc as ContractBase>::Api>,
+    ) -> BigUint<<Self::FarmSc as ContractBase>::Api> {
+        if sc.emission_schedule().is_empty() {
+            return DefaultFarmWrapper::<T>::mint_per_block_rewards(sc, token_id);
+        }
+
+        let to_mint = sc.take_scheduled_rewards();
+        if to_mint != 0 {
+            Self::mint_rewards(sc, token_id, &to_mint);
+        }
+
+        to_mint
+    }
+
     fn generate_aggregated_rewards(
         sc: &Self::FarmSc,
         storage_cache: &mut StorageCache<Self::FarmSc>,
     ) {
+        sc.generate_additional_rewards();
+        sc.generate_campaign_rewards();
+        sc.snapshot_reward_per_share(&storage_cache.reward_per_share);
+
         let total_reward = Self::mint_per_block_rewards(sc, &storage_cache.reward_token_id);
         if total_reward > 0u64 {
             storage_cache.reward_reserve += &total_reward;
@@ -331,9 +472,67 @@ where
             token_attributes,
             storage_cache,
         );
+        let lock_boost_rewards = sc.get_lock_boost_rewards(
+            farm_token_amount,
+            token_attributes,
+            &storage_cache.reward_per_share,
+            &storage_cache.division_safety_constant,
+        );
         let boosted_yield_rewards = Self::calculate_boosted_rewards(sc, caller);
 
-        base_farm_reward + boosted_yield_rewards
+        base_farm_reward + lock_boost_rewards + boosted_yield_rewards
+    }
+
+    /// The lock boost is paid on top of the aggregated rewards, so it is minted separately.
+    /// It is at most `MAX_REWARD_BOOST_PERCENT` of the base farm rewards.
+    fn mint_extra_rewards(
+        sc: &Self::FarmSc,
+        farm_token_amount: &BigUint<<Self::FarmSc as ContractBase>::Api>,
+        token_attributes: &Self::AttributesType,
+        storage_cache: &mut StorageCache<Self::FarmSc>,
+    ) {
+        let lock_boost_rewards = sc.get_lock_boost_rewards(
+            farm_token_amount,
+            token_attributes,
+            &storage_cache.reward_per_share,
+            &storage_cache.division_safety_constant,
+        );
+        if lock_boost_rewards > 0 {
+            Self::mint_rewards(sc, &storage_cache.reward_token_id, &lock_boost_rewards);
+            storage_cache.reward_reserve += lock_boost_rewards;
+        }
+    }
+
+    fn increase_user_farm_position(
+        sc: &Self::FarmSc,
+        user: &ManagedAddress<<Self::FarmSc as ContractBase>::Api>,
+        increase_farm_position_amount: &BigUint<<Self::FarmSc as ContractBase>::Api>,
+    ) {
+        sc.update_user_extra_rewards(user);
+
+        DefaultFarmWrapper::<T>::increase_user_farm_position(
+            sc,
+            user,
+            increase_farm_position_amount,
+        );
+        sc.sync_user_counted_farm_position(user);
+    }
+
+    fn decrease_user_farm_position(
+        sc: &Self::FarmSc,
+        farm_position: &EsdtTokenPayment<<Self::FarmSc as ContractBase>::Api>,
+    ) {
+        if sc.is_old_farm_position(farm_position.token_nonce) {
+            return;
+        }
+
+        let token_attributes: FarmTokenAttributes<<Self::FarmSc as ContractBase>::Api> = sc
+            .farm_token()
+            .get_token_attributes(farm_position.token_nonce);
+        sc.update_user_extra_rewards(&token_attributes.original_owner);
+
+        DefaultFarmWrapper::<T>::decrease_user_farm_position(sc, farm_position);
+        sc.sync_user_counted_farm_position(&token_attributes.original_owner);
     }
 
     fn get_exit_penalty(
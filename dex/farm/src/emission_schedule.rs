@@ -0,0 +1,167 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use common_errors::ERROR_PARAMETERS;
+
+pub const MAX_EMISSION_SEGMENTS: usize = 20;
+
+#[derive(
+    TypeAbi,
+    TopEncode,
+    TopDecode,
+    NestedEncode,
+    NestedDecode,
+    ManagedVecItem,
+    Clone,
+    PartialEq,
+    Debug,
+)]
+pub struct EmissionSegment<M: ManagedTypeApi> {
+    pub start_timestamp: u64,
+    pub reward_per_second: BigUint<M>,
+}
+
+/// Total rewards emitted by the schedule between the two timestamps.
+/// Each segment lasts until the next one starts, the last one never ends.
+pub fn compute_scheduled_emission<M: ManagedTypeApi>(
+    schedule: &ManagedVec<M, EmissionSegment<M>>,
+    from_timestamp: u64,
+    to_timestamp: u64,
+) -> BigUint<M> {
+    let mut total = BigUint::zero();
+    let nr_segments = schedule.len();
+    for i in 0..nr_segments {
+        let segment = schedule.get(i);
+        let segment_end = if i + 1 < nr_segments {
+            schedule.get(i + 1).start_timestamp
+        } else {
+            u64::MAX
+        };
+
+        let start = core::cmp::max(segment.start_timestamp, from_timestamp);
+        let end = core::cmp::min(segment_end, to_timestamp);
+        if end > start {
+            total += segment.reward_per_second * (end - start);
+        }
+    }
+
+    total
+}
+
+/// While an emission schedule is set, the farm rewards follow it instead of
+/// the per block reward amount. The schedule is evaluated lazily, whenever
+/// the rewards are aggregated.
+#[multiversx_sc::module]
+pub trait EmissionScheduleModule:
+    config::ConfigModule + permissions_module::PermissionsModule + pausable::PausableModule
+{
+    fn build_emission_schedule(
+        &self,
+        segments: MultiValueEncoded<MultiValue2<u64, BigUint>>,
+    ) -> ManagedVec<EmissionSegment<Self::Api>> {
+        require!(
+            !segments.is_empty() && segments.len() <= MAX_EMISSION_SEGMENTS,
+            ERROR_PARAMETERS
+        );
+
+        let mut schedule = ManagedVec::new();
+        let mut last_start_timestamp_opt = None;
+        for segment in segments {
+            let (start_timestamp, reward_per_second) = segment.into_tuple();
+            if let Some(last_start_timestamp) = last_start_timestamp_opt {
+                require!(start_timestamp > last_start_timestamp, ERROR_PARAMETERS);
+            }
+
+            last_start_timestamp_opt = Some(start_timestamp);
+            schedule.push(EmissionSegment {
+                start_timestamp,
+                reward_per_second,
+            });
+        }
+
+        schedule
+    }
+
+    /// The rate halves every `halving_period` seconds, `nr_halvings` times,
+    /// then stays at the last value
+    fn build_halving_emission_schedule(
+        &self,
+        start_timestamp: u64,
+        initial_reward_per_second: BigUint,
+        halving_period: u64,
+        nr_halvings: usize,
+    ) -> ManagedVec<EmissionSegment<Self::Api>> {
+        require!(
+            halving_period > 0 && nr_halvings < MAX_EMISSION_SEGMENTS,
+            ERROR_PARAMETERS
+        );
+
+        let mut schedule = ManagedVec::new();
+        let mut segment = EmissionSegment {
+            start_timestamp,
+            reward_per_second: initial_reward_per_second,
+        };
+        for _ in 0..nr_halvings {
+            let next_segment = EmissionSegment {
+                start_timestamp: segment.start_timestamp + halving_period,
+                reward_per_second: &segment.reward_per_second / 2u32,
+            };
+            schedule.push(segment);
+            segment = next_segment;
+        }
+        schedule.push(segment);
+
+        schedule
+    }
+
+    /// Must be called after the rewards were aggregated with the previous settings
+    fn set_emission_schedule(&self, schedule: ManagedVec<EmissionSegment<Self::Api>>) {
+        self.last_reward_timestamp()
+            .set(self.blockchain().get_block_timestamp());
+        self.emission_schedule().set(schedule);
+    }
+
+    fn start_produce_scheduled_rewards(&self) {
+        require!(
+            !self.produce_rewards_enabled().get(),
+            "Producing rewards is already enabled"
+        );
+
+        self.produce_rewards_enabled().set(true);
+        self.last_reward_block_nonce()
+            .set(self.blockchain().get_block_nonce());
+        self.last_reward_timestamp()
+            .set(self.blockchain().get_block_timestamp());
+    }
+
+    /// Returns the rewards emitted by the schedule since the last call
+    fn take_scheduled_rewards(&self) -> BigUint {
+        self.last_reward_block_nonce()
+            .set(self.blockchain().get_block_nonce());
+
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        let last_reward_timestamp = self.last_reward_timestamp().get();
+        if current_timestamp <= last_reward_timestamp {
+            return BigUint::zero();
+        }
+
+        self.last_reward_timestamp().set(current_timestamp);
+        if !self.produce_rewards_enabled().get() {
+            return BigUint::zero();
+        }
+
+        compute_scheduled_emission(
+            &self.emission_schedule().get(),
+            last_reward_timestamp,
+            current_timestamp,
+        )
+    }
+
+    #[view(getEmissionSchedule)]
+    #[storage_mapper("emission_schedule")]
+    fn emission_schedule(&self) -> SingleValueMapper<ManagedVec<EmissionSegment<Self::Api>>>;
+
+    #[view(getLastRewardTimestamp)]
+    #[storage_mapper("last_reward_timestamp")]
+    fn last_reward_timestamp(&self) -> SingleValueMapper<u64>;
+}
@@ -3,11 +3,15 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
+pub mod additional_rewards;
 pub mod base_functions;
+pub mod emission_schedule;
 pub mod exit_penalty;
+pub mod lock_boosts;
+pub mod reward_campaigns;
 
 use base_functions::{ClaimRewardsResultType, DoubleMultiPayment, Wrapper};
-use common_structs::FarmTokenAttributes;
+use common_structs::{Epoch, FarmTokenAttributes};
 use contexts::storage_cache::StorageCache;
 
 use exit_penalty::{
@@ -17,7 +21,7 @@ use farm_base_impl::base_traits_impl::FarmContract;
 use fixed_supply_token::FixedSupplyToken;
 
 pub type EnterFarmResultType<M> = DoubleMultiPayment<M>;
-pub type ExitFarmWithPartialPosResultType<M> = DoubleMultiPayment<M>;
+pub type ExitFarmWithPartialPosResultType<M> = base_functions::ExitFarmResultType<M>;
 
 pub const MAX_PERCENT: u64 = 10_000;
 
@@ -34,6 +38,10 @@ pub trait Farm:
     + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
     + base_functions::BaseFunctionsModule
     + exit_penalty::ExitPenaltyModule
+    + additional_rewards::AdditionalRewardsModule
+    + emission_schedule::EmissionScheduleModule
+    + reward_campaigns::RewardCampaignsModule
+    + lock_boosts::LockBoostsModule
     + farm_base_impl::base_farm_init::BaseFarmInitModule
     + farm_base_impl::base_farm_validation::BaseFarmValidationModule
     + farm_base_impl::enter_farm::BaseEnterFarmModule
@@ -99,21 +107,22 @@ pub trait Farm:
         &self,
         opt_orig_caller: OptionalValue<ManagedAddress>,
     ) -> EnterFarmResultType<Self::Api> {
-        let caller = self.blockchain().get_caller();
-        let orig_caller = self.get_orig_caller_from_opt(&caller, opt_orig_caller);
-
-        self.migrate_old_farm_positions(&orig_caller);
-        let boosted_rewards = self.claim_only_boosted_payment(&orig_caller);
-        let boosted_rewards_payment =
-            EsdtTokenPayment::new(self.reward_token_id().get(), 0, boosted_rewards);
-
-        let new_farm_token = self.enter_farm::<Wrapper<Self>>(orig_caller.clone());
-        self.send_payment_non_zero(&caller, &new_farm_token);
-        self.send_payment_non_zero(&caller, &boosted_rewards_payment);
-
-        self.update_energy_and_progress(&orig_caller);
+        self.enter_farm_and_send(opt_orig_caller, None)
+    }
 
-        (new_farm_token, boosted_rewards_payment).into()
+    /// Same as `enterFarm`, but the new position is locked for `lock_epochs`,
+    /// which must be one of the options in `getLockBoostOptions`,
+    /// in exchange for the corresponding reward boost.
+    /// Additional farm tokens sent along are merged into the locked position,
+    /// so they must have the same lock, i.e. be locked for the same option in the same epoch.
+    #[payable("*")]
+    #[endpoint(enterFarmLocked)]
+    fn enter_farm_locked_endpoint(
+        &self,
+        lock_epochs: Epoch,
+        opt_orig_caller: OptionalValue<ManagedAddress>,
+    ) -> EnterFarmResultType<Self::Api> {
+        self.enter_farm_and_send(opt_orig_caller, Some(lock_epochs))
     }
 
     #[payable("*")]
@@ -127,10 +136,11 @@ pub trait Farm:
 
         self.migrate_old_farm_positions(&orig_caller);
 
-        let claim_rewards_result = self.claim_rewards::<Wrapper<Self>>(orig_caller);
+        let claim_rewards_result = self.claim_rewards::<Wrapper<Self>>(orig_caller.clone());
 
         self.send_payment_non_zero(&caller, &claim_rewards_result.new_farm_token);
         self.send_payment_non_zero(&caller, &claim_rewards_result.rewards);
+        self.send_multiple_tokens_if_not_zero(&caller, &claim_rewards_result.extra_rewards);
 
         claim_rewards_result.into()
     }
@@ -174,10 +184,11 @@ pub trait Farm:
 
         self.send_payment_non_zero(&caller, &exit_farm_result.farming_tokens);
         self.send_payment_non_zero(&caller, &exit_farm_result.rewards);
+        self.send_multiple_tokens_if_not_zero(&caller, &exit_farm_result.extra_rewards);
 
         self.clear_user_energy_if_needed(&orig_caller);
 
-        (exit_farm_result.farming_tokens, exit_farm_result.rewards).into()
+        exit_farm_result.into()
     }
 
     #[payable("*")]
@@ -246,7 +257,11 @@ pub trait Farm:
     #[endpoint(startProduceRewards)]
     fn start_produce_rewards_endpoint(&self) {
         self.require_caller_has_admin_permissions();
-        self.start_produce_rewards();
+        if self.emission_schedule().is_empty() {
+            self.start_produce_rewards();
+        } else {
+            self.start_produce_scheduled_rewards();
+        }
     }
 
     #[endpoint(endProduceRewards)]
@@ -261,6 +276,42 @@ pub trait Farm:
         self.set_per_block_rewards::<Wrapper<Self>>(per_block_amount);
     }
 
+    /// Sets the emission schedule, as pairs of (start_timestamp, reward_per_second),
+    /// sorted by start_timestamp. While set, it replaces the per block reward amount.
+    #[endpoint(setEmissionSchedule)]
+    fn set_emission_schedule_endpoint(
+        &self,
+        segments: MultiValueEncoded<MultiValue2<u64, BigUint>>,
+    ) {
+        self.require_caller_has_admin_permissions();
+        let schedule = self.build_emission_schedule(segments);
+        self.replace_emission_schedule::<Wrapper<Self>>(Some(schedule));
+    }
+
+    #[endpoint(setHalvingEmissionSchedule)]
+    fn set_halving_emission_schedule_endpoint(
+        &self,
+        start_timestamp: u64,
+        initial_reward_per_second: BigUint,
+        halving_period: u64,
+        nr_halvings: usize,
+    ) {
+        self.require_caller_has_admin_permissions();
+        let schedule = self.build_halving_emission_schedule(
+            start_timestamp,
+            initial_reward_per_second,
+            halving_period,
+            nr_halvings,
+        );
+        self.replace_emission_schedule::<Wrapper<Self>>(Some(schedule));
+    }
+
+    #[endpoint(clearEmissionSchedule)]
+    fn clear_emission_schedule_endpoint(&self) {
+        self.require_caller_has_admin_permissions();
+        self.replace_emission_schedule::<Wrapper<Self>>(None);
+    }
+
     #[endpoint(setBoostedYieldsRewardsPercentage)]
     fn set_boosted_yields_rewards_percentage(&self, percentage: u64) {
         self.require_caller_has_admin_permissions();
@@ -273,24 +324,63 @@ pub trait Farm:
         self.boosted_yields_rewards_percentage().set(percentage);
     }
 
+    /// Returns the rewards of the given position: the farm reward token first,
+    /// followed by the position's share of the additional token and campaign rewards
     #[view(calculateRewardsForGivenPosition)]
     fn calculate_rewards_for_given_position(
         &self,
         user: ManagedAddress,
         farm_token_amount: BigUint,
         attributes: FarmTokenAttributes<Self::Api>,
-    ) -> BigUint {
+    ) -> MultiValueEncoded<EsdtTokenPayment> {
         self.require_queried();
 
         let mut storage_cache = StorageCache::new(self);
         Wrapper::<Self>::generate_aggregated_rewards(self, &mut storage_cache);
 
-        Wrapper::<Self>::calculate_rewards(
+        let rewards = Wrapper::<Self>::calculate_rewards(
             self,
             &user,
             &farm_token_amount,
             &attributes,
             &storage_cache,
-        )
+        );
+
+        let mut result = MultiValueEncoded::new();
+        result.push(EsdtTokenPayment::new(
+            storage_cache.reward_token_id.clone(),
+            0,
+            rewards,
+        ));
+        for extra_reward in
+            &self.calculate_extra_rewards_for_given_position(&user, &farm_token_amount)
+        {
+            result.push(extra_reward);
+        }
+
+        result
+    }
+
+    fn enter_farm_and_send(
+        &self,
+        opt_orig_caller: OptionalValue<ManagedAddress>,
+        opt_lock_epochs: Option<Epoch>,
+    ) -> EnterFarmResultType<Self::Api> {
+        let caller = self.blockchain().get_caller();
+        let orig_caller = self.get_orig_caller_from_opt(&caller, opt_orig_caller);
+
+        self.migrate_old_farm_positions(&orig_caller);
+        let boosted_rewards = self.claim_only_boosted_payment(&orig_caller);
+        let boosted_rewards_payment =
+            EsdtTokenPayment::new(self.reward_token_id().get(), 0, boosted_rewards);
+
+        let new_farm_token =
+            self.enter_farm_with_lock::<Wrapper<Self>>(orig_caller.clone(), opt_lock_epochs);
+        self.send_payment_non_zero(&caller, &new_farm_token);
+        self.send_payment_non_zero(&caller, &boosted_rewards_payment);
+
+        self.update_energy_and_progress(&orig_caller);
+
+        (new_farm_token, boosted_rewards_payment).into()
     }
 }
@@ -0,0 +1,171 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use common_errors::ERROR_PARAMETERS;
+use common_structs::{Epoch, FarmTokenAttributes, Percent};
+
+use crate::MAX_PERCENT;
+
+pub const MAX_LOCK_BOOST_OPTIONS: usize = 10;
+/// The boosts are minted on top of the farm's emission, so this caps the extra supply
+/// at half of the base farm rewards
+pub const MAX_REWARD_BOOST_PERCENT: Percent = MAX_PERCENT / 2;
+
+#[derive(
+    TypeAbi,
+    TopEncode,
+    TopDecode,
+    NestedEncode,
+    NestedDecode,
+    ManagedVecItem,
+    Clone,
+    Copy,
+    PartialEq,
+    Debug,
+)]
+pub struct LockBoostOption {
+    pub lock_epochs: Epoch,
+    pub reward_boost_percent: Percent,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub struct RewardPerShareSnapshot<M: ManagedTypeApi> {
+    pub epoch: Epoch,
+    pub reward_per_share: BigUint<M>,
+}
+
+/// Users may lock their farm position for one of the configured periods,
+/// in exchange for a bonus on the farm rewards, e.g. a `reward_boost_percent`
+/// of 5_000 pays 50% on top of the base farm rewards.
+/// The bonus is paid for the rewards accrued until the unlock epoch, even if claimed later,
+/// and is capped at `MAX_REWARD_BOOST_PERCENT`.
+/// Positions may only be merged with positions having the same lock.
+#[multiversx_sc::module]
+pub trait LockBoostsModule: permissions_module::PermissionsModule {
+    /// Pairs of (lock_epochs, reward_boost_percent), sorted by lock_epochs.
+    /// Calling it without arguments disables locking new positions.
+    #[endpoint(setLockBoostOptions)]
+    fn set_lock_boost_options(&self, options: MultiValueEncoded<MultiValue2<Epoch, Percent>>) {
+        self.require_caller_has_admin_permissions();
+        require!(options.len() <= MAX_LOCK_BOOST_OPTIONS, ERROR_PARAMETERS);
+
+        let mut lock_boost_options = ManagedVec::new();
+        let mut last_lock_epochs = 0;
+        for option in options {
+            let (lock_epochs, reward_boost_percent) = option.into_tuple();
+            require!(lock_epochs > last_lock_epochs, ERROR_PARAMETERS);
+            require!(
+                reward_boost_percent > 0 && reward_boost_percent <= MAX_REWARD_BOOST_PERCENT,
+                ERROR_PARAMETERS
+            );
+
+            last_lock_epochs = lock_epochs;
+            lock_boost_options.push(LockBoostOption {
+                lock_epochs,
+                reward_boost_percent,
+            });
+        }
+
+        self.lock_boost_options().set(lock_boost_options);
+    }
+
+    fn get_reward_boost_percent(&self, lock_epochs: Epoch) -> Percent {
+        for option in self.lock_boost_options().get().iter() {
+            if option.lock_epochs == lock_epochs {
+                return option.reward_boost_percent;
+            }
+        }
+
+        sc_panic!("Invalid lock choice");
+    }
+
+    fn lock_farm_position(
+        &self,
+        attributes: &mut FarmTokenAttributes<Self::Api>,
+        lock_epochs: Epoch,
+    ) {
+        let reward_boost_percent = self.get_reward_boost_percent(lock_epochs);
+        let current_epoch = self.blockchain().get_block_epoch();
+        attributes.unlock_epoch = current_epoch + lock_epochs;
+        attributes.reward_boost_percent = reward_boost_percent;
+    }
+
+    /// The boost is paid on the base rewards accrued until the unlock epoch,
+    /// i.e. up to the reward per share snapshotted at the unlock epoch
+    fn get_lock_boost_rewards(
+        &self,
+        farm_token_amount: &BigUint,
+        attributes: &FarmTokenAttributes<Self::Api>,
+        current_reward_per_share: &BigUint,
+        division_safety_constant: &BigUint,
+    ) -> BigUint {
+        if attributes.reward_boost_percent == 0 {
+            return BigUint::zero();
+        }
+
+        let boosted_reward_per_share = self
+            .get_reward_per_share_at_epoch(attributes.unlock_epoch)
+            .unwrap_or_else(|| current_reward_per_share.clone());
+        if boosted_reward_per_share <= attributes.reward_per_share {
+            return BigUint::zero();
+        }
+
+        let boosted_rewards = farm_token_amount
+            * &(boosted_reward_per_share - &attributes.reward_per_share)
+            / division_safety_constant;
+
+        boosted_rewards * attributes.reward_boost_percent / MAX_PERCENT
+    }
+
+    /// Saves the reward per share reached before the first update of each epoch.
+    /// Must be called before the reward per share is increased.
+    fn snapshot_reward_per_share(&self, reward_per_share: &BigUint) {
+        let current_epoch = self.blockchain().get_block_epoch();
+        let mut snapshots = self.reward_per_share_snapshots();
+        let snapshots_len = snapshots.len();
+        if snapshots_len > 0 && snapshots.get(snapshots_len).epoch >= current_epoch {
+            return;
+        }
+
+        let _ = snapshots.push(&RewardPerShareSnapshot {
+            epoch: current_epoch,
+            reward_per_share: reward_per_share.clone(),
+        });
+    }
+
+    /// The first snapshot taken at or after the given epoch, found by binary search
+    fn get_reward_per_share_at_epoch(&self, epoch: Epoch) -> Option<BigUint> {
+        let snapshots = self.reward_per_share_snapshots();
+        let mut low = 1;
+        let mut high = snapshots.len() + 1;
+        while low < high {
+            let mid = (low + high) / 2;
+            if snapshots.get(mid).epoch < epoch {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        if low > snapshots.len() {
+            return None;
+        }
+
+        Some(snapshots.get(low).reward_per_share)
+    }
+
+    fn require_farm_position_unlocked(&self, attributes: &FarmTokenAttributes<Self::Api>) {
+        let current_epoch = self.blockchain().get_block_epoch();
+        require!(
+            current_epoch >= attributes.unlock_epoch,
+            "Farm position is still locked"
+        );
+    }
+
+    #[view(getLockBoostOptions)]
+    #[storage_mapper("lock_boost_options")]
+    fn lock_boost_options(&self) -> SingleValueMapper<ManagedVec<LockBoostOption>>;
+
+    #[storage_mapper("reward_per_share_snapshots")]
+    fn reward_per_share_snapshots(&self) -> VecMapper<RewardPerShareSnapshot<Self::Api>>;
+}
@@ -0,0 +1,308 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use common_errors::{ERROR_BAD_INPUT_TOKEN, ERROR_PARAMETERS, ERROR_ZERO_AMOUNT};
+use common_structs::PaymentsVec;
+
+pub type CampaignId = u64;
+
+pub const MAX_LIVE_CAMPAIGNS: usize = 10;
+pub const MIN_CAMPAIGN_DURATION: u64 = 60 * 60;
+pub const MAX_CAMPAIGN_DURATION: u64 = 365 * 24 * 60 * 60;
+pub const CAMPAIGN_CLAIM_PERIOD: u64 = 30 * 24 * 60 * 60;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub struct RewardCampaign<M: ManagedTypeApi> {
+    pub creator: ManagedAddress<M>,
+    pub token_id: TokenIdentifier<M>,
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    pub reward_per_second: BigUint<M>,
+    pub last_update_timestamp: u64,
+    pub reward_per_share: BigUint<M>,
+    pub undistributed_amount: BigUint<M>,
+    pub unclaimed_amount: BigUint<M>,
+}
+
+/// Time-bound bonus campaigns, funded by anyone in one of the fungible tokens
+/// whitelisted by the admins, with at least the minimum deposit set by the owner for that token,
+/// so the limited campaign slots cannot be taken up by worthless tokens or dust.
+/// The deposit is distributed linearly over the campaign duration, pro-rata to the users'
+/// counted farm positions, same as the additional reward tokens. Whatever was not distributed, e.g. while the farm was empty,
+/// is refunded to the creator once the campaign ends. Ended campaigns leave the live set on the next update,
+/// freeing their slot, and their rewards stay claimable for `CAMPAIGN_CLAIM_PERIOD` after the end.
+/// Rewards left unclaimed by then are refunded as well, and the campaign is closed.
+#[multiversx_sc::module]
+pub trait RewardCampaignsModule:
+    config::ConfigModule
+    + token_send::TokenSendModule
+    + farm_token::FarmTokenModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+    + multiversx_sc_modules::default_issue_callbacks::DefaultIssueCallbacksModule
+    + crate::additional_rewards::AdditionalRewardsModule
+{
+    #[endpoint(addCampaignRewardToken)]
+    fn add_campaign_reward_token(&self, token_id: TokenIdentifier) {
+        self.require_caller_has_admin_permissions();
+        require!(token_id.is_valid_esdt_identifier(), ERROR_BAD_INPUT_TOKEN);
+        require!(
+            token_id != self.farming_token_id().get(),
+            ERROR_BAD_INPUT_TOKEN
+        );
+
+        let _ = self.campaign_reward_tokens().insert(token_id);
+    }
+
+    /// Only prevents new campaigns in the given token, the live ones are not affected
+    #[endpoint(removeCampaignRewardToken)]
+    fn remove_campaign_reward_token(&self, token_id: TokenIdentifier) {
+        self.require_caller_has_admin_permissions();
+
+        let _ = self.campaign_reward_tokens().swap_remove(&token_id);
+    }
+
+    #[only_owner]
+    #[endpoint(setCampaignMinDeposit)]
+    fn set_campaign_min_deposit(&self, token_id: TokenIdentifier, min_deposit: BigUint) {
+        require!(min_deposit > 0, ERROR_ZERO_AMOUNT);
+
+        self.campaign_min_deposit(&token_id).set(min_deposit);
+    }
+
+    #[payable("*")]
+    #[endpoint(createRewardCampaign)]
+    fn create_reward_campaign(&self, duration: u64) -> CampaignId {
+        let (token_id, amount) = self.call_value().single_fungible_esdt();
+        require!(amount > 0, ERROR_ZERO_AMOUNT);
+        require!(
+            self.campaign_reward_tokens().contains(&token_id),
+            ERROR_BAD_INPUT_TOKEN
+        );
+        let min_deposit_mapper = self.campaign_min_deposit(&token_id);
+        require!(
+            !min_deposit_mapper.is_empty() && amount >= min_deposit_mapper.get(),
+            "Campaign deposit below minimum"
+        );
+        require!(
+            (MIN_CAMPAIGN_DURATION..=MAX_CAMPAIGN_DURATION).contains(&duration),
+            ERROR_PARAMETERS
+        );
+
+        let reward_per_second = &amount / duration;
+        require!(reward_per_second > 0, ERROR_PARAMETERS);
+
+        self.generate_campaign_rewards();
+
+        let mut live_campaigns = self.live_reward_campaigns();
+        require!(live_campaigns.len() < MAX_LIVE_CAMPAIGNS, ERROR_PARAMETERS);
+
+        let campaign_id = self.last_reward_campaign_id().update(|id| {
+            *id += 1;
+            *id
+        });
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        let campaign = RewardCampaign {
+            creator: self.blockchain().get_caller(),
+            token_id,
+            start_timestamp: current_timestamp,
+            end_timestamp: current_timestamp + duration,
+            reward_per_second,
+            last_update_timestamp: current_timestamp,
+            reward_per_share: BigUint::zero(),
+            undistributed_amount: amount,
+            unclaimed_amount: BigUint::zero(),
+        };
+        self.reward_campaign(campaign_id).set(campaign);
+        let _ = live_campaigns.insert(campaign_id);
+
+        campaign_id
+    }
+
+    /// Refunds the undistributed part of an ended campaign to its creator.
+    /// After the claim period, the unclaimed rewards are refunded too and the campaign is closed.
+    #[endpoint(withdrawCampaignLeftover)]
+    fn withdraw_campaign_leftover(&self, campaign_id: CampaignId) -> BigUint {
+        let campaign_mapper = self.reward_campaign(campaign_id);
+        require!(!campaign_mapper.is_empty(), ERROR_PARAMETERS);
+
+        self.generate_campaign_rewards();
+
+        let mut campaign = campaign_mapper.get();
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == campaign.creator,
+            "Only the campaign creator may withdraw"
+        );
+
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        require!(
+            current_timestamp >= campaign.end_timestamp,
+            "Campaign has not ended yet"
+        );
+
+        let mut refund_amount = core::mem::take(&mut campaign.undistributed_amount);
+        if current_timestamp >= campaign.end_timestamp + CAMPAIGN_CLAIM_PERIOD {
+            refund_amount += core::mem::take(&mut campaign.unclaimed_amount);
+        }
+
+        self.send_tokens_non_zero(&caller, &campaign.token_id, 0, &refund_amount);
+        campaign_mapper.set(campaign);
+
+        refund_amount
+    }
+
+    /// Distributes the campaign rewards produced since the last update.
+    /// Rewards are only distributed while there are counted farm positions.
+    /// Ended campaigns are moved from the live set to the ended set, and closed
+    /// once their claim period is over.
+    fn generate_campaign_rewards(&self) {
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        let mut ended_campaigns = self.ended_reward_campaigns();
+        let ended_campaign_ids: ManagedVec<CampaignId> = ended_campaigns.iter().collect();
+        for campaign_id in &ended_campaign_ids {
+            let end_timestamp = self.reward_campaign(campaign_id).get().end_timestamp;
+            if current_timestamp >= end_timestamp + CAMPAIGN_CLAIM_PERIOD {
+                let _ = ended_campaigns.swap_remove(&campaign_id);
+            }
+        }
+
+        let total_counted_farm_position = self.total_counted_farm_position().get();
+        let division_safety_constant = self.division_safety_constant().get();
+        let mut live_campaigns = self.live_reward_campaigns();
+        let live_campaign_ids: ManagedVec<CampaignId> = live_campaigns.iter().collect();
+        for campaign_id in &live_campaign_ids {
+            let campaign_mapper = self.reward_campaign(campaign_id);
+            let mut campaign = campaign_mapper.get();
+            let update_timestamp = core::cmp::min(current_timestamp, campaign.end_timestamp);
+            if update_timestamp == campaign.end_timestamp {
+                let _ = live_campaigns.swap_remove(&campaign_id);
+                if update_timestamp + CAMPAIGN_CLAIM_PERIOD > current_timestamp {
+                    let _ = ended_campaigns.insert(campaign_id);
+                }
+            }
+            if update_timestamp <= campaign.last_update_timestamp {
+                continue;
+            }
+
+            let elapsed_time = update_timestamp - campaign.last_update_timestamp;
+            campaign.last_update_timestamp = update_timestamp;
+            if total_counted_farm_position > 0 {
+                let mut reward = &campaign.reward_per_second * elapsed_time;
+                if reward > campaign.undistributed_amount {
+                    reward = campaign.undistributed_amount.clone();
+                }
+
+                campaign.undistributed_amount -= &reward;
+                campaign.reward_per_share +=
+                    &reward * &division_safety_constant / &total_counted_farm_position;
+                campaign.unclaimed_amount += reward;
+            }
+
+            campaign_mapper.set(campaign);
+        }
+    }
+
+    /// Accrues the user's campaign rewards for their counted farm position.
+    /// Must be called before every change of the user's total farm position.
+    fn update_user_campaign_rewards(&self, user: &ManagedAddress) {
+        if self.live_reward_campaigns().is_empty() && self.ended_reward_campaigns().is_empty() {
+            return;
+        }
+
+        self.generate_campaign_rewards();
+
+        let user_farm_position = self.user_counted_farm_position(user).get();
+        let division_safety_constant = self.division_safety_constant().get();
+        for campaign_id in &self.get_claimable_reward_campaigns() {
+            let reward_per_share = self.reward_campaign(campaign_id).get().reward_per_share;
+            let user_rps_mapper = self.user_campaign_reward_per_share(user, campaign_id);
+            let user_reward_per_share = user_rps_mapper.get();
+            if reward_per_share <= user_reward_per_share {
+                continue;
+            }
+
+            if user_farm_position > 0 {
+                let reward = &user_farm_position * &(&reward_per_share - &user_reward_per_share)
+                    / &division_safety_constant;
+                self.user_campaign_rewards(user, campaign_id)
+                    .update(|rewards| *rewards += reward);
+            }
+
+            user_rps_mapper.set(reward_per_share);
+        }
+    }
+
+    /// Accrues and takes out all the user's campaign rewards, without sending them
+    fn claim_campaign_rewards(&self, user: &ManagedAddress) -> PaymentsVec<Self::Api> {
+        self.update_user_campaign_rewards(user);
+
+        let mut payments = PaymentsVec::new();
+        for campaign_id in &self.get_claimable_reward_campaigns() {
+            let reward = self.user_campaign_rewards(user, campaign_id).take();
+            if reward == 0 {
+                continue;
+            }
+
+            let campaign_mapper = self.reward_campaign(campaign_id);
+            let mut campaign = campaign_mapper.get();
+            campaign.unclaimed_amount -= &reward;
+            payments.push(EsdtTokenPayment::new(campaign.token_id.clone(), 0, reward));
+            campaign_mapper.set(campaign);
+        }
+
+        payments
+    }
+
+    /// The live campaigns, followed by the ended ones still in their claim period
+    fn get_claimable_reward_campaigns(&self) -> ManagedVec<CampaignId> {
+        let mut campaign_ids: ManagedVec<CampaignId> =
+            self.live_reward_campaigns().iter().collect();
+        for campaign_id in self.ended_reward_campaigns().iter() {
+            campaign_ids.push(campaign_id);
+        }
+
+        campaign_ids
+    }
+
+    #[view(getCampaignRewardTokens)]
+    #[storage_mapper("campaign_reward_tokens")]
+    fn campaign_reward_tokens(&self) -> UnorderedSetMapper<TokenIdentifier>;
+
+    #[view(getCampaignMinDeposit)]
+    #[storage_mapper("campaign_min_deposit")]
+    fn campaign_min_deposit(&self, token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("last_reward_campaign_id")]
+    fn last_reward_campaign_id(&self) -> SingleValueMapper<CampaignId>;
+
+    #[view(getLiveRewardCampaigns)]
+    #[storage_mapper("live_reward_campaigns")]
+    fn live_reward_campaigns(&self) -> UnorderedSetMapper<CampaignId>;
+
+    #[view(getEndedRewardCampaigns)]
+    #[storage_mapper("ended_reward_campaigns")]
+    fn ended_reward_campaigns(&self) -> UnorderedSetMapper<CampaignId>;
+
+    #[view(getRewardCampaign)]
+    #[storage_mapper("reward_campaign")]
+    fn reward_campaign(
+        &self,
+        campaign_id: CampaignId,
+    ) -> SingleValueMapper<RewardCampaign<Self::Api>>;
+
+    #[storage_mapper("user_campaign_reward_per_share")]
+    fn user_campaign_reward_per_share(
+        &self,
+        user: &ManagedAddress,
+        campaign_id: CampaignId,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getUserCampaignRewards)]
+    #[storage_mapper("user_campaign_rewards")]
+    fn user_campaign_rewards(
+        &self,
+        user: &ManagedAddress,
+        campaign_id: CampaignId,
+    ) -> SingleValueMapper<BigUint>;
+}
@@ -1,7 +1,18 @@
 #![allow(deprecated)]
 
 use common_structs::FarmTokenAttributes;
-use multiversx_sc_scenario::{managed_address, managed_biguint, rust_biguint, DebugApi};
+use farm::additional_rewards::AdditionalRewardsModule;
+use farm::base_functions::BaseFunctionsModule;
+use farm::emission_schedule::EmissionScheduleModule;
+use farm::lock_boosts::LockBoostsModule;
+use farm::reward_campaigns::RewardCampaignsModule;
+use farm::Farm;
+use mergeable::Mergeable;
+use multiversx_sc::codec::multi_types::OptionalValue;
+use multiversx_sc::types::MultiValueEncoded;
+use multiversx_sc_scenario::{
+    managed_address, managed_biguint, managed_token_id, rust_biguint, DebugApi,
+};
 
 pub mod farm_setup;
 use farm_boosted_yields::boosted_yields_factors::BoostedYieldsFactorsModule;
@@ -44,6 +55,8 @@ fn farm_with_no_boost_test() {
         compounded_reward: managed_biguint!(0),
         current_farm_amount: managed_biguint!(first_farm_token_amount),
         original_owner: managed_address!(&first_user),
+        unlock_epoch: 0,
+        reward_boost_percent: 0,
     };
     let first_rewards_amt =
         farm_setup.calculate_rewards(&first_user, first_farm_token_amount, first_attributes);
@@ -57,6 +70,8 @@ fn farm_with_no_boost_test() {
         compounded_reward: managed_biguint!(0),
         current_farm_amount: managed_biguint!(second_farm_token_amount),
         original_owner: managed_address!(&second_user),
+        unlock_epoch: 0,
+        reward_boost_percent: 0,
     };
     let second_rewards_amt =
         farm_setup.calculate_rewards(&second_user, second_farm_token_amount, second_attributes);
@@ -315,6 +330,8 @@ fn farm_boosted_yields_claim_with_different_user_pos_test() {
             entering_epoch: 2,
             current_farm_amount: managed_biguint!(first_farm_token_amount),
             original_owner: managed_address!(&first_user),
+            unlock_epoch: 0,
+            reward_boost_percent: 0,
         }),
     );
 
@@ -359,6 +376,8 @@ fn farm_boosted_yields_claim_with_different_user_pos_test() {
             entering_epoch: 2,
             current_farm_amount: managed_biguint!(first_farm_token_amount),
             original_owner: managed_address!(&first_user),
+            unlock_epoch: 0,
+            reward_boost_percent: 0,
         },
     );
 
@@ -423,6 +442,8 @@ fn farm_known_proxy_test() {
         compounded_reward: managed_biguint!(0),
         current_farm_amount: managed_biguint!(first_farm_token_amount),
         original_owner: managed_address!(&first_user),
+        unlock_epoch: 0,
+        reward_boost_percent: 0,
     };
     let first_rewards_amt =
         farm_setup.calculate_rewards(&first_user, first_farm_token_amount, first_attributes);
@@ -436,6 +457,8 @@ fn farm_known_proxy_test() {
         compounded_reward: managed_biguint!(0),
         current_farm_amount: managed_biguint!(second_farm_token_amount),
         original_owner: managed_address!(&second_user),
+        unlock_epoch: 0,
+        reward_boost_percent: 0,
     };
     let second_rewards_amt =
         farm_setup.calculate_rewards(&second_user, second_farm_token_amount, second_attributes);
@@ -972,3 +995,465 @@ fn farm_claim_with_minimum_tokens() {
     farm_setup.check_remaining_boosted_rewards_to_distribute(2, 0);
     farm_setup.check_remaining_boosted_rewards_to_distribute(3, 0);
 }
+
+#[test]
+fn farm_additional_reward_token_test() {
+    DebugApi::dummy();
+    let mut farm_setup = MultiUserFarmSetup::new(
+        farm::contract_obj,
+        energy_factory_mock::contract_obj,
+        energy_update::contract_obj,
+    );
+
+    let partner_token_id = b"PARTNER-123456";
+    let owner = farm_setup.owner.clone();
+    farm_setup
+        .b_mock
+        .set_esdt_balance(&owner, partner_token_id, &rust_biguint!(30_000));
+
+    farm_setup
+        .b_mock
+        .execute_tx(&owner, &farm_setup.farm_wrapper, &rust_biguint!(0), |sc| {
+            sc.add_additional_reward_token(
+                managed_token_id!(partner_token_id),
+                managed_biguint!(2_000),
+            );
+        })
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &owner,
+            &farm_setup.farm_wrapper,
+            partner_token_id,
+            0,
+            &rust_biguint!(30_000),
+            |sc| {
+                sc.deposit_additional_rewards();
+            },
+        )
+        .assert_ok();
+
+    let first_user = farm_setup.first_user.clone();
+    let second_user = farm_setup.second_user.clone();
+    farm_setup.enter_farm(&first_user, 100_000_000);
+    farm_setup.enter_farm(&second_user, 50_000_000);
+
+    // 10 blocks * 2_000 = 20_000 partner tokens, split 2:1
+    farm_setup.b_mock.set_block_nonce(10);
+    farm_setup.claim_rewards(&first_user, 1, 100_000_000);
+    farm_setup
+        .b_mock
+        .check_esdt_balance(&first_user, partner_token_id, &rust_biguint!(13_333));
+
+    // only 10_000 partner tokens left to distribute
+    farm_setup.b_mock.set_block_nonce(20);
+    farm_setup.exit_farm(&second_user, 2, 50_000_000);
+    farm_setup
+        .b_mock
+        .check_esdt_balance(&second_user, partner_token_id, &rust_biguint!(9_999));
+
+    // half of the first user's position gets half of their pending partner tokens
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.farm_wrapper, |sc| {
+            assert_eq!(
+                sc.total_counted_farm_position().get(),
+                managed_biguint!(100_000_000)
+            );
+
+            let extra_rewards = sc.calculate_extra_rewards_for_given_position(
+                &managed_address!(&first_user),
+                &managed_biguint!(50_000_000),
+            );
+            assert_eq!(extra_rewards.len(), 1);
+            assert_eq!(extra_rewards.get(0).amount, managed_biguint!(3_333));
+        })
+        .assert_ok();
+
+    farm_setup.b_mock.set_block_nonce(30);
+    farm_setup.claim_rewards(&first_user, 3, 100_000_000);
+    farm_setup
+        .b_mock
+        .check_esdt_balance(&first_user, partner_token_id, &rust_biguint!(19_999));
+
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.farm_wrapper, |sc| {
+            let partner_token_id = managed_token_id!(partner_token_id);
+            assert_eq!(
+                sc.additional_reward_capacity(&partner_token_id).get(),
+                managed_biguint!(0)
+            );
+            assert_eq!(
+                sc.additional_reward_reserve(&partner_token_id).get(),
+                managed_biguint!(2)
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn farm_halving_emission_schedule_test() {
+    DebugApi::dummy();
+    let mut farm_setup = MultiUserFarmSetup::new(
+        farm::contract_obj,
+        energy_factory_mock::contract_obj,
+        energy_update::contract_obj,
+    );
+
+    // 100 per second from timestamp 100, halved every 100 seconds, twice
+    let owner = farm_setup.owner.clone();
+    farm_setup
+        .b_mock
+        .execute_tx(&owner, &farm_setup.farm_wrapper, &rust_biguint!(0), |sc| {
+            sc.set_halving_emission_schedule_endpoint(100, managed_biguint!(100), 100, 2);
+
+            let schedule = sc.emission_schedule().get();
+            assert_eq!(schedule.len(), 3);
+            assert_eq!(schedule.get(2).start_timestamp, 300);
+            assert_eq!(schedule.get(2).reward_per_second, managed_biguint!(25));
+        })
+        .assert_ok();
+
+    let first_user = farm_setup.first_user.clone();
+    farm_setup.enter_farm(&first_user, 100_000_000);
+
+    // 100 * 100 + 50 * 100 + 25 * 50, regardless of the number of blocks
+    farm_setup.b_mock.set_block_nonce(10);
+    farm_setup.b_mock.set_block_timestamp(350);
+    let rewards = farm_setup.claim_rewards(&first_user, 1, 100_000_000);
+    assert_eq!(rewards, 16_250);
+
+    // back to per block rewards
+    farm_setup.b_mock.set_block_nonce(20);
+    farm_setup.b_mock.set_block_timestamp(400);
+    farm_setup
+        .b_mock
+        .execute_tx(&owner, &farm_setup.farm_wrapper, &rust_biguint!(0), |sc| {
+            sc.clear_emission_schedule_endpoint();
+        })
+        .assert_ok();
+
+    // 25 * 50 from the schedule + 10 blocks * 1_000
+    farm_setup.b_mock.set_block_nonce(30);
+    farm_setup.b_mock.set_block_timestamp(450);
+    let rewards = farm_setup.claim_rewards(&first_user, 2, 100_000_000);
+    assert_eq!(rewards, 11_250);
+}
+
+#[test]
+fn farm_reward_campaign_test() {
+    DebugApi::dummy();
+    let mut farm_setup = MultiUserFarmSetup::new(
+        farm::contract_obj,
+        energy_factory_mock::contract_obj,
+        energy_update::contract_obj,
+    );
+
+    let campaign_token_id = b"CAMPAIGN-123456";
+    let creator = farm_setup.owner.clone();
+    farm_setup
+        .b_mock
+        .set_esdt_balance(&creator, campaign_token_id, &rust_biguint!(36_000));
+
+    // only whitelisted tokens may fund campaigns
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &creator,
+            &farm_setup.farm_wrapper,
+            campaign_token_id,
+            0,
+            &rust_biguint!(36_000),
+            |sc| {
+                let _ = sc.create_reward_campaign(3_600);
+            },
+        )
+        .assert_user_error("Bad input token");
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &creator,
+            &farm_setup.farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.add_campaign_reward_token(managed_token_id!(campaign_token_id));
+                sc.set_campaign_min_deposit(
+                    managed_token_id!(campaign_token_id),
+                    managed_biguint!(10_000),
+                );
+            },
+        )
+        .assert_ok();
+
+    // dust campaigns are rejected
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &creator,
+            &farm_setup.farm_wrapper,
+            campaign_token_id,
+            0,
+            &rust_biguint!(9_999),
+            |sc| {
+                let _ = sc.create_reward_campaign(3_600);
+            },
+        )
+        .assert_user_error("Campaign deposit below minimum");
+
+    // 10 tokens per second for one hour
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &creator,
+            &farm_setup.farm_wrapper,
+            campaign_token_id,
+            0,
+            &rust_biguint!(36_000),
+            |sc| {
+                let campaign_id = sc.create_reward_campaign(3_600);
+                assert_eq!(campaign_id, 1);
+            },
+        )
+        .assert_ok();
+
+    // nothing is distributed while the farm is empty
+    let first_user = farm_setup.first_user.clone();
+    let second_user = farm_setup.second_user.clone();
+    farm_setup.b_mock.set_block_timestamp(600);
+    farm_setup.enter_farm(&first_user, 100_000_000);
+    farm_setup.b_mock.set_block_timestamp(1_800);
+    farm_setup.enter_farm(&second_user, 50_000_000);
+
+    // campaign not ended yet
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &creator,
+            &farm_setup.farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let _ = sc.withdraw_campaign_leftover(1);
+            },
+        )
+        .assert_user_error("Campaign has not ended yet");
+
+    // 1_200 * 10 alone, then 1_800 * 10 split 2:1
+    farm_setup.b_mock.set_block_timestamp(4_000);
+    farm_setup.claim_rewards(&first_user, 1, 100_000_000);
+    farm_setup
+        .b_mock
+        .check_esdt_balance(&first_user, campaign_token_id, &rust_biguint!(24_000));
+
+    // the ended campaign no longer takes a live slot, but stays claimable
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.farm_wrapper, |sc| {
+            assert!(sc.live_reward_campaigns().is_empty());
+            assert!(sc.ended_reward_campaigns().contains(&1));
+        })
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &creator,
+            &farm_setup.farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let refund_amount = sc.withdraw_campaign_leftover(1);
+                assert_eq!(refund_amount, managed_biguint!(6_000));
+            },
+        )
+        .assert_ok();
+
+    // the second user's rewards are refunded after the claim period
+    farm_setup
+        .b_mock
+        .set_block_timestamp(3_600 + farm::reward_campaigns::CAMPAIGN_CLAIM_PERIOD);
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &creator,
+            &farm_setup.farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let refund_amount = sc.withdraw_campaign_leftover(1);
+                assert_eq!(refund_amount, managed_biguint!(6_000));
+                assert!(sc.ended_reward_campaigns().is_empty());
+            },
+        )
+        .assert_ok();
+
+    farm_setup
+        .b_mock
+        .check_esdt_balance(&creator, campaign_token_id, &rust_biguint!(12_000));
+
+    farm_setup.exit_farm(&second_user, 2, 50_000_000);
+    farm_setup
+        .b_mock
+        .check_esdt_balance(&second_user, campaign_token_id, &rust_biguint!(0));
+}
+
+#[test]
+fn farm_lock_boost_test() {
+    DebugApi::dummy();
+    let mut farm_setup = MultiUserFarmSetup::new(
+        farm::contract_obj,
+        energy_factory_mock::contract_obj,
+        energy_update::contract_obj,
+    );
+
+    // 30 epochs lock for a 50% reward boost
+    farm_setup
+        .b_mock
+        .execute_tx(
+            &farm_setup.owner,
+            &farm_setup.farm_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut options = MultiValueEncoded::new();
+                options.push((30u64, 5_000u64).into());
+                sc.set_lock_boost_options(options);
+            },
+        )
+        .assert_ok();
+
+    let first_user = farm_setup.first_user.clone();
+    let second_user = farm_setup.second_user.clone();
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &first_user,
+            &farm_setup.farm_wrapper,
+            FARMING_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000_000),
+            |sc| {
+                let _ = sc.enter_farm_locked_endpoint(10, OptionalValue::None);
+            },
+        )
+        .assert_user_error("Invalid lock choice");
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &first_user,
+            &farm_setup.farm_wrapper,
+            FARMING_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000_000),
+            |sc| {
+                let (out_farm_token, _) = sc
+                    .enter_farm_locked_endpoint(30, OptionalValue::None)
+                    .into_tuple();
+                assert_eq!(out_farm_token.token_nonce, 1);
+            },
+        )
+        .assert_ok();
+    farm_setup.last_farm_token_nonce += 1;
+    farm_setup.enter_farm(&second_user, 100_000_000);
+
+    // 10 blocks - 10_000 rewards, split equally, plus the first user's boost
+    farm_setup.b_mock.set_block_nonce(10);
+    let first_rewards = farm_setup.claim_rewards(&first_user, 1, 100_000_000);
+    assert_eq!(first_rewards, 7_500);
+    let second_rewards = farm_setup.claim_rewards(&second_user, 2, 100_000_000);
+    assert_eq!(second_rewards, 5_000);
+
+    // the lock is kept on claim
+    farm_setup.b_mock.check_nft_balance(
+        &first_user,
+        FARM_TOKEN_ID,
+        3,
+        &rust_biguint!(100_000_000),
+        Some(&FarmTokenAttributes::<DebugApi> {
+            reward_per_share: managed_biguint!(50_000_000),
+            entering_epoch: 0,
+            compounded_reward: managed_biguint!(0),
+            current_farm_amount: managed_biguint!(100_000_000),
+            original_owner: managed_address!(&first_user),
+            unlock_epoch: 30,
+            reward_boost_percent: 5_000,
+        }),
+    );
+
+    farm_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &first_user,
+            &farm_setup.farm_wrapper,
+            FARM_TOKEN_ID,
+            3,
+            &rust_biguint!(100_000_000),
+            |sc| {
+                let _ = sc.exit_farm_endpoint(OptionalValue::None);
+            },
+        )
+        .assert_user_error("Farm position is still locked");
+
+    // the boost is paid until the unlock epoch, even when claimed later,
+    // and the position is unlocked on claim
+    farm_setup.b_mock.set_block_epoch(20);
+    farm_setup.b_mock.set_block_nonce(20);
+    let second_rewards = farm_setup.claim_rewards(&second_user, 4, 100_000_000);
+    assert_eq!(second_rewards, 5_000);
+
+    farm_setup.b_mock.set_block_epoch(40);
+    farm_setup.b_mock.set_block_nonce(30);
+    let first_rewards = farm_setup.claim_rewards(&first_user, 3, 100_000_000);
+    assert_eq!(first_rewards, 10_000 + 2_500);
+    farm_setup.b_mock.check_nft_balance(
+        &first_user,
+        FARM_TOKEN_ID,
+        6,
+        &rust_biguint!(100_000_000),
+        Some(&FarmTokenAttributes::<DebugApi> {
+            reward_per_share: managed_biguint!(150_000_000),
+            entering_epoch: 0,
+            compounded_reward: managed_biguint!(0),
+            current_farm_amount: managed_biguint!(100_000_000),
+            original_owner: managed_address!(&first_user),
+            unlock_epoch: 0,
+            reward_boost_percent: 0,
+        }),
+    );
+
+    farm_setup.exit_farm(&first_user, 6, 100_000_000);
+    farm_setup.b_mock.check_esdt_balance(
+        &first_user,
+        REWARD_TOKEN_ID,
+        &rust_biguint!(7_500 + 12_500),
+    );
+
+    // positions with different locks cannot be merged
+    farm_setup
+        .b_mock
+        .execute_query(&farm_setup.farm_wrapper, |_sc| {
+            let mut attributes = FarmTokenAttributes::<DebugApi> {
+                reward_per_share: managed_biguint!(0),
+                entering_epoch: 0,
+                compounded_reward: managed_biguint!(0),
+                current_farm_amount: managed_biguint!(100_000_000),
+                original_owner: managed_address!(&first_user),
+                unlock_epoch: 30,
+                reward_boost_percent: 5_000,
+            };
+            let mut other_attributes = attributes.clone();
+            other_attributes.unlock_epoch = 60;
+            assert!(!attributes.can_merge_with(&other_attributes));
+
+            other_attributes.unlock_epoch = 30;
+            attributes.merge_with(other_attributes);
+            assert_eq!(attributes.unlock_epoch, 30);
+            assert_eq!(attributes.reward_boost_percent, 5_000);
+            assert_eq!(
+                attributes.current_farm_amount,
+                managed_biguint!(200_000_000)
+            );
+        })
+        .assert_ok();
+}
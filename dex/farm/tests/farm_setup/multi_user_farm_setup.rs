@@ -47,6 +47,8 @@ pub struct RawFarmTokenAttributes {
     pub compounded_reward_bytes: Vec<u8>,
     pub current_farm_amount_bytes: Vec<u8>,
     pub original_owner_bytes: [u8; 32],
+    pub unlock_epoch: Epoch,
+    pub reward_boost_percent: u64,
 }
 
 pub struct NonceAmountPair {
@@ -373,6 +375,8 @@ where
                 .as_slice()
                 .to_vec(),
             original_owner_bytes: attributes.original_owner.to_byte_array(),
+            unlock_epoch: attributes.unlock_epoch,
+            reward_boost_percent: attributes.reward_boost_percent,
         };
 
         self.b_mock
@@ -392,6 +396,8 @@ where
                         multiversx_sc::types::ManagedAddress::<DebugApi>::new_from_bytes(
                             &raw_attributes.original_owner_bytes,
                         ),
+                    unlock_epoch: raw_attributes.unlock_epoch,
+                    reward_boost_percent: raw_attributes.reward_boost_percent,
                 };
 
                 let result_managed = sc.calculate_rewards_for_given_position(
@@ -399,7 +405,7 @@ where
                     managed_biguint!(farm_token_amount),
                     attributes_managed,
                 );
-                result = result_managed.to_u64().unwrap();
+                result = result_managed.to_vec().get(0).amount.to_u64().unwrap();
             })
             .assert_ok();
 
@@ -424,7 +430,7 @@ where
                 farm_token_nonce,
                 &rust_biguint!(farm_token_amount),
                 |sc| {
-                    let (out_farm_token, out_reward_token) =
+                    let (out_farm_token, out_reward_token, _) =
                         sc.claim_rewards_endpoint(OptionalValue::None).into_tuple();
                     assert_eq!(
                         out_farm_token.token_identifier,
@@ -470,7 +476,7 @@ where
         let mut result = 0;
         self.b_mock
             .execute_esdt_multi_transfer(user, &self.farm_wrapper, &payments, |sc| {
-                let (out_farm_token, out_reward_token) =
+                let (out_farm_token, out_reward_token, _) =
                     sc.claim_rewards_endpoint(OptionalValue::None).into_tuple();
                 assert_eq!(
                     out_farm_token.token_identifier,
@@ -547,7 +553,7 @@ where
                 farm_token_nonce,
                 &rust_biguint!(farm_token_amount),
                 |sc| {
-                    let (out_farm_token, out_reward_token) = sc
+                    let (out_farm_token, out_reward_token, _) = sc
                         .claim_rewards_endpoint(OptionalValue::Some(managed_address!(user)))
                         .into_tuple();
                     assert_eq!(
@@ -749,6 +755,8 @@ where
                 compounded_reward: managed_biguint!(0),
                 current_farm_amount: managed_biguint!(amount),
                 original_owner: managed_address!(&sender),
+                unlock_epoch: 0,
+                reward_boost_percent: 0,
             }),
         );
 
@@ -772,6 +780,8 @@ where
                 compounded_reward: managed_biguint!(0),
                 current_farm_amount: managed_biguint!(amount),
                 original_owner: managed_address!(&sender),
+                unlock_epoch: 0,
+                reward_boost_percent: 0,
             },
         );
 
@@ -786,6 +796,8 @@ where
                 compounded_reward: managed_biguint!(0),
                 current_farm_amount: managed_biguint!(amount),
                 original_owner: managed_address!(&sender),
+                unlock_epoch: 0,
+                reward_boost_percent: 0,
             },
         );
 
@@ -809,6 +821,8 @@ where
                 compounded_reward: managed_biguint!(0),
                 current_farm_amount: managed_biguint!(amount),
                 original_owner: managed_address!(&sender),
+                unlock_epoch: 0,
+                reward_boost_percent: 0,
             }),
         );
     }
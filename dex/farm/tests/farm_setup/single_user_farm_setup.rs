@@ -287,6 +287,8 @@ where
             compounded_reward: managed_biguint!(expected_compounded_reward),
             current_farm_amount: managed_biguint!(expected_total_out_amount),
             original_owner: managed_address!(&self.user_address),
+            unlock_epoch: 0,
+            reward_boost_percent: 0,
         };
         b_mock.check_nft_balance(
             &self.user_address,
@@ -319,7 +321,7 @@ where
                 |sc| {
                     let multi_result = sc.exit_farm_endpoint(OptionalValue::None);
 
-                    let (first_result, second_result) = multi_result.into_tuple();
+                    let (first_result, second_result, _) = multi_result.into_tuple();
 
                     assert_eq!(
                         first_result.token_identifier,
@@ -371,7 +373,7 @@ where
                 |sc| {
                     let multi_result = sc.claim_rewards_endpoint(OptionalValue::None);
 
-                    let (first_result, second_result) = multi_result.into_tuple();
+                    let (first_result, second_result, _) = multi_result.into_tuple();
 
                     assert_eq!(
                         first_result.token_identifier,
@@ -397,6 +399,8 @@ where
             compounded_reward: managed_biguint!(0),
             current_farm_amount: managed_biguint!(farm_token_amount),
             original_owner: managed_address!(&self.user_address),
+            unlock_epoch: 0,
+            reward_boost_percent: 0,
         };
 
         b_mock.check_nft_balance(
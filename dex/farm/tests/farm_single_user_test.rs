@@ -347,7 +347,7 @@ fn test_farm_through_simple_lock() {
             &rust_biguint!(1_000_000_000),
             |sc| {
                 let claim_result = sc.farm_claim_rewards_locked_token();
-                let (new_proxy_token, reward_tokens) = claim_result.into_tuple();
+                let (new_proxy_token, reward_tokens, _) = claim_result.into_tuple();
                 assert_eq!(
                     new_proxy_token.token_identifier,
                     managed_token_id!(FARM_PROXY_TOKEN_ID)
@@ -399,7 +399,7 @@ fn test_farm_through_simple_lock() {
             &rust_biguint!(1_000_000_000),
             |sc| {
                 let exit_farm_result = sc.exit_farm_locked_token();
-                let (locked_tokens, reward_tokens) = exit_farm_result.into_tuple();
+                let (locked_tokens, reward_tokens, _) = exit_farm_result.into_tuple();
 
                 assert_eq!(
                     locked_tokens.token_identifier,
@@ -597,7 +597,7 @@ fn test_farm_through_simple_lock() {
             &rust_biguint!(1_000_000_000),
             |sc| {
                 let exit_farm_result = sc.exit_farm_locked_token();
-                let (locked_tokens, _reward_tokens) = exit_farm_result.into_tuple();
+                let (locked_tokens, _reward_tokens, _) = exit_farm_result.into_tuple();
 
                 assert_eq!(
                     locked_tokens.token_identifier,
@@ -49,6 +49,8 @@ fn total_farm_position_claim_test() {
             entering_epoch: 2,
             current_farm_amount: managed_biguint!(farm_in_amount),
             original_owner: managed_address!(&first_user),
+            unlock_epoch: 0,
+            reward_boost_percent: 0,
         }),
     );
 
@@ -63,6 +65,8 @@ fn total_farm_position_claim_test() {
             entering_epoch: 2,
             current_farm_amount: managed_biguint!(farm_in_amount),
             original_owner: managed_address!(&first_user),
+            unlock_epoch: 0,
+            reward_boost_percent: 0,
         }),
     );
 
@@ -854,6 +858,8 @@ fn total_farm_position_owner_change_test() {
             compounded_reward: managed_biguint!(0),
             current_farm_amount: managed_biguint!(half_token_amount),
             original_owner: managed_address!(&second_user),
+            unlock_epoch: 0,
+            reward_boost_percent: 0,
         }),
     );
 
@@ -952,6 +958,8 @@ fn total_farm_position_owner_change_test() {
             compounded_reward: managed_biguint!(0),
             current_farm_amount: managed_biguint!(half_token_amount * 3),
             original_owner: managed_address!(&second_user),
+            unlock_epoch: 0,
+            reward_boost_percent: 0,
         }),
     );
 
@@ -1135,6 +1143,8 @@ fn total_farm_position_through_simple_lock_test() {
             entering_epoch: 2,
             current_farm_amount: managed_biguint!(farm_in_amount),
             original_owner: managed_address!(&user_addr),
+            unlock_epoch: 0,
+            reward_boost_percent: 0,
         }),
     );
 
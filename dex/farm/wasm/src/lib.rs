@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           63
+// Endpoints:                           94
 // Async Callback:                       1
-// Total number of exported functions:  66
+// Total number of exported functions:  97
 
 #![no_std]
 
@@ -21,6 +21,7 @@ multiversx_sc_wasm_adapter::endpoints! {
         init => init
         upgrade => upgrade
         enterFarm => enter_farm_endpoint
+        enterFarmLocked => enter_farm_locked_endpoint
         claimRewards => claim_rewards_endpoint
         compoundRewards => compound_rewards_endpoint
         exitFarm => exit_farm_endpoint
@@ -29,6 +30,9 @@ multiversx_sc_wasm_adapter::endpoints! {
         startProduceRewards => start_produce_rewards_endpoint
         endProduceRewards => end_produce_rewards_endpoint
         setPerBlockRewardAmount => set_per_block_rewards_endpoint
+        setEmissionSchedule => set_emission_schedule_endpoint
+        setHalvingEmissionSchedule => set_halving_emission_schedule_endpoint
+        clearEmissionSchedule => clear_emission_schedule_endpoint
         setBoostedYieldsRewardsPercentage => set_boosted_yields_rewards_percentage
         calculateRewardsForGivenPosition => calculate_rewards_for_given_position
         getRewardPerShare => reward_per_share
@@ -63,6 +67,33 @@ multiversx_sc_wasm_adapter::endpoints! {
         getMinimumFarmingEpoch => minimum_farming_epochs
         getBurnGasLimit => burn_gas_limit
         getPairContractManagedAddress => pair_contract_address
+        addAdditionalRewardToken => add_additional_reward_token
+        setAdditionalRewardPerBlockAmount => set_additional_reward_per_block_amount
+        depositAdditionalRewards => deposit_additional_rewards
+        getAdditionalRewardTokens => additional_reward_tokens
+        getAdditionalRewardPerBlockAmount => additional_reward_per_block_amount
+        getAdditionalRewardLastBlockNonce => additional_reward_last_block_nonce
+        getAdditionalRewardCapacity => additional_reward_capacity
+        getAdditionalRewardReserve => additional_reward_reserve
+        getAdditionalRewardPerShare => additional_reward_per_share
+        getTotalCountedFarmPosition => total_counted_farm_position
+        getUserCountedFarmPosition => user_counted_farm_position
+        getUserAdditionalRewards => user_additional_rewards
+        getEmissionSchedule => emission_schedule
+        getLastRewardTimestamp => last_reward_timestamp
+        addCampaignRewardToken => add_campaign_reward_token
+        removeCampaignRewardToken => remove_campaign_reward_token
+        setCampaignMinDeposit => set_campaign_min_deposit
+        createRewardCampaign => create_reward_campaign
+        withdrawCampaignLeftover => withdraw_campaign_leftover
+        getCampaignRewardTokens => campaign_reward_tokens
+        getCampaignMinDeposit => campaign_min_deposit
+        getLiveRewardCampaigns => live_reward_campaigns
+        getEndedRewardCampaigns => ended_reward_campaigns
+        getRewardCampaign => reward_campaign
+        getUserCampaignRewards => user_campaign_rewards
+        setLockBoostOptions => set_lock_boost_options
+        getLockBoostOptions => lock_boost_options
         collectUndistributedBoostedRewards => collect_undistributed_boosted_rewards
         getBoostedYieldsRewardsPercentage => boosted_yields_rewards_percentage
         getAccumulatedRewardsForWeek => accumulated_rewards_for_week
@@ -2,12 +2,24 @@ multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
 use crate::config::MAX_PERCENTAGE;
+use crate::contexts::base::SwapTokensOrder;
+use crate::stable_swap::{
+    compute_stable_swap_d, get_stable_swap_amount_in, get_stable_swap_amount_out,
+};
+use crate::weighted_pool::{
+    compute_weighted_k, compute_weighted_liquidity, get_weighted_amount_in,
+    get_weighted_amount_out, get_weighted_equivalent,
+};
 
 use super::config;
+use super::stable_swap;
+
+pub const MAX_SINGLE_TOKEN_SWAP_SEARCH_STEPS: usize = 128;
 
 #[multiversx_sc::module]
 pub trait AmmModule:
     config::ConfigModule
+    + stable_swap::StableSwapModule
     + token_send::TokenSendModule
     + permissions_module::PermissionsModule
     + pausable::PausableModule
@@ -17,9 +29,47 @@ pub trait AmmModule:
         first_token_amount: &BigUint,
         second_token_amount: &BigUint,
     ) -> BigUint {
+        let amp = self.get_current_amplification();
+        if amp != 0 {
+            return compute_stable_swap_d(amp, first_token_amount, second_token_amount);
+        }
+
+        let token_weights_mapper = self.token_weights();
+        if !token_weights_mapper.is_empty() {
+            return compute_weighted_k(
+                &token_weights_mapper.get(),
+                first_token_amount,
+                second_token_amount,
+            );
+        }
+
         first_token_amount * second_token_amount
     }
 
+    /// Pool invariant expressed in liquidity units, i.e. proportional with the LP supply
+    /// for balanced deposits: sqrt(x * y) for constant product pools
+    fn calculate_liquidity_invariant(
+        &self,
+        first_token_amount: &BigUint,
+        second_token_amount: &BigUint,
+    ) -> BigUint {
+        let amp = self.get_current_amplification();
+        if amp != 0 {
+            return compute_stable_swap_d(amp, first_token_amount, second_token_amount);
+        }
+
+        let token_weights_mapper = self.token_weights();
+        if !token_weights_mapper.is_empty() {
+            return compute_weighted_liquidity(
+                &token_weights_mapper.get(),
+                first_token_amount,
+                second_token_amount,
+            );
+        }
+
+        (first_token_amount * second_token_amount).sqrt()
+    }
+
     fn quote(
         &self,
         first_token_amount: &BigUint,
@@ -29,12 +79,50 @@ pub trait AmmModule:
         &(first_token_amount * second_token_reserve) / first_token_reserve
     }
 
+    /// Spot price equivalent of the input amount. Unlike `quote`, which keeps
+    /// the reserves ratio for liquidity operations, this accounts for the token weights.
+    fn get_equivalent_amount(
+        &self,
+        amount_in: &BigUint,
+        reserve_in: &BigUint,
+        reserve_out: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
+    ) -> BigUint {
+        if let Some((weight_in, weight_out)) = self.get_token_weights_in_out(swap_tokens_order) {
+            return get_weighted_equivalent(
+                weight_in,
+                weight_out,
+                amount_in,
+                reserve_in,
+                reserve_out,
+            );
+        }
+
+        self.quote(amount_in, reserve_in, reserve_out)
+    }
+
     fn get_amount_out_no_fee(
         &self,
         amount_in: &BigUint,
         reserve_in: &BigUint,
         reserve_out: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
     ) -> BigUint {
+        let amp = self.get_current_amplification();
+        if amp != 0 {
+            return get_stable_swap_amount_out(amp, amount_in, reserve_in, reserve_out);
+        }
+
+        if let Some((weight_in, weight_out)) = self.get_token_weights_in_out(swap_tokens_order) {
+            return get_weighted_amount_out(
+                weight_in,
+                weight_out,
+                amount_in,
+                reserve_in,
+                reserve_out,
+            );
+        }
+
         let numerator = amount_in * reserve_out;
         let denominator = reserve_in + amount_in;
 
@@ -46,8 +134,39 @@ pub trait AmmModule:
         amount_in: &BigUint,
         reserve_in: &BigUint,
         reserve_out: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
     ) -> BigUint {
-        let amount_in_with_fee = amount_in * (MAX_PERCENTAGE - self.total_fee_percent().get());
+        self.get_amount_out_with_fee(
+            amount_in,
+            reserve_in,
+            reserve_out,
+            swap_tokens_order,
+            self.total_fee_percent().get(),
+        )
+    }
+
+    fn get_amount_out_with_fee(
+        &self,
+        amount_in: &BigUint,
+        reserve_in: &BigUint,
+        reserve_out: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
+        total_fee_percent: u64,
+    ) -> BigUint {
+        let amount_in_with_fee = amount_in * (MAX_PERCENTAGE - total_fee_percent);
+
+        let amp = self.get_current_amplification();
+        let token_weights_opt = self.get_token_weights_in_out(swap_tokens_order);
+        if amp != 0 || token_weights_opt.is_some() {
+            let amount_in_after_fee = amount_in_with_fee / MAX_PERCENTAGE;
+            return self.get_amount_out_no_fee(
+                &amount_in_after_fee,
+                reserve_in,
+                reserve_out,
+                swap_tokens_order,
+            );
+        }
+
         let numerator = &amount_in_with_fee * reserve_out;
         let denominator = (reserve_in * MAX_PERCENTAGE) + amount_in_with_fee;
 
@@ -59,15 +178,135 @@ pub trait AmmModule:
         amount_out: &BigUint,
         reserve_in: &BigUint,
         reserve_out: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
     ) -> BigUint {
+        self.get_amount_in_with_fee(
+            amount_out,
+            reserve_in,
+            reserve_out,
+            swap_tokens_order,
+            self.total_fee_percent().get(),
+        )
+    }
+
+    fn get_amount_in_with_fee(
+        &self,
+        amount_out: &BigUint,
+        reserve_in: &BigUint,
+        reserve_out: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
+        total_fee_percent: u64,
+    ) -> BigUint {
+        let amp = self.get_current_amplification();
+        let token_weights_opt = self.get_token_weights_in_out(swap_tokens_order);
+        if amp != 0 || token_weights_opt.is_some() {
+            let amount_in_after_fee = match token_weights_opt {
+                Some((weight_in, weight_out)) => get_weighted_amount_in(
+                    weight_in,
+                    weight_out,
+                    amount_out,
+                    reserve_in,
+                    reserve_out,
+                ),
+                None => get_stable_swap_amount_in(amp, amount_out, reserve_in, reserve_out),
+            };
+            let numerator = amount_in_after_fee * MAX_PERCENTAGE;
+            let denominator = MAX_PERCENTAGE - total_fee_percent;
+
+            return (numerator / denominator) + 1u64;
+        }
+
         let numerator = reserve_in * amount_out * MAX_PERCENTAGE;
-        let denominator =
-            (reserve_out - amount_out) * (MAX_PERCENTAGE - self.total_fee_percent().get());
+        let denominator = (reserve_out - amount_out) * (MAX_PERCENTAGE - total_fee_percent);
 
         (numerator / denominator) + 1u64
     }
 
+    /// Part of a single token deposit that has to be swapped so that the rest, together
+    /// with the swap output, matches the pool ratio. For constant product pools this solves
+    /// s^2 * (1 - f) + s * R * (2 - f) - A * R = 0, with f being the total fee.
+    /// Stable and weighted pools have no closed form, so the amount is searched
+    /// against the pool's own swap output.
+    fn get_single_token_swap_amount(
+        &self,
+        amount_in: &BigUint,
+        reserve_in: &BigUint,
+        reserve_out: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
+    ) -> BigUint {
+        if self.get_current_amplification() != 0
+            || self.get_token_weights_in_out(swap_tokens_order).is_some()
+        {
+            return self.search_single_token_swap_amount(
+                amount_in,
+                reserve_in,
+                reserve_out,
+                swap_tokens_order,
+            );
+        }
+
+        let fee_complement = MAX_PERCENTAGE - self.total_fee_percent().get();
+        let linear_term = reserve_in * (MAX_PERCENTAGE + fee_complement);
+        let discriminant = &linear_term * &linear_term
+            + reserve_in * amount_in * (4 * fee_complement * MAX_PERCENTAGE);
+
+        (discriminant.sqrt() - linear_term) / (2 * fee_complement)
+    }
+
+    /// Bisection on the swapped amount s, keeping the largest s for which the
+    /// unswapped rest (A - s) is still not below the ratio of the reserves after the swap:
+    /// (A - s) * (reserve_out - out(s)) >= out(s) * (reserve_in + s)
+    fn search_single_token_swap_amount(
+        &self,
+        amount_in: &BigUint,
+        reserve_in: &BigUint,
+        reserve_out: &BigUint,
+        swap_tokens_order: SwapTokensOrder,
+    ) -> BigUint {
+        let mut low = BigUint::zero();
+        let mut high = amount_in.clone();
+        for _ in 0..MAX_SINGLE_TOKEN_SWAP_SEARCH_STEPS {
+            if &low + 1u64 >= high {
+                break;
+            }
+
+            let middle = (&low + &high) / 2u64;
+            let amount_out =
+                self.get_amount_out(&middle, reserve_in, reserve_out, swap_tokens_order);
+            let swaps_too_little = &amount_out < reserve_out
+                && (amount_in - &middle) * (reserve_out - &amount_out)
+                    >= &amount_out * &(reserve_in + &middle);
+            if swaps_too_little {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+
+        low
+    }
+
     fn get_special_fee_from_input(&self, amount_in: &BigUint) -> BigUint {
         amount_in * self.special_fee_percent().get() / MAX_PERCENTAGE
     }
+
+    /// Returns the (input, output) token weights, if this is a weighted pool
+    fn get_token_weights_in_out(&self, swap_tokens_order: SwapTokensOrder) -> Option<(u64, u64)> {
+        let token_weights_mapper = self.token_weights();
+        if token_weights_mapper.is_empty() {
+            return None;
+        }
+
+        let token_weights = token_weights_mapper.get();
+        match swap_tokens_order {
+            SwapTokensOrder::PoolOrder => Some((
+                token_weights.first_token_weight,
+                token_weights.second_token_weight,
+            )),
+            SwapTokensOrder::ReverseOrder => Some((
+                token_weights.second_token_weight,
+                token_weights.first_token_weight,
+            )),
+        }
+    }
 }
@@ -4,6 +4,8 @@ multiversx_sc::derive_imports!();
 use pausable::State;
 
 use super::errors::*;
+use super::flash_loan::OngoingFlashLoan;
+use super::weighted_pool::TokenWeights;
 
 pub const MAX_PERCENTAGE: u64 = 100_000;
 pub const MAX_FEE_PERCENTAGE: u64 = 5_000;
@@ -21,6 +23,11 @@ pub trait ConfigModule:
     #[endpoint(setFeePercents)]
     fn set_fee_percent(&self, total_fee_percent: u64, special_fee_percent: u64) {
         self.require_caller_has_owner_or_admin_permissions();
+        require!(
+            self.base_total_fee_percent().is_empty(),
+            ERROR_DYNAMIC_FEE_ENABLED
+        );
+
         self.set_fee_percents(total_fee_percent, special_fee_percent);
     }
 
@@ -33,6 +40,14 @@ pub trait ConfigModule:
         self.special_fee_percent().set(special_fee_percent);
     }
 
+    /// The pool is locked while its reserves are lent out
+    fn require_no_flash_loan_ongoing(&self) {
+        require!(
+            self.ongoing_flash_loan().is_empty(),
+            ERROR_FLASH_LOAN_ONGOING
+        );
+    }
+
     #[view(getLpTokenIdentifier)]
     fn get_lp_token_identifier(&self) -> TokenIdentifier {
         self.lp_token_identifier().get()
@@ -72,4 +87,16 @@ pub trait ConfigModule:
     #[view(getReserve)]
     #[storage_mapper("reserve")]
     fn pair_reserve(&self, token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    #[view(getTokenWeights)]
+    #[storage_mapper("token_weights")]
+    fn token_weights(&self) -> SingleValueMapper<TokenWeights>;
+
+    /// The total fee to restore once the dynamic fee mode is disabled.
+    /// Only set while the dynamic fee mode is on.
+    #[storage_mapper("base_total_fee_percent")]
+    fn base_total_fee_percent(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("ongoing_flash_loan")]
+    fn ongoing_flash_loan(&self) -> SingleValueMapper<OngoingFlashLoan<Self::Api>>;
 }
@@ -0,0 +1,210 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::{
+    amm,
+    config::{self, MAX_FEE_PERCENTAGE, MAX_PERCENTAGE},
+    errors::*,
+    safe_price::{self, PriceObservation},
+    stable_swap,
+};
+
+pub const MIN_VOLATILITY_OBSERVATIONS: usize = 3;
+pub const MAX_VOLATILITY_OBSERVATIONS: usize = 50;
+pub const VOLATILITY_PRICE_PRECISION: u64 = 1_000_000_000_000_000_000;
+
+#[derive(TypeAbi, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct DynamicFeeConfig {
+    pub min_fee_percent: u64,
+    pub max_fee_percent: u64,
+    pub nr_observations: usize,
+    pub max_volatility: u64,
+}
+
+#[multiversx_sc::module]
+pub trait DynamicFeeModule:
+    config::ConfigModule
+    + safe_price::SafePriceModule
+    + amm::AmmModule
+    + stable_swap::StableSwapModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    /// Enables the dynamic fee mode. The total fee floats between the given bounds,
+    /// proportionally with the volatility measured over the last `nr_observations`
+    /// price observations. Volatility is in MAX_PERCENTAGE units, capped at `max_volatility`.
+    /// The current total fee is kept and restored when the mode is disabled. Meanwhile,
+    /// the fee percents cannot be changed through `setFeePercents`.
+    #[endpoint(setDynamicFeeConfig)]
+    fn set_dynamic_fee_config(
+        &self,
+        min_fee_percent: u64,
+        max_fee_percent: u64,
+        nr_observations: usize,
+        max_volatility: u64,
+    ) {
+        self.require_caller_has_owner_permissions();
+        require!(
+            self.special_fee_percent().get() <= min_fee_percent
+                && min_fee_percent <= max_fee_percent
+                && max_fee_percent <= MAX_FEE_PERCENTAGE,
+            ERROR_BAD_PERCENTS
+        );
+        require!(
+            (MIN_VOLATILITY_OBSERVATIONS..=MAX_VOLATILITY_OBSERVATIONS).contains(&nr_observations)
+                && max_volatility > 0,
+            ERROR_BAD_DYNAMIC_FEE_CONFIG
+        );
+
+        let total_fee_percent = self.total_fee_percent().get();
+        self.base_total_fee_percent()
+            .set_if_empty(total_fee_percent);
+        let dynamic_fee_config = DynamicFeeConfig {
+            min_fee_percent,
+            max_fee_percent,
+            nr_observations,
+            max_volatility,
+        };
+        self.compute_dynamic_fee(&dynamic_fee_config);
+        self.dynamic_fee_config().set(dynamic_fee_config);
+        self.dynamic_fee_last_update_round()
+            .set(self.blockchain().get_block_round());
+    }
+
+    #[endpoint(disableDynamicFee)]
+    fn disable_dynamic_fee(&self) {
+        self.require_caller_has_owner_permissions();
+        require!(
+            !self.dynamic_fee_config().is_empty(),
+            ERROR_DYNAMIC_FEE_DISABLED
+        );
+
+        self.dynamic_fee_config().clear();
+        self.dynamic_fee_last_update_round().clear();
+        let base_total_fee_percent = self.base_total_fee_percent().take();
+        self.total_fee_percent().set(base_total_fee_percent);
+    }
+
+    #[view(getVolatility)]
+    fn get_volatility_view(&self) -> u64 {
+        let dynamic_fee_config_mapper = self.dynamic_fee_config();
+        let nr_observations = if dynamic_fee_config_mapper.is_empty() {
+            MAX_VOLATILITY_OBSERVATIONS
+        } else {
+            dynamic_fee_config_mapper.get().nr_observations
+        };
+
+        self.compute_volatility(nr_observations)
+    }
+
+    /// Recomputes the fee at most once per round, as the volatility
+    /// only changes when a new price observation is recorded
+    fn update_dynamic_fee(&self) {
+        let dynamic_fee_config_mapper = self.dynamic_fee_config();
+        if dynamic_fee_config_mapper.is_empty() {
+            return;
+        }
+
+        let current_round = self.blockchain().get_block_round();
+        let last_update_round_mapper = self.dynamic_fee_last_update_round();
+        if !last_update_round_mapper.is_empty() && last_update_round_mapper.get() == current_round {
+            return;
+        }
+
+        last_update_round_mapper.set(current_round);
+        self.compute_dynamic_fee(&dynamic_fee_config_mapper.get());
+    }
+
+    fn compute_dynamic_fee(&self, dynamic_fee_config: &DynamicFeeConfig) {
+        let volatility = core::cmp::min(
+            self.compute_volatility(dynamic_fee_config.nr_observations),
+            dynamic_fee_config.max_volatility,
+        );
+        let fee_range = dynamic_fee_config.max_fee_percent - dynamic_fee_config.min_fee_percent;
+        let new_fee_percent = dynamic_fee_config.min_fee_percent
+            + fee_range * volatility / dynamic_fee_config.max_volatility;
+
+        let special_fee_percent = self.special_fee_percent().get();
+        self.total_fee_percent()
+            .set(core::cmp::max(new_fee_percent, special_fee_percent));
+    }
+
+    /// Mean absolute relative change of the average price between consecutive
+    /// observation intervals, in MAX_PERCENTAGE units
+    fn compute_volatility(&self, nr_observations: usize) -> u64 {
+        let price_observations = self.price_observations();
+        let nr_stored_observations = price_observations.len();
+        let nr_observations = core::cmp::min(nr_observations, nr_stored_observations);
+        if nr_observations < MIN_VOLATILITY_OBSERVATIONS {
+            return 0;
+        }
+
+        let mut observation_index = self.safe_price_current_index().get();
+        let mut newer_observation = price_observations.get(observation_index);
+        let mut newer_price_opt: Option<BigUint> = None;
+        let mut total_price_change = BigUint::zero();
+        let mut nr_price_changes = 0u64;
+        for _ in 1..nr_observations {
+            observation_index = if observation_index == 1 {
+                nr_stored_observations
+            } else {
+                observation_index - 1
+            };
+
+            let older_observation = price_observations.get(observation_index);
+            let price = match self.compute_interval_price(&older_observation, &newer_observation) {
+                Some(price) => price,
+                None => break,
+            };
+
+            if let Some(newer_price) = newer_price_opt {
+                let price_diff = if newer_price > price {
+                    &newer_price - &price
+                } else {
+                    &price - &newer_price
+                };
+                total_price_change += price_diff * MAX_PERCENTAGE / &price;
+                nr_price_changes += 1;
+            }
+
+            newer_price_opt = Some(price);
+            newer_observation = older_observation;
+        }
+
+        if nr_price_changes == 0 {
+            return 0;
+        }
+
+        let volatility = total_price_change / nr_price_changes;
+        volatility.to_u64().unwrap_or(u64::MAX)
+    }
+
+    /// Average price of the second token in terms of the first one, between two observations
+    fn compute_interval_price(
+        &self,
+        older_observation: &PriceObservation<Self::Api>,
+        newer_observation: &PriceObservation<Self::Api>,
+    ) -> Option<BigUint> {
+        if newer_observation.recording_round <= older_observation.recording_round {
+            return None;
+        }
+
+        let first_token_reserve_diff = &newer_observation.first_token_reserve_accumulated
+            - &older_observation.first_token_reserve_accumulated;
+        let second_token_reserve_diff = &newer_observation.second_token_reserve_accumulated
+            - &older_observation.second_token_reserve_accumulated;
+        if first_token_reserve_diff == 0u64 || second_token_reserve_diff == 0u64 {
+            return None;
+        }
+
+        Some(second_token_reserve_diff * VOLATILITY_PRICE_PRECISION / first_token_reserve_diff)
+    }
+
+    #[view(getDynamicFeeConfig)]
+    #[storage_mapper("dynamic_fee_config")]
+    fn dynamic_fee_config(&self) -> SingleValueMapper<DynamicFeeConfig>;
+
+    #[storage_mapper("dynamic_fee_last_update_round")]
+    fn dynamic_fee_last_update_round(&self) -> SingleValueMapper<u64>;
+}
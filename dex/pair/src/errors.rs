@@ -30,6 +30,7 @@ pub static ERROR_POOL_TOKEN_IS_PLT: &[u8] = b"Token ID cannot be the same as LP
 pub static ERROR_BAD_PERCENTS: &[u8] = b"Bad percents";
 pub static ERROR_NOT_WHITELISTED: &[u8] = b"Not whitelisted";
 pub static ERROR_ALREADY_WHITELISTED: &[u8] = b"Already whitelisted";
+pub static ERROR_ORIG_CALLER_NOT_ALLOWED: &[u8] = b"Only the router may swap on behalf of a user";
 pub static ERROR_PAIR_ALREADY_TRUSTED: &[u8] = b"Pair already trusted";
 pub static ERROR_PAIR_NOT_TRUSTED: &[u8] = b"Pair not trusted";
 
@@ -52,3 +53,29 @@ pub static ERROR_SAFE_PRICE_CURRENT_INDEX: &[u8] =
     b"The current safe price index is greater than the maximum number of observations";
 pub static ERROR_SAFE_PRICE_OBSERVATION_DOES_NOT_EXIST: &[u8] =
     b"The price observation does not exist";
+
+pub static ERROR_NOT_STABLE_SWAP_POOL: &[u8] = b"Not a stable swap pool";
+pub static ERROR_BAD_AMPLIFICATION: &[u8] = b"Bad amplification coefficient";
+pub static ERROR_BAD_RAMP_TIMESTAMP: &[u8] = b"Bad amplification ramp timestamp";
+pub static ERROR_STABLE_SWAP_NOT_CONVERGED: &[u8] = b"Stable swap invariant did not converge";
+pub static ERROR_BAD_TOKEN_WEIGHTS: &[u8] = b"Bad token weights";
+pub static ERROR_POOL_TYPE_ALREADY_SET: &[u8] = b"Pool type already set";
+pub static ERROR_BAD_DYNAMIC_FEE_CONFIG: &[u8] = b"Bad dynamic fee config";
+pub static ERROR_DYNAMIC_FEE_ENABLED: &[u8] = b"Dynamic fee mode is enabled";
+pub static ERROR_DYNAMIC_FEE_DISABLED: &[u8] = b"Dynamic fee mode is disabled";
+pub static ERROR_LIMIT_ORDER_NOT_FOUND: &[u8] = b"Limit order not found";
+pub static ERROR_NOT_LIMIT_ORDER_OWNER: &[u8] = b"Not the limit order owner";
+pub static ERROR_LIMIT_ORDER_TOO_SMALL: &[u8] = b"Limit order too small";
+pub static ERROR_LIMIT_ORDER_BOOK_FULL: &[u8] = b"Limit order book full";
+pub static ERROR_FLASH_LOANS_DISABLED: &[u8] = b"Flash loans are disabled";
+pub static ERROR_FLASH_LOAN_ONGOING: &[u8] = b"Flash loan ongoing";
+pub static ERROR_NO_FLASH_LOAN_ONGOING: &[u8] = b"No flash loan ongoing";
+pub static ERROR_NOT_FLASH_LOAN_BORROWER: &[u8] = b"Not the flash loan borrower";
+pub static ERROR_FLASH_LOAN_NOT_REPAID: &[u8] = b"Flash loan not repaid";
+pub static ERROR_LOG_PRICE_NOT_RECORDED: &[u8] = b"Log price not recorded for the given rounds";
+pub static ERROR_BAD_OBSERVATIONS_CAPACITY: &[u8] = b"Bad price observations capacity";
+pub static ERROR_BAD_FEE_DISCOUNT_TIERS: &[u8] = b"Bad fee discount tiers";
+pub static ERROR_TWAMM_ORDER_NOT_FOUND: &[u8] = b"TWAMM order not found";
+pub static ERROR_NOT_TWAMM_ORDER_OWNER: &[u8] = b"Not the TWAMM order owner";
+pub static ERROR_TWAMM_ORDERS_NOT_EXECUTED: &[u8] =
+    b"TWAMM orders are not executed up to the current block";
@@ -2,6 +2,8 @@ use crate::contexts::add_liquidity::AddLiquidityContext;
 use crate::contexts::base::StorageCache;
 use crate::contexts::remove_liquidity::RemoveLiquidityContext;
 use crate::contexts::swap::SwapContext;
+use crate::limit_orders::{LimitOrder, LimitOrderId};
+use crate::twamm::{TwammOrder, TwammOrderId};
 
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
@@ -68,6 +70,51 @@ pub struct RemoveLiquidityEvent<M: ManagedTypeApi> {
     timestamp: u64,
 }
 
+#[derive(TypeAbi, TopEncode)]
+pub struct LimitOrderEvent<M: ManagedTypeApi> {
+    caller: ManagedAddress<M>,
+    order_id: LimitOrderId,
+    order_owner: ManagedAddress<M>,
+    input_token_id: TokenIdentifier<M>,
+    input_token_amount: BigUint<M>,
+    output_token_id: TokenIdentifier<M>,
+    min_output_token_amount: BigUint<M>,
+    output_token_amount: BigUint<M>,
+    fee_amount: BigUint<M>,
+    block: u64,
+    epoch: u64,
+    timestamp: u64,
+}
+
+#[derive(TypeAbi, TopEncode)]
+pub struct TwammOrderEvent<M: ManagedTypeApi> {
+    caller: ManagedAddress<M>,
+    order_id: TwammOrderId,
+    order_owner: ManagedAddress<M>,
+    input_token_id: TokenIdentifier<M>,
+    input_token_amount: BigUint<M>,
+    output_token_id: TokenIdentifier<M>,
+    output_token_amount: BigUint<M>,
+    start_block: u64,
+    expiry_block: u64,
+    block: u64,
+    epoch: u64,
+    timestamp: u64,
+}
+
+#[derive(TypeAbi, TopEncode)]
+pub struct FlashLoanEvent<M: ManagedTypeApi> {
+    caller: ManagedAddress<M>,
+    token_id: TokenIdentifier<M>,
+    token_amount: BigUint<M>,
+    fee_amount: BigUint<M>,
+    first_token_reserves: BigUint<M>,
+    second_token_reserves: BigUint<M>,
+    block: u64,
+    epoch: u64,
+    timestamp: u64,
+}
+
 #[multiversx_sc::module]
 pub trait EventsModule:
     crate::config::ConfigModule
@@ -188,6 +235,180 @@ pub trait EventsModule:
         )
     }
 
+    fn emit_place_limit_order_event(&self, order_id: LimitOrderId, order: &LimitOrder<Self::Api>) {
+        let epoch = self.blockchain().get_block_epoch();
+        let caller = self.blockchain().get_caller();
+        self.place_limit_order_event(
+            &order.input_token_id,
+            &caller,
+            epoch,
+            &self.build_limit_order_event(
+                caller.clone(),
+                order_id,
+                order,
+                BigUint::zero(),
+                BigUint::zero(),
+            ),
+        )
+    }
+
+    fn emit_cancel_limit_order_event(&self, order_id: LimitOrderId, order: &LimitOrder<Self::Api>) {
+        let epoch = self.blockchain().get_block_epoch();
+        let caller = self.blockchain().get_caller();
+        self.cancel_limit_order_event(
+            &order.input_token_id,
+            &caller,
+            epoch,
+            &self.build_limit_order_event(
+                caller.clone(),
+                order_id,
+                order,
+                BigUint::zero(),
+                BigUint::zero(),
+            ),
+        )
+    }
+
+    fn emit_fill_limit_order_event(
+        &self,
+        order_id: LimitOrderId,
+        order: &LimitOrder<Self::Api>,
+        output_amount: &BigUint,
+        fee_amount: &BigUint,
+    ) {
+        let epoch = self.blockchain().get_block_epoch();
+        let caller = self.blockchain().get_caller();
+        self.fill_limit_order_event(
+            &order.input_token_id,
+            &order.owner,
+            epoch,
+            &self.build_limit_order_event(
+                caller,
+                order_id,
+                order,
+                output_amount.clone(),
+                fee_amount.clone(),
+            ),
+        )
+    }
+
+    fn build_limit_order_event(
+        &self,
+        caller: ManagedAddress,
+        order_id: LimitOrderId,
+        order: &LimitOrder<Self::Api>,
+        output_token_amount: BigUint,
+        fee_amount: BigUint,
+    ) -> LimitOrderEvent<Self::Api> {
+        LimitOrderEvent {
+            caller,
+            order_id,
+            order_owner: order.owner.clone(),
+            input_token_id: order.input_token_id.clone(),
+            input_token_amount: order.input_amount.clone(),
+            output_token_id: order.output_token_id.clone(),
+            min_output_token_amount: order.min_output_amount.clone(),
+            output_token_amount,
+            fee_amount,
+            block: self.blockchain().get_block_nonce(),
+            epoch: self.blockchain().get_block_epoch(),
+            timestamp: self.blockchain().get_block_timestamp(),
+        }
+    }
+
+    fn emit_place_twamm_order_event(&self, order_id: TwammOrderId, order: &TwammOrder<Self::Api>) {
+        let epoch = self.blockchain().get_block_epoch();
+        let caller = self.blockchain().get_caller();
+        self.place_twamm_order_event(
+            &order.input_token_id,
+            &caller,
+            epoch,
+            &self.build_twamm_order_event(caller.clone(), order_id, order, BigUint::zero()),
+        )
+    }
+
+    fn emit_withdraw_twamm_order_event(
+        &self,
+        order_id: TwammOrderId,
+        order: &TwammOrder<Self::Api>,
+        output_amount: &BigUint,
+    ) {
+        let epoch = self.blockchain().get_block_epoch();
+        let caller = self.blockchain().get_caller();
+        self.withdraw_twamm_order_event(
+            &order.input_token_id,
+            &caller,
+            epoch,
+            &self.build_twamm_order_event(caller.clone(), order_id, order, output_amount.clone()),
+        )
+    }
+
+    fn emit_cancel_twamm_order_event(
+        &self,
+        order_id: TwammOrderId,
+        order: &TwammOrder<Self::Api>,
+        output_amount: &BigUint,
+    ) {
+        let epoch = self.blockchain().get_block_epoch();
+        let caller = self.blockchain().get_caller();
+        self.cancel_twamm_order_event(
+            &order.input_token_id,
+            &caller,
+            epoch,
+            &self.build_twamm_order_event(caller.clone(), order_id, order, output_amount.clone()),
+        )
+    }
+
+    fn build_twamm_order_event(
+        &self,
+        caller: ManagedAddress,
+        order_id: TwammOrderId,
+        order: &TwammOrder<Self::Api>,
+        output_token_amount: BigUint,
+    ) -> TwammOrderEvent<Self::Api> {
+        TwammOrderEvent {
+            caller,
+            order_id,
+            order_owner: order.owner.clone(),
+            input_token_id: order.input_token_id.clone(),
+            input_token_amount: order.input_amount.clone(),
+            output_token_id: order.output_token_id.clone(),
+            output_token_amount,
+            start_block: order.start_block,
+            expiry_block: order.expiry_block,
+            block: self.blockchain().get_block_nonce(),
+            epoch: self.blockchain().get_block_epoch(),
+            timestamp: self.blockchain().get_block_timestamp(),
+        }
+    }
+
+    fn emit_flash_loan_event(
+        &self,
+        storage_cache: &StorageCache<Self>,
+        token_id: &TokenIdentifier,
+        token_amount: &BigUint,
+        fee_amount: &BigUint,
+    ) {
+        let epoch = self.blockchain().get_block_epoch();
+        let caller = self.blockchain().get_caller();
+        self.flash_loan_event(
+            token_id,
+            &caller,
+            epoch,
+            &FlashLoanEvent {
+                caller: caller.clone(),
+                token_id: token_id.clone(),
+                token_amount: token_amount.clone(),
+                fee_amount: fee_amount.clone(),
+                first_token_reserves: storage_cache.first_token_reserve.clone(),
+                second_token_reserves: storage_cache.second_token_reserve.clone(),
+                block: self.blockchain().get_block_nonce(),
+                epoch,
+                timestamp: self.blockchain().get_block_timestamp(),
+            },
+        )
+    }
+
     #[event("swap")]
     fn swap_event(
         &self,
@@ -226,4 +447,67 @@ pub trait EventsModule:
         #[indexed] epoch: u64,
         remove_liquidity_event: &RemoveLiquidityEvent<Self::Api>,
     );
+
+    #[event("place_limit_order")]
+    fn place_limit_order_event(
+        &self,
+        #[indexed] input_token: &TokenIdentifier,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        limit_order_event: &LimitOrderEvent<Self::Api>,
+    );
+
+    #[event("cancel_limit_order")]
+    fn cancel_limit_order_event(
+        &self,
+        #[indexed] input_token: &TokenIdentifier,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        limit_order_event: &LimitOrderEvent<Self::Api>,
+    );
+
+    #[event("fill_limit_order")]
+    fn fill_limit_order_event(
+        &self,
+        #[indexed] input_token: &TokenIdentifier,
+        #[indexed] order_owner: &ManagedAddress,
+        #[indexed] epoch: u64,
+        limit_order_event: &LimitOrderEvent<Self::Api>,
+    );
+
+    #[event("place_twamm_order")]
+    fn place_twamm_order_event(
+        &self,
+        #[indexed] input_token: &TokenIdentifier,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        twamm_order_event: &TwammOrderEvent<Self::Api>,
+    );
+
+    #[event("withdraw_twamm_order")]
+    fn withdraw_twamm_order_event(
+        &self,
+        #[indexed] input_token: &TokenIdentifier,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        twamm_order_event: &TwammOrderEvent<Self::Api>,
+    );
+
+    #[event("cancel_twamm_order")]
+    fn cancel_twamm_order_event(
+        &self,
+        #[indexed] input_token: &TokenIdentifier,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        twamm_order_event: &TwammOrderEvent<Self::Api>,
+    );
+
+    #[event("flash_loan")]
+    fn flash_loan_event(
+        &self,
+        #[indexed] token_id: &TokenIdentifier,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] epoch: u64,
+        flash_loan_event: &FlashLoanEvent<Self::Api>,
+    );
 }
@@ -5,6 +5,7 @@ use super::amm;
 use super::config;
 use super::errors::*;
 use super::liquidity_pool;
+use super::stable_swap::{self, compute_stable_swap_d};
 use crate::config::MAX_PERCENTAGE;
 use crate::contexts::base::StorageCache;
 use crate::contexts::base::SwapTokensOrder;
@@ -28,6 +29,7 @@ pub trait FeeModule:
     config::ConfigModule
     + liquidity_pool::LiquidityPoolModule
     + amm::AmmModule
+    + stable_swap::StableSwapModule
     + token_send::TokenSendModule
     + permissions_module::PermissionsModule
     + pausable::PausableModule
@@ -37,6 +39,17 @@ pub trait FeeModule:
         !self.destination_map().is_empty() || !self.fees_collector_address().is_empty()
     }
 
+    #[view(isLpFeeModeEnabled)]
+    fn is_lp_fee_mode_enabled(&self) -> bool {
+        !self.lp_fee_treasury().is_empty()
+    }
+
+    /// Whether the special fee is taken out of the pool on every swap.
+    /// In LP fee mode, the special fee stays in the reserves instead.
+    fn is_special_fee_taken_on_swap(&self) -> bool {
+        self.is_fee_enabled() && !self.is_lp_fee_mode_enabled()
+    }
+
     #[endpoint(whitelist)]
     fn whitelist_endpoint(&self, address: ManagedAddress) {
         self.require_caller_has_owner_permissions();
@@ -370,6 +383,114 @@ pub trait FeeModule:
         }
     }
 
+    /// Enables the LP fee mode. Instead of sending the special fee on every swap, the
+    /// protocol share of the fees accrued in the reserves is minted as LP tokens to
+    /// `treasury_address` whenever liquidity is added or removed.
+    #[endpoint(setLpFeeOn)]
+    fn set_lp_fee_on(&self, treasury_address: ManagedAddress) {
+        self.require_caller_has_owner_permissions();
+        require!(!treasury_address.is_zero(), ERROR_INVALID_ARGS);
+
+        let mut storage_cache = StorageCache::new(self);
+        self.mint_lp_fee(&mut storage_cache);
+        self.lp_fee_treasury().set(&treasury_address);
+        self.update_root_k_last(&storage_cache);
+    }
+
+    /// Mints the fee accrued so far to the treasury, then disables the LP fee mode
+    #[endpoint(setLpFeeOff)]
+    fn set_lp_fee_off(&self) {
+        self.require_caller_has_owner_permissions();
+
+        let mut storage_cache = StorageCache::new(self);
+        self.mint_lp_fee(&mut storage_cache);
+        self.lp_fee_treasury().clear();
+        self.update_root_k_last(&storage_cache);
+    }
+
+    /// Mints to the treasury the LP tokens matching the protocol share of the fees
+    /// accrued since the last liquidity change, as the special fee out of the total fee.
+    ///
+    /// liquidity = supply * (root_k - root_k_last) * special_fee
+    ///     / (root_k * (total_fee - special_fee) + root_k_last * special_fee)
+    fn mint_lp_fee(&self, storage_cache: &mut StorageCache<Self>) {
+        let treasury_mapper = self.lp_fee_treasury();
+        if treasury_mapper.is_empty() || storage_cache.lp_token_supply == 0u64 {
+            return;
+        }
+
+        let root_k_last = self.root_k_last().get();
+        if root_k_last == 0u64 {
+            return;
+        }
+
+        let root_k_last_amplification = self.root_k_last_amplification().get();
+        let root_k = self.calculate_lp_fee_invariant(storage_cache, root_k_last_amplification);
+        if root_k <= root_k_last {
+            return;
+        }
+
+        let special_fee_percent = self.special_fee_percent().get();
+        let total_fee_percent = self.total_fee_percent().get();
+        if special_fee_percent == 0 || total_fee_percent < special_fee_percent {
+            return;
+        }
+
+        let numerator =
+            &storage_cache.lp_token_supply * &(&root_k - &root_k_last) * special_fee_percent;
+        let denominator = &root_k * (total_fee_percent - special_fee_percent)
+            + &root_k_last * special_fee_percent;
+        let liquidity = numerator / denominator;
+        if liquidity == 0u64 {
+            return;
+        }
+
+        storage_cache.lp_token_supply += &liquidity;
+        self.send()
+            .esdt_local_mint(&storage_cache.lp_token_id, 0, &liquidity);
+        self.send().direct_esdt(
+            &treasury_mapper.get(),
+            &storage_cache.lp_token_id,
+            0,
+            &liquidity,
+        );
+    }
+
+    fn update_root_k_last(&self, storage_cache: &StorageCache<Self>) {
+        if self.lp_fee_treasury().is_empty() {
+            self.root_k_last().clear();
+            self.root_k_last_amplification().clear();
+            return;
+        }
+
+        let amplification = self.get_current_amplification();
+        let root_k = self.calculate_lp_fee_invariant(storage_cache, amplification);
+        self.root_k_last().set(root_k);
+        self.root_k_last_amplification().set(amplification);
+    }
+
+    /// For stable pools, the invariant depends on the amplification, which may be ramping.
+    /// Both root_k and root_k_last are computed with the amplification of the last
+    /// liquidity change, so that only the growth from fees is minted.
+    fn calculate_lp_fee_invariant(
+        &self,
+        storage_cache: &StorageCache<Self>,
+        amplification: u64,
+    ) -> BigUint {
+        if amplification != 0 {
+            return compute_stable_swap_d(
+                amplification,
+                &storage_cache.first_token_reserve,
+                &storage_cache.second_token_reserve,
+            );
+        }
+
+        self.calculate_liquidity_invariant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        )
+    }
+
     #[view(getFeeDestinations)]
     fn get_fee_destinations(&self) -> MultiValueEncoded<(ManagedAddress, TokenIdentifier)> {
         let mut result = MultiValueEncoded::new();
@@ -419,4 +540,16 @@ pub trait FeeModule:
 
     #[storage_mapper("whitelist")]
     fn whitelist(&self) -> SetMapper<ManagedAddress>;
+
+    #[view(getLpFeeTreasury)]
+    #[storage_mapper("lp_fee_treasury")]
+    fn lp_fee_treasury(&self) -> SingleValueMapper<ManagedAddress>;
+
+    /// Liquidity invariant of the reserves after the last liquidity change
+    #[view(getRootKLast)]
+    #[storage_mapper("root_k_last")]
+    fn root_k_last(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("root_k_last_amplification")]
+    fn root_k_last_amplification(&self) -> SingleValueMapper<u64>;
 }
@@ -0,0 +1,135 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::{
+    config::{self, MAX_PERCENTAGE},
+    errors::*,
+};
+
+pub const MAX_FEE_DISCOUNT_TIERS: usize = 10;
+
+#[derive(
+    TypeAbi,
+    TopEncode,
+    TopDecode,
+    NestedEncode,
+    NestedDecode,
+    ManagedVecItem,
+    Clone,
+    PartialEq,
+    Debug,
+)]
+pub struct FeeDiscountTier<M: ManagedTypeApi> {
+    pub min_energy: BigUint<M>,
+    pub discount_percent: u64,
+}
+
+#[multiversx_sc::module]
+pub trait FeeDiscountModule:
+    config::ConfigModule
+    + energy_query::EnergyQueryModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    /// Sets the energy based fee discount tiers, as pairs of (min_energy, discount_percent),
+    /// sorted by min_energy. The discount is in MAX_PERCENTAGE units and applies
+    /// to the LP part of the total fee. Passing no tiers disables the discounts.
+    #[endpoint(setFeeDiscountTiers)]
+    fn set_fee_discount_tiers(&self, tiers: MultiValueEncoded<MultiValue2<BigUint, u64>>) {
+        self.require_caller_has_owner_permissions();
+        require!(
+            tiers.len() <= MAX_FEE_DISCOUNT_TIERS,
+            ERROR_BAD_FEE_DISCOUNT_TIERS
+        );
+
+        let mut fee_discount_tiers = ManagedVec::new();
+        let mut last_tier_opt: Option<FeeDiscountTier<Self::Api>> = None;
+        for tier_multi_value in tiers {
+            let (min_energy, discount_percent) = tier_multi_value.into_tuple();
+            require!(
+                min_energy > 0 && discount_percent > 0 && discount_percent <= MAX_PERCENTAGE,
+                ERROR_BAD_FEE_DISCOUNT_TIERS
+            );
+            if let Some(last_tier) = &last_tier_opt {
+                require!(
+                    min_energy > last_tier.min_energy
+                        && discount_percent > last_tier.discount_percent,
+                    ERROR_BAD_FEE_DISCOUNT_TIERS
+                );
+            }
+
+            let tier = FeeDiscountTier {
+                min_energy,
+                discount_percent,
+            };
+            fee_discount_tiers.push(tier.clone());
+            last_tier_opt = Some(tier);
+        }
+
+        if fee_discount_tiers.is_empty() {
+            self.fee_discount_tiers().clear();
+        } else {
+            self.fee_discount_tiers().set(fee_discount_tiers);
+        }
+    }
+
+    /// The total fee percent paid by the given user on swaps, after the energy discount
+    #[view(getUserTotalFeePercent)]
+    fn get_user_total_fee_percent(&self, user: ManagedAddress) -> u64 {
+        let total_fee_percent = self.total_fee_percent().get();
+        let discount_percent = self.get_fee_discount_percent(&user);
+        if discount_percent == 0 {
+            return total_fee_percent;
+        }
+
+        let special_fee_percent = self.special_fee_percent().get();
+        let lp_fee_percent = total_fee_percent - special_fee_percent;
+        let lp_fee_discount = lp_fee_percent * discount_percent / MAX_PERCENTAGE;
+
+        total_fee_percent - lp_fee_discount
+    }
+
+    /// The user whose energy sets the fee discount of a swap.
+    /// Only the router may swap on behalf of another user.
+    fn get_fee_discount_user(
+        &self,
+        caller: &ManagedAddress,
+        opt_orig_caller: OptionalValue<ManagedAddress>,
+    ) -> ManagedAddress {
+        match opt_orig_caller {
+            OptionalValue::Some(orig_caller) => {
+                require!(
+                    *caller == self.router_address().get(),
+                    ERROR_ORIG_CALLER_NOT_ALLOWED
+                );
+
+                orig_caller
+            }
+            OptionalValue::None => caller.clone(),
+        }
+    }
+
+    fn get_fee_discount_percent(&self, user: &ManagedAddress) -> u64 {
+        let tiers_mapper = self.fee_discount_tiers();
+        if tiers_mapper.is_empty() {
+            return 0;
+        }
+
+        let user_energy = self.get_energy_amount(user);
+        let mut discount_percent = 0;
+        for tier in tiers_mapper.get().iter() {
+            if user_energy < tier.min_energy {
+                break;
+            }
+
+            discount_percent = tier.discount_percent;
+        }
+
+        discount_percent
+    }
+
+    #[view(getFeeDiscountTiers)]
+    #[storage_mapper("fee_discount_tiers")]
+    fn fee_discount_tiers(&self) -> SingleValueMapper<ManagedVec<FeeDiscountTier<Self::Api>>>;
+}
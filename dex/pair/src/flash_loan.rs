@@ -0,0 +1,184 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::{
+    config::{MAX_FEE_PERCENTAGE, MAX_PERCENTAGE},
+    contexts::base::{StorageCache, SwapTokensOrder},
+    errors::*,
+};
+
+#[derive(TypeAbi, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct FlashLoanConfig {
+    pub fee_percent: u64,
+    pub whitelisted_only: bool,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct OngoingFlashLoan<M: ManagedTypeApi> {
+    pub borrower: ManagedAddress<M>,
+    pub token_id: TokenIdentifier<M>,
+    pub amount_to_repay: BigUint<M>,
+    pub amount_repaid: BigUint<M>,
+}
+
+#[multiversx_sc::module]
+pub trait FlashLoanModule:
+    crate::liquidity_pool::LiquidityPoolModule
+    + crate::amm::AmmModule
+    + crate::stable_swap::StableSwapModule
+    + crate::events::EventsModule
+    + crate::safe_price::SafePriceModule
+    + crate::fee::FeeModule
+    + crate::config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+    + crate::pair_actions::common_methods::CommonMethodsModule
+{
+    /// Enables flash loans. When `whitelisted_only` is set, only the addresses
+    /// in the pair's whitelist can borrow.
+    #[endpoint(setFlashLoanConfig)]
+    fn set_flash_loan_config(&self, fee_percent: u64, whitelisted_only: bool) {
+        self.require_caller_has_owner_permissions();
+        require!(
+            fee_percent > 0 && fee_percent <= MAX_FEE_PERCENTAGE,
+            ERROR_BAD_PERCENTS
+        );
+
+        self.flash_loan_config().set(FlashLoanConfig {
+            fee_percent,
+            whitelisted_only,
+        });
+    }
+
+    #[endpoint(disableFlashLoans)]
+    fn disable_flash_loans(&self) {
+        self.require_caller_has_owner_permissions();
+        self.flash_loan_config().clear();
+    }
+
+    /// Lends `amount` of one of the pool tokens to the caller and calls `callback_endpoint`
+    /// on it. Before the callback returns, the borrower has to send back the borrowed amount
+    /// plus the fee through `repayFlashLoan`, in the borrowed token.
+    #[endpoint(flashLoan)]
+    fn flash_loan(
+        &self,
+        token_id: TokenIdentifier,
+        amount: BigUint,
+        callback_endpoint: ManagedBuffer,
+        callback_args: MultiValueEncoded<ManagedBuffer>,
+    ) {
+        let flash_loan_config_mapper = self.flash_loan_config();
+        require!(
+            !flash_loan_config_mapper.is_empty(),
+            ERROR_FLASH_LOANS_DISABLED
+        );
+
+        let flash_loan_config = flash_loan_config_mapper.get();
+        let caller = self.blockchain().get_caller();
+        if flash_loan_config.whitelisted_only {
+            require!(self.whitelist().contains(&caller), ERROR_NOT_WHITELISTED);
+        }
+
+        self.require_no_flash_loan_ongoing();
+        let storage_cache = StorageCache::new(self);
+        require!(
+            self.can_swap(storage_cache.contract_state),
+            ERROR_SWAP_NOT_ENABLED
+        );
+        let swap_tokens_order = if token_id == storage_cache.first_token_id {
+            SwapTokensOrder::PoolOrder
+        } else if token_id == storage_cache.second_token_id {
+            SwapTokensOrder::ReverseOrder
+        } else {
+            sc_panic!(ERROR_UNKNOWN_TOKEN);
+        };
+        require!(amount > 0, ERROR_ZERO_AMOUNT);
+        require!(
+            *storage_cache.get_reserve_in(swap_tokens_order) > amount,
+            ERROR_NOT_ENOUGH_RESERVE
+        );
+
+        self.update_safe_price(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            &storage_cache.lp_token_supply,
+        );
+
+        let initial_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+
+        // the stored reserves stay untouched while the loan is ongoing,
+        // so the views keep showing the pool's state during the callback
+        drop(storage_cache);
+
+        // rounded up, so that small loans are not free
+        let fee_amount =
+            (&amount * flash_loan_config.fee_percent + (MAX_PERCENTAGE - 1)) / MAX_PERCENTAGE;
+
+        self.ongoing_flash_loan().set(OngoingFlashLoan {
+            borrower: caller.clone(),
+            token_id: token_id.clone(),
+            amount_to_repay: &amount + &fee_amount,
+            amount_repaid: BigUint::zero(),
+        });
+        let _: IgnoreValue = self
+            .send()
+            .contract_call::<IgnoreValue>(caller, callback_endpoint)
+            .with_raw_arguments(callback_args.to_arg_buffer())
+            .with_esdt_transfer(EsdtTokenPayment::new(token_id.clone(), 0, amount.clone()))
+            .execute_on_dest_context();
+
+        let ongoing_flash_loan = self.ongoing_flash_loan().take();
+        require!(
+            ongoing_flash_loan.amount_repaid >= ongoing_flash_loan.amount_to_repay,
+            ERROR_FLASH_LOAN_NOT_REPAID
+        );
+
+        let mut storage_cache = StorageCache::new(self);
+        *storage_cache.get_mut_reserve_in(swap_tokens_order) +=
+            &ongoing_flash_loan.amount_repaid - &amount;
+
+        let new_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        require!(initial_k <= new_k, ERROR_K_INVARIANT_FAILED);
+
+        self.emit_flash_loan_event(&storage_cache, &token_id, &amount, &fee_amount);
+    }
+
+    /// Called by the borrower from within its flash loan callback.
+    /// Can be called more than once, until the whole amount is repaid.
+    #[payable("*")]
+    #[endpoint(repayFlashLoan)]
+    fn repay_flash_loan(&self) {
+        let ongoing_flash_loan_mapper = self.ongoing_flash_loan();
+        require!(
+            !ongoing_flash_loan_mapper.is_empty(),
+            ERROR_NO_FLASH_LOAN_ONGOING
+        );
+
+        let mut ongoing_flash_loan = ongoing_flash_loan_mapper.get();
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == ongoing_flash_loan.borrower,
+            ERROR_NOT_FLASH_LOAN_BORROWER
+        );
+
+        let payment = self.call_value().single_esdt();
+        require!(
+            payment.token_identifier == ongoing_flash_loan.token_id && payment.token_nonce == 0,
+            ERROR_BAD_PAYMENT_TOKENS
+        );
+
+        ongoing_flash_loan.amount_repaid += payment.amount;
+        ongoing_flash_loan_mapper.set(ongoing_flash_loan);
+    }
+
+    #[view(getFlashLoanConfig)]
+    #[storage_mapper("flash_loan_config")]
+    fn flash_loan_config(&self) -> SingleValueMapper<FlashLoanConfig>;
+}
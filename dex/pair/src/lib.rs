@@ -6,15 +6,22 @@ multiversx_sc::derive_imports!();
 mod amm;
 pub mod config;
 mod contexts;
+pub mod dynamic_fee;
 pub mod errors;
 mod events;
 pub mod fee;
+pub mod fee_discount;
+pub mod flash_loan;
+pub mod limit_orders;
 mod liquidity_pool;
 pub mod locking_wrapper;
 pub mod pair_actions;
 pub mod read_pair_storage;
 pub mod safe_price;
 pub mod safe_price_view;
+pub mod stable_swap;
+pub mod twamm;
+pub mod weighted_pool;
 
 use crate::errors::*;
 
@@ -32,11 +39,19 @@ pub trait Pair<ContractReader>:
     + fee::FeeModule
     + liquidity_pool::LiquidityPoolModule
     + config::ConfigModule
+    + stable_swap::StableSwapModule
+    + weighted_pool::WeightedPoolModule
     + token_send::TokenSendModule
     + events::EventsModule
     + read_pair_storage::ReadPairStorageModule
     + safe_price::SafePriceModule
     + safe_price_view::SafePriceViewModule
+    + dynamic_fee::DynamicFeeModule
+    + limit_orders::LimitOrdersModule
+    + flash_loan::FlashLoanModule
+    + twamm::TwammModule
+    + fee_discount::FeeDiscountModule
+    + energy_query::EnergyQueryModule
     + contexts::output_builder::OutputBuilderModule
     + locking_wrapper::LockingWrapperModule
     + permissions_module::PermissionsModule
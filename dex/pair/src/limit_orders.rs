@@ -0,0 +1,312 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::{contexts::base::StorageCache, errors::*, pair_actions::common_methods};
+
+pub const MAX_LIMIT_ORDERS_FILLED_PER_SWAP: usize = 5;
+/// Bounds the sorted insertion, which reads every order on the side of the book
+pub const MAX_LIMIT_ORDERS_PER_SIDE: usize = 100;
+/// An order must sell at least this fraction of the pool's reserve of the sold token
+pub const MIN_LIMIT_ORDER_RESERVE_FRACTION: u64 = 1_000;
+
+pub type LimitOrderId = u64;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub struct LimitOrder<M: ManagedTypeApi> {
+    pub owner: ManagedAddress<M>,
+    pub input_token_id: TokenIdentifier<M>,
+    pub input_amount: BigUint<M>,
+    pub output_token_id: TokenIdentifier<M>,
+    pub min_output_amount: BigUint<M>,
+}
+
+impl<M: ManagedTypeApi> LimitOrder<M> {
+    /// Whether the minimum price asked by this order, output per input,
+    /// is higher than the one asked by the other order
+    pub fn has_higher_limit_than(&self, other: &Self) -> bool {
+        &self.min_output_amount * &other.input_amount
+            > &other.min_output_amount * &self.input_amount
+    }
+}
+
+/// The resting orders are kept sorted by their limit price, lowest first,
+/// so fills only ever look at the front of the book.
+#[multiversx_sc::module]
+pub trait LimitOrdersModule:
+    crate::liquidity_pool::LiquidityPoolModule
+    + crate::amm::AmmModule
+    + crate::stable_swap::StableSwapModule
+    + crate::events::EventsModule
+    + crate::safe_price::SafePriceModule
+    + crate::fee::FeeModule
+    + crate::config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+    + common_methods::CommonMethodsModule
+{
+    /// Deposits one of the pool tokens, to be swapped for the other one as soon as
+    /// the pool can give at least `min_amount_out` for the whole deposit.
+    #[payable("*")]
+    #[endpoint(placeLimitOrder)]
+    fn place_limit_order(&self, min_amount_out: BigUint) -> LimitOrderId {
+        require!(self.can_swap(self.state().get()), ERROR_SWAP_NOT_ENABLED);
+        require!(min_amount_out > 0, ERROR_INVALID_ARGS);
+
+        let payment = self.call_value().single_esdt();
+        require!(payment.amount > 0, ERROR_ZERO_AMOUNT);
+
+        let reserve_in = self.pair_reserve(&payment.token_identifier).get();
+        require!(
+            &payment.amount * MIN_LIMIT_ORDER_RESERVE_FRACTION >= reserve_in,
+            ERROR_LIMIT_ORDER_TOO_SMALL
+        );
+
+        let first_token_id = self.first_token_id().get();
+        let second_token_id = self.second_token_id().get();
+        let output_token_id = if payment.token_identifier == first_token_id {
+            second_token_id
+        } else if payment.token_identifier == second_token_id {
+            first_token_id
+        } else {
+            sc_panic!(ERROR_BAD_PAYMENT_TOKENS);
+        };
+
+        let caller = self.blockchain().get_caller();
+        let order = LimitOrder {
+            owner: caller.clone(),
+            input_token_id: payment.token_identifier,
+            input_amount: payment.amount,
+            output_token_id,
+            min_output_amount: min_amount_out,
+        };
+
+        require!(
+            self.limit_order_ids(&order.input_token_id).len() < MAX_LIMIT_ORDERS_PER_SIDE,
+            ERROR_LIMIT_ORDER_BOOK_FULL
+        );
+
+        let order_id = self.last_limit_order_id().update(|id| {
+            *id += 1;
+            *id
+        });
+        self.limit_order(order_id).set(&order);
+        self.insert_limit_order_id(order_id, &order);
+        self.user_limit_order_ids(&caller).insert(order_id);
+
+        self.emit_place_limit_order_event(order_id, &order);
+
+        order_id
+    }
+
+    #[endpoint(cancelLimitOrder)]
+    fn cancel_limit_order(&self, order_id: LimitOrderId) -> EsdtTokenPayment {
+        let order_mapper = self.limit_order(order_id);
+        require!(!order_mapper.is_empty(), ERROR_LIMIT_ORDER_NOT_FOUND);
+
+        let order = order_mapper.get();
+        let caller = self.blockchain().get_caller();
+        require!(order.owner == caller, ERROR_NOT_LIMIT_ORDER_OWNER);
+
+        self.remove_limit_order(order_id, &order);
+
+        let payment =
+            EsdtTokenPayment::new(order.input_token_id.clone(), 0, order.input_amount.clone());
+        self.send()
+            .direct_esdt(&caller, &payment.token_identifier, 0, &payment.amount);
+
+        self.emit_cancel_limit_order_event(order_id, &order);
+
+        payment
+    }
+
+    /// Keeper entry point. Fills the resting orders selling `input_token_id`
+    /// whose limit is reached at the current pool price. Returns the number of filled orders.
+    #[endpoint(fillLimitOrders)]
+    fn fill_limit_orders_endpoint(
+        &self,
+        input_token_id: TokenIdentifier,
+        max_orders: usize,
+    ) -> usize {
+        self.require_no_flash_loan_ongoing();
+        let mut storage_cache = StorageCache::new(self);
+        require!(
+            self.can_swap(storage_cache.contract_state),
+            ERROR_SWAP_NOT_ENABLED
+        );
+        require!(
+            input_token_id == storage_cache.first_token_id
+                || input_token_id == storage_cache.second_token_id,
+            ERROR_UNKNOWN_TOKEN
+        );
+
+        self.update_safe_price(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            &storage_cache.lp_token_supply,
+        );
+
+        self.fill_limit_orders(&mut storage_cache, &input_token_id, max_orders)
+    }
+
+    /// Fills the orders from the front of the book, until one of them cannot be filled,
+    /// as the ones behind it ask for an even higher price
+    fn fill_limit_orders(
+        &self,
+        storage_cache: &mut StorageCache<Self>,
+        input_token_id: &TokenIdentifier,
+        max_orders: usize,
+    ) -> usize {
+        let order_ids_mapper = self.limit_order_ids(input_token_id);
+        let mut nr_filled_orders = 0;
+        while nr_filled_orders < max_orders {
+            let order_id = match order_ids_mapper.front() {
+                Some(node) => node.into_value(),
+                None => break,
+            };
+
+            let order = self.limit_order(order_id).get();
+            if !self.try_fill_limit_order(storage_cache, order_id, &order) {
+                break;
+            }
+
+            nr_filled_orders += 1;
+        }
+
+        nr_filled_orders
+    }
+
+    fn try_fill_limit_order(
+        &self,
+        storage_cache: &mut StorageCache<Self>,
+        order_id: LimitOrderId,
+        order: &LimitOrder<Self::Api>,
+    ) -> bool {
+        let swap_tokens_order =
+            storage_cache.get_swap_tokens_order(&order.input_token_id, &order.output_token_id);
+        let reserve_in = storage_cache.get_reserve_in(swap_tokens_order);
+        let reserve_out = storage_cache.get_reserve_out(swap_tokens_order);
+        if *reserve_in == 0 || *reserve_out == 0 {
+            return false;
+        }
+
+        let amount_out = self.get_amount_out(
+            &order.input_amount,
+            reserve_in,
+            reserve_out,
+            swap_tokens_order,
+        );
+        if amount_out < order.min_output_amount || amount_out == 0 || &amount_out >= reserve_out {
+            return false;
+        }
+
+        let initial_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+
+        let mut amount_in_after_fee = order.input_amount.clone();
+        let mut fee_amount = BigUint::zero();
+        if self.is_special_fee_taken_on_swap() {
+            fee_amount = self.get_special_fee_from_input(&amount_in_after_fee);
+            amount_in_after_fee -= &fee_amount;
+        }
+
+        *storage_cache.get_mut_reserve_in(swap_tokens_order) += amount_in_after_fee;
+        *storage_cache.get_mut_reserve_out(swap_tokens_order) -= &amount_out;
+
+        let new_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        require!(initial_k <= new_k, ERROR_K_INVARIANT_FAILED);
+
+        if fee_amount > 0 {
+            self.send_fee(
+                storage_cache,
+                swap_tokens_order,
+                &order.input_token_id,
+                &fee_amount,
+            );
+        }
+
+        self.remove_limit_order(order_id, order);
+        self.send()
+            .direct_esdt(&order.owner, &order.output_token_id, 0, &amount_out);
+
+        self.emit_fill_limit_order_event(order_id, order, &amount_out, &fee_amount);
+
+        true
+    }
+
+    /// Inserts the order behind all the ones with the same or a lower limit price
+    fn insert_limit_order_id(&self, order_id: LimitOrderId, order: &LimitOrder<Self::Api>) {
+        let mut order_ids_mapper = self.limit_order_ids(&order.input_token_id);
+        let mut next_node_id_opt = None;
+        for node in order_ids_mapper.iter() {
+            let other_order = self.limit_order(node.get_value_cloned()).get();
+            if other_order.has_higher_limit_than(order) {
+                next_node_id_opt = Some(node.get_node_id());
+                break;
+            }
+        }
+
+        let node_id = match next_node_id_opt {
+            Some(next_node_id) => order_ids_mapper
+                .push_before_node_id(next_node_id, order_id)
+                .unwrap_or_else(|| sc_panic!(ERROR_LIMIT_ORDER_NOT_FOUND))
+                .get_node_id(),
+            None => order_ids_mapper.push_back(order_id).get_node_id(),
+        };
+        self.limit_order_node_id(order_id).set(node_id);
+    }
+
+    fn remove_limit_order(&self, order_id: LimitOrderId, order: &LimitOrder<Self::Api>) {
+        self.limit_order(order_id).clear();
+        let node_id = self.limit_order_node_id(order_id).take();
+        let _ = self
+            .limit_order_ids(&order.input_token_id)
+            .remove_node_by_id(node_id);
+        self.user_limit_order_ids(&order.owner)
+            .swap_remove(&order_id);
+    }
+
+    #[view(getLimitOrder)]
+    fn get_limit_order(&self, order_id: LimitOrderId) -> LimitOrder<Self::Api> {
+        let order_mapper = self.limit_order(order_id);
+        require!(!order_mapper.is_empty(), ERROR_LIMIT_ORDER_NOT_FOUND);
+
+        order_mapper.get()
+    }
+
+    #[view(getUserLimitOrders)]
+    fn get_user_limit_orders(
+        &self,
+        user: ManagedAddress,
+    ) -> MultiValueEncoded<MultiValue2<LimitOrderId, LimitOrder<Self::Api>>> {
+        let mut result = MultiValueEncoded::new();
+        for order_id in self.user_limit_order_ids(&user).iter() {
+            let order = self.limit_order(order_id).get();
+            result.push((order_id, order).into());
+        }
+
+        result
+    }
+
+    #[storage_mapper("limit_order")]
+    fn limit_order(&self, order_id: LimitOrderId) -> SingleValueMapper<LimitOrder<Self::Api>>;
+
+    /// Sorted by limit price, lowest first
+    #[view(getLimitOrderIds)]
+    #[storage_mapper("limit_order_ids")]
+    fn limit_order_ids(&self, input_token_id: &TokenIdentifier) -> LinkedListMapper<LimitOrderId>;
+
+    #[storage_mapper("limit_order_node_id")]
+    fn limit_order_node_id(&self, order_id: LimitOrderId) -> SingleValueMapper<u32>;
+
+    #[storage_mapper("user_limit_order_ids")]
+    fn user_limit_order_ids(&self, user: &ManagedAddress) -> UnorderedSetMapper<LimitOrderId>;
+
+    #[storage_mapper("last_limit_order_id")]
+    fn last_limit_order_id(&self) -> SingleValueMapper<LimitOrderId>;
+}
@@ -1,7 +1,8 @@
 use crate::{
-    contexts::add_liquidity::AddLiquidityContext, StorageCache, ERROR_BAD_PAYMENT_TOKENS,
-    ERROR_INITIAL_LIQUIDITY_NOT_ADDED, ERROR_INVALID_ARGS, ERROR_K_INVARIANT_FAILED,
-    ERROR_LP_TOKEN_NOT_ISSUED, ERROR_NOT_ACTIVE,
+    contexts::{add_liquidity::AddLiquidityContext, base::SwapTokensOrder, swap::SwapContext},
+    StorageCache, ERROR_BAD_PAYMENT_TOKENS, ERROR_INITIAL_LIQUIDITY_NOT_ADDED, ERROR_INVALID_ARGS,
+    ERROR_K_INVARIANT_FAILED, ERROR_LP_TOKEN_NOT_ISSUED, ERROR_NOT_ACTIVE, ERROR_SLIPPAGE_EXCEEDED,
+    ERROR_SWAP_NOT_ENABLED, ERROR_ZERO_AMOUNT,
 };
 
 use super::common_result_types::AddLiquidityResultType;
@@ -12,15 +13,23 @@ multiversx_sc::imports!();
 pub trait AddLiquidityModule:
     crate::liquidity_pool::LiquidityPoolModule
     + crate::amm::AmmModule
+    + crate::stable_swap::StableSwapModule
     + crate::contexts::output_builder::OutputBuilderModule
     + crate::locking_wrapper::LockingWrapperModule
     + crate::events::EventsModule
     + crate::safe_price::SafePriceModule
+    + crate::dynamic_fee::DynamicFeeModule
+    + crate::limit_orders::LimitOrdersModule
+    + crate::fee_discount::FeeDiscountModule
+    + energy_query::EnergyQueryModule
+    + crate::fee::FeeModule
+    + crate::twamm::TwammModule
     + crate::config::ConfigModule
     + token_send::TokenSendModule
     + permissions_module::PermissionsModule
     + pausable::PausableModule
     + super::common_methods::CommonMethodsModule
+    + super::swap::SwapModule
     + utils::UtilsModule
 {
     #[payable("*")]
@@ -35,6 +44,7 @@ pub trait AddLiquidityModule:
             ERROR_INVALID_ARGS
         );
 
+        self.require_no_flash_loan_ongoing();
         let mut storage_cache = StorageCache::new(self);
         let caller = self.blockchain().get_caller();
 
@@ -67,6 +77,9 @@ pub trait AddLiquidityModule:
             &storage_cache.second_token_reserve,
             &storage_cache.lp_token_supply,
         );
+        self.execute_twamm_orders(&mut storage_cache);
+        self.update_dynamic_fee();
+        self.mint_lp_fee(&mut storage_cache);
 
         let initial_k = self.calculate_k_constant(
             &storage_cache.first_token_reserve,
@@ -114,6 +127,7 @@ pub trait AddLiquidityModule:
             self.build_add_liq_output_payments(&storage_cache, &add_liq_context);
         output_payments.push(lp_payment);
 
+        self.update_root_k_last(&storage_cache);
         self.send_multiple_tokens_if_not_zero(&caller, &output_payments);
 
         let output = self.build_add_liq_results(&storage_cache, &add_liq_context);
@@ -122,4 +136,159 @@ pub trait AddLiquidityModule:
 
         output
     }
+
+    /// Adds liquidity using a single pool token. The optimal part of the payment is
+    /// swapped internally for the other token, and the leftovers are sent back.
+    #[payable("*")]
+    #[endpoint(addLiquiditySingleToken)]
+    fn add_liquidity_single_token(&self, min_lp_out: BigUint) -> AddLiquidityResultType<Self::Api> {
+        require!(min_lp_out > 0, ERROR_INVALID_ARGS);
+
+        self.require_no_flash_loan_ongoing();
+        let mut storage_cache = StorageCache::new(self);
+        let caller = self.blockchain().get_caller();
+
+        let payment = self.call_value().single_esdt();
+        require!(payment.amount > 0, ERROR_BAD_PAYMENT_TOKENS);
+        let token_out = if payment.token_identifier == storage_cache.first_token_id {
+            storage_cache.second_token_id.clone()
+        } else if payment.token_identifier == storage_cache.second_token_id {
+            storage_cache.first_token_id.clone()
+        } else {
+            sc_panic!(ERROR_BAD_PAYMENT_TOKENS);
+        };
+        let swap_tokens_order =
+            storage_cache.get_swap_tokens_order(&payment.token_identifier, &token_out);
+
+        require!(
+            self.can_swap(storage_cache.contract_state),
+            ERROR_SWAP_NOT_ENABLED
+        );
+        require!(
+            storage_cache.lp_token_id.is_valid_esdt_identifier(),
+            ERROR_LP_TOKEN_NOT_ISSUED
+        );
+        require!(
+            storage_cache.lp_token_supply != 0,
+            ERROR_INITIAL_LIQUIDITY_NOT_ADDED
+        );
+
+        self.update_safe_price(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            &storage_cache.lp_token_supply,
+        );
+        self.execute_twamm_orders(&mut storage_cache);
+        self.update_dynamic_fee();
+
+        let swap_amount = self.get_single_token_swap_amount(
+            &payment.amount,
+            storage_cache.get_reserve_in(swap_tokens_order),
+            storage_cache.get_reserve_out(swap_tokens_order),
+            swap_tokens_order,
+        );
+        require!(
+            swap_amount > 0 && swap_amount < payment.amount,
+            ERROR_ZERO_AMOUNT
+        );
+
+        let initial_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+
+        let mut swap_context = SwapContext::new(
+            payment.token_identifier.clone(),
+            swap_amount.clone(),
+            token_out,
+            BigUint::from(1u64),
+            swap_tokens_order,
+        );
+        self.perform_swap_fixed_input(&mut swap_context, &mut storage_cache, &caller);
+
+        let new_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        require!(initial_k <= new_k, ERROR_K_INVARIANT_FAILED);
+
+        if swap_context.fee_amount > 0 {
+            self.send_fee(
+                &mut storage_cache,
+                swap_context.swap_tokens_order,
+                &swap_context.input_token_id,
+                &swap_context.fee_amount,
+            );
+        }
+
+        let remaining_payment = EsdtTokenPayment::new(
+            payment.token_identifier.clone(),
+            0,
+            &payment.amount - &swap_amount,
+        );
+        let swap_output_payment = EsdtTokenPayment::new(
+            swap_context.output_token_id.clone(),
+            0,
+            swap_context.final_output_amount.clone(),
+        );
+        let (first_payment, second_payment) = match swap_tokens_order {
+            SwapTokensOrder::PoolOrder => (remaining_payment, swap_output_payment),
+            SwapTokensOrder::ReverseOrder => (swap_output_payment, remaining_payment),
+        };
+
+        // the internal swap counts as a regular swap, so its fee is minted as well
+        self.mint_lp_fee(&mut storage_cache);
+
+        let mut add_liq_context = AddLiquidityContext::new(
+            first_payment,
+            second_payment,
+            BigUint::from(1u64),
+            BigUint::from(1u64),
+        );
+        self.set_optimal_amounts(&mut add_liq_context, &storage_cache);
+
+        let initial_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+
+        add_liq_context.liq_added = self.pool_add_liquidity(
+            &add_liq_context.first_token_optimal_amount,
+            &add_liq_context.second_token_optimal_amount,
+            &mut storage_cache,
+        );
+        require!(
+            add_liq_context.liq_added >= min_lp_out,
+            ERROR_SLIPPAGE_EXCEEDED
+        );
+
+        let new_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        require!(initial_k <= new_k, ERROR_K_INVARIANT_FAILED);
+
+        self.send()
+            .esdt_local_mint(&storage_cache.lp_token_id, 0, &add_liq_context.liq_added);
+
+        let lp_payment = EsdtTokenPayment::new(
+            storage_cache.lp_token_id.clone(),
+            0,
+            add_liq_context.liq_added.clone(),
+        );
+
+        let mut output_payments =
+            self.build_add_liq_output_payments(&storage_cache, &add_liq_context);
+        output_payments.push(lp_payment);
+
+        self.update_root_k_last(&storage_cache);
+        self.send_multiple_tokens_if_not_zero(&caller, &output_payments);
+
+        let output = self.build_add_liq_results(&storage_cache, &add_liq_context);
+
+        self.emit_swap_event(&storage_cache, swap_context);
+        self.emit_add_liquidity_event(&storage_cache, add_liq_context);
+
+        output
+    }
 }
@@ -14,9 +14,11 @@ multiversx_sc::imports!();
 pub trait InitialLiquidityModule:
     crate::liquidity_pool::LiquidityPoolModule
     + crate::amm::AmmModule
+    + crate::stable_swap::StableSwapModule
     + crate::contexts::output_builder::OutputBuilderModule
     + crate::locking_wrapper::LockingWrapperModule
     + crate::events::EventsModule
+    + crate::fee::FeeModule
     + crate::config::ConfigModule
     + token_send::TokenSendModule
     + permissions_module::PermissionsModule
@@ -62,6 +64,7 @@ pub trait InitialLiquidityModule:
             second_token_optimal_amount,
             &mut storage_cache,
         );
+        self.update_root_k_last(&storage_cache);
 
         self.send()
             .esdt_local_mint(&storage_cache.lp_token_id, 0, &liq_added);
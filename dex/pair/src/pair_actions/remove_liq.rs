@@ -1,7 +1,8 @@
 use crate::{
-    contexts::remove_liquidity::RemoveLiquidityContext, StorageCache, SwapTokensOrder,
-    ERROR_BAD_PAYMENT_TOKENS, ERROR_INVALID_ARGS, ERROR_K_INVARIANT_FAILED,
-    ERROR_LP_TOKEN_NOT_ISSUED, ERROR_NOT_ACTIVE, ERROR_NOT_WHITELISTED, ERROR_SLIPPAGE_ON_REMOVE,
+    contexts::{remove_liquidity::RemoveLiquidityContext, swap::SwapContext},
+    StorageCache, SwapTokensOrder, ERROR_BAD_PAYMENT_TOKENS, ERROR_INVALID_ARGS,
+    ERROR_K_INVARIANT_FAILED, ERROR_LP_TOKEN_NOT_ISSUED, ERROR_NOT_ACTIVE, ERROR_NOT_WHITELISTED,
+    ERROR_SLIPPAGE_EXCEEDED, ERROR_SLIPPAGE_ON_REMOVE, ERROR_SWAP_NOT_ENABLED, ERROR_UNKNOWN_TOKEN,
 };
 
 use super::common_result_types::RemoveLiquidityResultType;
@@ -12,16 +13,23 @@ multiversx_sc::imports!();
 pub trait RemoveLiquidityModule:
     crate::liquidity_pool::LiquidityPoolModule
     + crate::amm::AmmModule
+    + crate::stable_swap::StableSwapModule
     + crate::contexts::output_builder::OutputBuilderModule
     + crate::locking_wrapper::LockingWrapperModule
     + crate::events::EventsModule
     + crate::safe_price::SafePriceModule
+    + crate::dynamic_fee::DynamicFeeModule
+    + crate::limit_orders::LimitOrdersModule
+    + crate::fee_discount::FeeDiscountModule
+    + energy_query::EnergyQueryModule
     + crate::fee::FeeModule
+    + crate::twamm::TwammModule
     + crate::config::ConfigModule
     + token_send::TokenSendModule
     + permissions_module::PermissionsModule
     + pausable::PausableModule
     + super::common_methods::CommonMethodsModule
+    + super::swap::SwapModule
     + utils::UtilsModule
 {
     #[payable("*")]
@@ -36,6 +44,7 @@ pub trait RemoveLiquidityModule:
             ERROR_INVALID_ARGS
         );
 
+        self.require_no_flash_loan_ongoing();
         let mut storage_cache = StorageCache::new(self);
         let caller = self.blockchain().get_caller();
         let payment = self.call_value().single_esdt();
@@ -58,6 +67,9 @@ pub trait RemoveLiquidityModule:
             &storage_cache.second_token_reserve,
             &storage_cache.lp_token_supply,
         );
+        self.execute_twamm_orders(&mut storage_cache);
+        self.update_dynamic_fee();
+        self.mint_lp_fee(&mut storage_cache);
 
         let initial_k = self.calculate_k_constant(
             &storage_cache.first_token_reserve,
@@ -96,6 +108,7 @@ pub trait RemoveLiquidityModule:
             ERROR_SLIPPAGE_ON_REMOVE
         );
 
+        self.update_root_k_last(&storage_cache);
         self.send_multiple_tokens_if_not_zero(&caller, &output_payments);
 
         self.emit_remove_liquidity_event(&storage_cache, remove_liq_context);
@@ -103,9 +116,138 @@ pub trait RemoveLiquidityModule:
         self.build_remove_liq_results(output_payments)
     }
 
+    /// Burns the LP tokens and swaps the removed amount of the other pool token
+    /// into `token_out`, so the caller receives a single payment.
+    #[payable("*")]
+    #[endpoint(removeLiquiditySingleToken)]
+    fn remove_liquidity_single_token(
+        &self,
+        token_out: TokenIdentifier,
+        min_amount_out: BigUint,
+    ) -> EsdtTokenPayment {
+        require!(min_amount_out > 0, ERROR_INVALID_ARGS);
+
+        self.require_no_flash_loan_ongoing();
+        let mut storage_cache = StorageCache::new(self);
+        let caller = self.blockchain().get_caller();
+        let payment = self.call_value().single_esdt();
+
+        require!(
+            self.can_swap(storage_cache.contract_state),
+            ERROR_SWAP_NOT_ENABLED
+        );
+        require!(
+            storage_cache.lp_token_id.is_valid_esdt_identifier(),
+            ERROR_LP_TOKEN_NOT_ISSUED
+        );
+        require!(
+            payment.token_identifier == storage_cache.lp_token_id && payment.amount > 0,
+            ERROR_BAD_PAYMENT_TOKENS
+        );
+
+        let token_in = if token_out == storage_cache.first_token_id {
+            storage_cache.second_token_id.clone()
+        } else if token_out == storage_cache.second_token_id {
+            storage_cache.first_token_id.clone()
+        } else {
+            sc_panic!(ERROR_UNKNOWN_TOKEN);
+        };
+        let swap_tokens_order = storage_cache.get_swap_tokens_order(&token_in, &token_out);
+
+        self.update_safe_price(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            &storage_cache.lp_token_supply,
+        );
+        self.execute_twamm_orders(&mut storage_cache);
+        self.update_dynamic_fee();
+        self.mint_lp_fee(&mut storage_cache);
+
+        let initial_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+
+        let mut remove_liq_context =
+            RemoveLiquidityContext::new(payment.amount, BigUint::from(1u64), BigUint::from(1u64));
+        self.pool_remove_liquidity(&mut remove_liq_context, &mut storage_cache);
+
+        let new_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        require!(new_k <= initial_k, ERROR_K_INVARIANT_FAILED);
+
+        self.burn(
+            &storage_cache.lp_token_id,
+            &remove_liq_context.lp_token_payment_amount,
+        );
+
+        // the internal swap counts as a regular swap, so its fee is left for the next mint
+        self.update_root_k_last(&storage_cache);
+
+        let (amount_to_swap, amount_removed_out) = match swap_tokens_order {
+            SwapTokensOrder::PoolOrder => (
+                remove_liq_context.first_token_amount_removed.clone(),
+                remove_liq_context.second_token_amount_removed.clone(),
+            ),
+            SwapTokensOrder::ReverseOrder => (
+                remove_liq_context.second_token_amount_removed.clone(),
+                remove_liq_context.first_token_amount_removed.clone(),
+            ),
+        };
+
+        let initial_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+
+        let mut swap_context = SwapContext::new(
+            token_in,
+            amount_to_swap,
+            token_out,
+            BigUint::from(1u64),
+            swap_tokens_order,
+        );
+        self.perform_swap_fixed_input(&mut swap_context, &mut storage_cache, &caller);
+
+        let new_k = self.calculate_k_constant(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+        );
+        require!(initial_k <= new_k, ERROR_K_INVARIANT_FAILED);
+
+        if swap_context.fee_amount > 0 {
+            self.send_fee(
+                &mut storage_cache,
+                swap_context.swap_tokens_order,
+                &swap_context.input_token_id,
+                &swap_context.fee_amount,
+            );
+        }
+
+        let amount_out = amount_removed_out + &swap_context.final_output_amount;
+        require!(amount_out >= min_amount_out, ERROR_SLIPPAGE_EXCEEDED);
+
+        let output_payment =
+            EsdtTokenPayment::new(swap_context.output_token_id.clone(), 0, amount_out);
+        self.send().direct_esdt(
+            &caller,
+            &output_payment.token_identifier,
+            0,
+            &output_payment.amount,
+        );
+
+        self.emit_remove_liquidity_event(&storage_cache, remove_liq_context);
+        self.emit_swap_event(&storage_cache, swap_context);
+
+        output_payment
+    }
+
     #[payable("*")]
     #[endpoint(removeLiquidityAndBuyBackAndBurnToken)]
     fn remove_liquidity_and_burn_token(&self, token_to_buyback_and_burn: TokenIdentifier) {
+        self.require_no_flash_loan_ongoing();
         let mut storage_cache = StorageCache::new(self);
         let caller = self.blockchain().get_caller();
         let payment = self.call_value().single_esdt();
@@ -125,6 +267,9 @@ pub trait RemoveLiquidityModule:
             &storage_cache.second_token_reserve,
             &storage_cache.lp_token_supply,
         );
+        self.execute_twamm_orders(&mut storage_cache);
+        self.update_dynamic_fee();
+        self.mint_lp_fee(&mut storage_cache);
 
         let mut remove_liq_context =
             RemoveLiquidityContext::new(payment.amount, BigUint::from(1u64), BigUint::from(1u64));
@@ -155,5 +300,7 @@ pub trait RemoveLiquidityModule:
             &dest_address,
             &token_to_buyback_and_burn,
         );
+
+        self.update_root_k_last(&storage_cache);
     }
 }
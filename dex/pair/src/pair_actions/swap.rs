@@ -1,7 +1,7 @@
 use crate::{
-    contexts::swap::SwapContext, StorageCache, ERROR_INVALID_ARGS, ERROR_K_INVARIANT_FAILED,
-    ERROR_NOT_ENOUGH_RESERVE, ERROR_NOT_WHITELISTED, ERROR_SLIPPAGE_EXCEEDED,
-    ERROR_SWAP_NOT_ENABLED, ERROR_ZERO_AMOUNT,
+    contexts::swap::SwapContext, limit_orders::MAX_LIMIT_ORDERS_FILLED_PER_SWAP, StorageCache,
+    ERROR_INVALID_ARGS, ERROR_K_INVARIANT_FAILED, ERROR_NOT_ENOUGH_RESERVE, ERROR_NOT_WHITELISTED,
+    ERROR_SLIPPAGE_EXCEEDED, ERROR_SWAP_NOT_ENABLED, ERROR_ZERO_AMOUNT,
 };
 
 use super::common_result_types::{SwapTokensFixedInputResultType, SwapTokensFixedOutputResultType};
@@ -19,10 +19,16 @@ pub enum SwapType {
 pub trait SwapModule:
     crate::liquidity_pool::LiquidityPoolModule
     + crate::amm::AmmModule
+    + crate::stable_swap::StableSwapModule
     + crate::contexts::output_builder::OutputBuilderModule
     + crate::locking_wrapper::LockingWrapperModule
     + crate::events::EventsModule
     + crate::safe_price::SafePriceModule
+    + crate::dynamic_fee::DynamicFeeModule
+    + crate::fee_discount::FeeDiscountModule
+    + energy_query::EnergyQueryModule
+    + crate::limit_orders::LimitOrdersModule
+    + crate::twamm::TwammModule
     + crate::fee::FeeModule
     + crate::config::ConfigModule
     + token_send::TokenSendModule
@@ -37,6 +43,7 @@ pub trait SwapModule:
         let caller = self.blockchain().get_caller();
         require!(self.whitelist().contains(&caller), ERROR_NOT_WHITELISTED);
 
+        self.require_no_flash_loan_ongoing();
         let mut storage_cache = StorageCache::new(self);
         let payment = self.call_value().single_esdt();
         let swap_tokens_order =
@@ -96,9 +103,13 @@ pub trait SwapModule:
         &self,
         token_out: TokenIdentifier,
         amount_out_min: BigUint,
+        opt_orig_caller: OptionalValue<ManagedAddress>,
     ) -> SwapTokensFixedInputResultType<Self::Api> {
         require!(amount_out_min > 0, ERROR_INVALID_ARGS);
 
+        self.require_no_flash_loan_ongoing();
+        let caller = self.blockchain().get_caller();
+        let fee_discount_user = self.get_fee_discount_user(&caller, opt_orig_caller);
         let mut storage_cache = StorageCache::new(self);
         let payment = self.call_value().single_esdt();
         let swap_tokens_order =
@@ -117,6 +128,8 @@ pub trait SwapModule:
             &storage_cache.second_token_reserve,
             &storage_cache.lp_token_supply,
         );
+        self.execute_twamm_orders(&mut storage_cache);
+        self.update_dynamic_fee();
 
         let initial_k = self.calculate_k_constant(
             &storage_cache.first_token_reserve,
@@ -130,7 +143,7 @@ pub trait SwapModule:
             amount_out_min,
             swap_tokens_order,
         );
-        self.perform_swap_fixed_input(&mut swap_context, &mut storage_cache);
+        self.perform_swap_fixed_input(&mut swap_context, &mut storage_cache, &fee_discount_user);
 
         let new_k = self.calculate_k_constant(
             &storage_cache.first_token_reserve,
@@ -147,7 +160,6 @@ pub trait SwapModule:
             );
         }
 
-        let caller = self.blockchain().get_caller();
         let output_payments = self.build_swap_output_payments(&swap_context);
 
         require!(
@@ -157,8 +169,15 @@ pub trait SwapModule:
 
         self.send_multiple_tokens_if_not_zero(&caller, &output_payments);
 
+        let output_token_id = swap_context.output_token_id.clone();
         self.emit_swap_event(&storage_cache, swap_context);
 
+        self.fill_limit_orders(
+            &mut storage_cache,
+            &output_token_id,
+            MAX_LIMIT_ORDERS_FILLED_PER_SWAP,
+        );
+
         self.build_swap_fixed_input_results(output_payments)
     }
 
@@ -168,9 +187,13 @@ pub trait SwapModule:
         &self,
         token_out: TokenIdentifier,
         amount_out: BigUint,
+        opt_orig_caller: OptionalValue<ManagedAddress>,
     ) -> SwapTokensFixedOutputResultType<Self::Api> {
         require!(amount_out > 0, ERROR_INVALID_ARGS);
 
+        self.require_no_flash_loan_ongoing();
+        let caller = self.blockchain().get_caller();
+        let fee_discount_user = self.get_fee_discount_user(&caller, opt_orig_caller);
         let mut storage_cache = StorageCache::new(self);
         let payment = self.call_value().single_esdt();
         let swap_tokens_order =
@@ -189,6 +212,8 @@ pub trait SwapModule:
             &storage_cache.second_token_reserve,
             &storage_cache.lp_token_supply,
         );
+        self.execute_twamm_orders(&mut storage_cache);
+        self.update_dynamic_fee();
 
         let initial_k = self.calculate_k_constant(
             &storage_cache.first_token_reserve,
@@ -202,7 +227,7 @@ pub trait SwapModule:
             amount_out,
             swap_tokens_order,
         );
-        self.perform_swap_fixed_output(&mut swap_context, &mut storage_cache);
+        self.perform_swap_fixed_output(&mut swap_context, &mut storage_cache, &fee_discount_user);
 
         let new_k = self.calculate_k_constant(
             &storage_cache.first_token_reserve,
@@ -219,13 +244,19 @@ pub trait SwapModule:
             );
         }
 
-        let caller = self.blockchain().get_caller();
         let output_payments = self.build_swap_output_payments(&swap_context);
 
         self.send_multiple_tokens_if_not_zero(&caller, &output_payments);
 
+        let output_token_id = swap_context.output_token_id.clone();
         self.emit_swap_event(&storage_cache, swap_context);
 
+        self.fill_limit_orders(
+            &mut storage_cache,
+            &output_token_id,
+            MAX_LIMIT_ORDERS_FILLED_PER_SWAP,
+        );
+
         self.build_swap_fixed_output_results(output_payments)
     }
 
@@ -233,14 +264,20 @@ pub trait SwapModule:
         &self,
         context: &mut SwapContext<Self::Api>,
         storage_cache: &mut StorageCache<Self>,
+        fee_discount_user: &ManagedAddress,
     ) {
         context.final_input_amount = context.input_token_amount.clone();
 
         let reserve_in = storage_cache.get_reserve_in(context.swap_tokens_order);
         let reserve_out = storage_cache.get_reserve_out(context.swap_tokens_order);
 
-        let amount_out_optimal =
-            self.get_amount_out(&context.input_token_amount, reserve_in, reserve_out);
+        let amount_out_optimal = self.get_amount_out_with_fee(
+            &context.input_token_amount,
+            reserve_in,
+            reserve_out,
+            context.swap_tokens_order,
+            self.get_user_total_fee_percent(fee_discount_user.clone()),
+        );
         require!(
             amount_out_optimal >= context.output_token_amount,
             ERROR_SLIPPAGE_EXCEEDED
@@ -251,7 +288,7 @@ pub trait SwapModule:
         context.final_output_amount = amount_out_optimal;
 
         let mut amount_in_after_fee = context.input_token_amount.clone();
-        if self.is_fee_enabled() {
+        if self.is_special_fee_taken_on_swap() {
             let fee_amount = self.get_special_fee_from_input(&amount_in_after_fee);
             amount_in_after_fee -= &fee_amount;
 
@@ -267,14 +304,20 @@ pub trait SwapModule:
         &self,
         context: &mut SwapContext<Self::Api>,
         storage_cache: &mut StorageCache<Self>,
+        fee_discount_user: &ManagedAddress,
     ) {
         context.final_output_amount = context.output_token_amount.clone();
 
         let reserve_in = storage_cache.get_reserve_in(context.swap_tokens_order);
         let reserve_out = storage_cache.get_reserve_out(context.swap_tokens_order);
 
-        let amount_in_optimal =
-            self.get_amount_in(&context.output_token_amount, reserve_in, reserve_out);
+        let amount_in_optimal = self.get_amount_in_with_fee(
+            &context.output_token_amount,
+            reserve_in,
+            reserve_out,
+            context.swap_tokens_order,
+            self.get_user_total_fee_percent(fee_discount_user.clone()),
+        );
         require!(
             amount_in_optimal <= context.input_token_amount,
             ERROR_SLIPPAGE_EXCEEDED
@@ -284,7 +327,7 @@ pub trait SwapModule:
         context.final_input_amount = amount_in_optimal.clone();
 
         let mut amount_in_optimal_after_fee = amount_in_optimal;
-        if self.is_fee_enabled() {
+        if self.is_special_fee_taken_on_swap() {
             let fee_amount = self.get_special_fee_from_input(&amount_in_optimal_after_fee);
             amount_in_optimal_after_fee -= &fee_amount;
 
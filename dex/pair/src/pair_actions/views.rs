@@ -1,4 +1,7 @@
-use crate::{ERROR_NOT_ENOUGH_RESERVE, ERROR_UNKNOWN_TOKEN, ERROR_ZERO_AMOUNT};
+use crate::{
+    contexts::base::SwapTokensOrder, ERROR_NOT_ENOUGH_RESERVE, ERROR_UNKNOWN_TOKEN,
+    ERROR_ZERO_AMOUNT,
+};
 
 multiversx_sc::imports!();
 
@@ -6,6 +9,7 @@ multiversx_sc::imports!();
 pub trait ViewsModule:
     crate::liquidity_pool::LiquidityPoolModule
     + crate::amm::AmmModule
+    + crate::stable_swap::StableSwapModule
     + crate::contexts::output_builder::OutputBuilderModule
     + crate::locking_wrapper::LockingWrapperModule
     + crate::events::EventsModule
@@ -46,14 +50,22 @@ pub trait ViewsModule:
 
         if token_in == first_token_id {
             require!(second_token_reserve > 0u64, ERROR_NOT_ENOUGH_RESERVE);
-            let amount_out =
-                self.get_amount_out(&amount_in, &first_token_reserve, &second_token_reserve);
+            let amount_out = self.get_amount_out(
+                &amount_in,
+                &first_token_reserve,
+                &second_token_reserve,
+                SwapTokensOrder::PoolOrder,
+            );
             require!(second_token_reserve > amount_out, ERROR_NOT_ENOUGH_RESERVE);
             amount_out
         } else if token_in == second_token_id {
             require!(first_token_reserve > 0u64, ERROR_NOT_ENOUGH_RESERVE);
-            let amount_out =
-                self.get_amount_out(&amount_in, &second_token_reserve, &first_token_reserve);
+            let amount_out = self.get_amount_out(
+                &amount_in,
+                &second_token_reserve,
+                &first_token_reserve,
+                SwapTokensOrder::ReverseOrder,
+            );
             require!(first_token_reserve > amount_out, ERROR_NOT_ENOUGH_RESERVE);
             amount_out
         } else {
@@ -76,14 +88,24 @@ pub trait ViewsModule:
                 ERROR_NOT_ENOUGH_RESERVE
             );
 
-            self.get_amount_in(&amount_wanted, &second_token_reserve, &first_token_reserve)
+            self.get_amount_in(
+                &amount_wanted,
+                &second_token_reserve,
+                &first_token_reserve,
+                SwapTokensOrder::ReverseOrder,
+            )
         } else if token_wanted == second_token_id {
             require!(
                 second_token_reserve > amount_wanted,
                 ERROR_NOT_ENOUGH_RESERVE
             );
 
-            self.get_amount_in(&amount_wanted, &first_token_reserve, &second_token_reserve)
+            self.get_amount_in(
+                &amount_wanted,
+                &first_token_reserve,
+                &second_token_reserve,
+                SwapTokensOrder::PoolOrder,
+            )
         } else {
             sc_panic!(ERROR_UNKNOWN_TOKEN);
         }
@@ -103,9 +125,19 @@ pub trait ViewsModule:
         }
 
         if token_in == first_token_id {
-            self.quote(&amount_in, &first_token_reserve, &second_token_reserve)
+            self.get_equivalent_amount(
+                &amount_in,
+                &first_token_reserve,
+                &second_token_reserve,
+                SwapTokensOrder::PoolOrder,
+            )
         } else if token_in == second_token_id {
-            self.quote(&amount_in, &second_token_reserve, &first_token_reserve)
+            self.get_equivalent_amount(
+                &amount_in,
+                &second_token_reserve,
+                &first_token_reserve,
+                SwapTokensOrder::ReverseOrder,
+            )
         } else {
             sc_panic!(ERROR_UNKNOWN_TOKEN);
         }
@@ -1,15 +1,24 @@
 use multiversx_sc::storage::StorageKey;
 
 use crate::safe_price::PriceObservation;
+use crate::stable_swap::AmplificationRamp;
+use crate::weighted_pool::TokenWeights;
+use pausable::State;
 
 multiversx_sc::imports!();
 
 pub static LP_TOKEN_SUPPLY_STORAGE_KEY: &[u8] = b"lp_token_supply";
+pub static LP_TOKEN_ID_STORAGE_KEY: &[u8] = b"lpTokenIdentifier";
 pub static FIRST_TOKEN_ID_STORAGE_KEY: &[u8] = b"first_token_id";
 pub static SECOND_TOKEN_ID_STORAGE_KEY: &[u8] = b"second_token_id";
 pub static SAFE_PRICE_CURRENT_INDEX_STORAGE_KEY: &[u8] = b"safe_price_current_index";
 pub static PRICE_OBSERVATIONS_STORAGE_KEY: &[u8] = b"price_observations";
 pub static PAIR_RESERVE_BASE_STORAGE_KEY: &[u8] = b"reserve";
+pub static TOTAL_FEE_PERCENT_STORAGE_KEY: &[u8] = b"total_fee_percent";
+pub static SPECIAL_FEE_PERCENT_STORAGE_KEY: &[u8] = b"special_fee_percent";
+pub static PAIR_STATE_STORAGE_KEY: &[u8] = b"state";
+pub static AMPLIFICATION_RAMP_STORAGE_KEY: &[u8] = b"amplification_ramp";
+pub static TOKEN_WEIGHTS_STORAGE_KEY: &[u8] = b"token_weights";
 
 #[multiversx_sc::module]
 pub trait ReadPairStorageModule {
@@ -23,6 +32,16 @@ pub trait ReadPairStorageModule {
         )
     }
 
+    fn get_lp_token_id_mapper(
+        &self,
+        pair_address: ManagedAddress,
+    ) -> SingleValueMapper<TokenIdentifier, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            pair_address,
+            StorageKey::new(LP_TOKEN_ID_STORAGE_KEY),
+        )
+    }
+
     fn get_first_token_id_mapper(
         &self,
         pair_address: ManagedAddress,
@@ -73,4 +92,54 @@ pub trait ReadPairStorageModule {
 
         SingleValueMapper::<_, _, ManagedAddress>::new_from_address(pair_address, storage_key)
     }
+
+    fn get_total_fee_percent_mapper(
+        &self,
+        pair_address: ManagedAddress,
+    ) -> SingleValueMapper<u64, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            pair_address,
+            StorageKey::new(TOTAL_FEE_PERCENT_STORAGE_KEY),
+        )
+    }
+
+    fn get_special_fee_percent_mapper(
+        &self,
+        pair_address: ManagedAddress,
+    ) -> SingleValueMapper<u64, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            pair_address,
+            StorageKey::new(SPECIAL_FEE_PERCENT_STORAGE_KEY),
+        )
+    }
+
+    fn get_pair_state_mapper(
+        &self,
+        pair_address: ManagedAddress,
+    ) -> SingleValueMapper<State, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            pair_address,
+            StorageKey::new(PAIR_STATE_STORAGE_KEY),
+        )
+    }
+
+    fn get_amplification_ramp_mapper(
+        &self,
+        pair_address: ManagedAddress,
+    ) -> SingleValueMapper<AmplificationRamp, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            pair_address,
+            StorageKey::new(AMPLIFICATION_RAMP_STORAGE_KEY),
+        )
+    }
+
+    fn get_token_weights_mapper(
+        &self,
+        pair_address: ManagedAddress,
+    ) -> SingleValueMapper<TokenWeights, ManagedAddress> {
+        SingleValueMapper::<_, _, ManagedAddress>::new_from_address(
+            pair_address,
+            StorageKey::new(TOKEN_WEIGHTS_STORAGE_KEY),
+        )
+    }
 }
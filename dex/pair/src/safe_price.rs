@@ -3,11 +3,22 @@ multiversx_sc::derive_imports!();
 
 use multiversx_sc::codec::{NestedDecodeInput, TopDecodeInput};
 
-use crate::{amm, config, errors::ERROR_SAFE_PRICE_CURRENT_INDEX};
+use crate::{
+    amm, config,
+    errors::{ERROR_BAD_OBSERVATIONS_CAPACITY, ERROR_SAFE_PRICE_CURRENT_INDEX},
+    stable_swap,
+};
 
 pub type Round = u64;
 
 pub const MAX_OBSERVATIONS: usize = 65_536; // 2^{16} records, to optimise binary search
+pub const MIN_OBSERVATIONS_CAPACITY: usize = 10;
+pub const MAX_OBSERVATIONS_CAPACITY: usize = 1_048_576;
+
+pub const LOG_PRICE_FRACTIONAL_BITS: u32 = 32;
+// keeps the accumulated log prices positive, as the price can be lower than 1
+pub const LOG_PRICE_OFFSET: u64 = 1 << 62;
+const EXP2_PRECISION_BITS: u32 = 64;
 
 #[derive(ManagedVecItem, Clone, TopEncode, NestedEncode, TypeAbi, Debug)]
 pub struct PriceObservation<M: ManagedTypeApi> {
@@ -16,6 +27,9 @@ pub struct PriceObservation<M: ManagedTypeApi> {
     pub weight_accumulated: u64,
     pub recording_round: Round,
     pub lp_supply_accumulated: BigUint<M>,
+    pub log_price_accumulated: BigUint<M>,
+    pub log_price_squared_accumulated: BigUint<M>,
+    pub recording_timestamp: u64,
 }
 
 impl<M: ManagedTypeApi> Default for PriceObservation<M> {
@@ -26,6 +40,9 @@ impl<M: ManagedTypeApi> Default for PriceObservation<M> {
             weight_accumulated: 0,
             recording_round: 0,
             lp_supply_accumulated: BigUint::zero(),
+            log_price_accumulated: BigUint::zero(),
+            log_price_squared_accumulated: BigUint::zero(),
+            recording_timestamp: 0,
         }
     }
 }
@@ -53,6 +70,18 @@ impl<M: ManagedTypeApi> NestedDecode for PriceObservation<M> {
             BigUint::zero()
         };
 
+        let (log_price_accumulated, log_price_squared_accumulated) = if !input.is_depleted() {
+            (BigUint::dep_decode(input)?, BigUint::dep_decode(input)?)
+        } else {
+            (BigUint::zero(), BigUint::zero())
+        };
+
+        let recording_timestamp = if !input.is_depleted() {
+            u64::dep_decode(input)?
+        } else {
+            0
+        };
+
         if !input.is_depleted() {
             return Result::Err(DecodeError::INPUT_TOO_LONG);
         }
@@ -63,18 +92,113 @@ impl<M: ManagedTypeApi> NestedDecode for PriceObservation<M> {
             weight_accumulated,
             recording_round,
             lp_supply_accumulated,
+            log_price_accumulated,
+            log_price_squared_accumulated,
+            recording_timestamp,
         })
     }
 }
 
+/// log2(second_token_reserve / first_token_reserve), as a fixed point number
+/// with LOG_PRICE_FRACTIONAL_BITS decimals, shifted by LOG_PRICE_OFFSET
+pub fn compute_log_price<M: ManagedTypeApi>(
+    first_token_reserve: &BigUint<M>,
+    second_token_reserve: &BigUint<M>,
+) -> u64 {
+    LOG_PRICE_OFFSET + log2_fixed_point(second_token_reserve)
+        - log2_fixed_point(first_token_reserve)
+}
+
+/// Binary logarithm of a non-zero value, with LOG_PRICE_FRACTIONAL_BITS decimals
+pub fn log2_fixed_point<M: ManagedTypeApi>(value: &BigUint<M>) -> u64 {
+    let integer_part = value.log2();
+
+    // the mantissa keeps the 64 most significant bits, a number in [1, 2) with 63 decimals
+    let mut mantissa = if integer_part >= 63 {
+        (value >> (integer_part - 63) as usize)
+            .to_u64()
+            .unwrap_or_default()
+    } else {
+        value.to_u64().unwrap_or_default() << (63 - integer_part)
+    };
+
+    let mut result = (integer_part as u64) << LOG_PRICE_FRACTIONAL_BITS;
+    for bit in (0..LOG_PRICE_FRACTIONAL_BITS).rev() {
+        let square = (mantissa as u128) * (mantissa as u128);
+        if square >= 1u128 << 127 {
+            mantissa = (square >> 64) as u64;
+            result |= 1u64 << bit;
+        } else {
+            mantissa = (square >> 63) as u64;
+        }
+    }
+
+    result
+}
+
+/// Computes amount * 2^exponent, for a signed fixed point exponent
+/// with LOG_PRICE_FRACTIONAL_BITS decimals
+pub fn mul_exp2_fixed_point<M: ManagedTypeApi>(amount: &BigUint<M>, exponent: i64) -> BigUint<M> {
+    let integer_part = exponent >> LOG_PRICE_FRACTIONAL_BITS;
+    let fractional_part = (exponent - (integer_part << LOG_PRICE_FRACTIONAL_BITS)) as u64;
+
+    // 2^(2^-k) is obtained by taking k consecutive square roots of 2
+    let one = BigUint::<M>::from(1u64) << EXP2_PRECISION_BITS as usize;
+    let mut root = &one * 2u64;
+    let mut factor = one.clone();
+    for bit in (0..LOG_PRICE_FRACTIONAL_BITS).rev() {
+        root = (&root * &one).sqrt();
+        if fractional_part & (1u64 << bit) != 0 {
+            factor = (factor * &root) >> EXP2_PRECISION_BITS as usize;
+        }
+    }
+
+    let result = amount * &factor;
+    let shift = integer_part - EXP2_PRECISION_BITS as i64;
+    if shift >= 0 {
+        result << shift as usize
+    } else {
+        result >> (-shift) as usize
+    }
+}
+
 #[multiversx_sc::module]
 pub trait SafePriceModule:
     config::ConfigModule
     + token_send::TokenSendModule
     + amm::AmmModule
+    + stable_swap::StableSwapModule
     + permissions_module::PermissionsModule
     + pausable::PausableModule
 {
+    /// Sets the size of the price observations ring, MAX_OBSERVATIONS by default.
+    /// Anyone can grow it, while only the owner can lower it, down to the number of
+    /// observations already stored. The ring grows once its newest observation is the
+    /// last stored one, so the observations always stay ordered.
+    #[endpoint(setPriceObservationsCapacity)]
+    fn set_price_observations_capacity(&self, capacity: usize) {
+        if capacity < self.get_price_observations_capacity() {
+            self.require_caller_has_owner_permissions();
+        }
+        require!(
+            (MIN_OBSERVATIONS_CAPACITY..=MAX_OBSERVATIONS_CAPACITY).contains(&capacity)
+                && capacity >= self.price_observations().len(),
+            ERROR_BAD_OBSERVATIONS_CAPACITY
+        );
+
+        self.price_observations_capacity().set(capacity);
+    }
+
+    #[view(getPriceObservationsCapacity)]
+    fn get_price_observations_capacity(&self) -> usize {
+        let capacity_mapper = self.price_observations_capacity();
+        if capacity_mapper.is_empty() {
+            MAX_OBSERVATIONS
+        } else {
+            capacity_mapper.get()
+        }
+    }
+
     fn update_safe_price(
         &self,
         first_token_reserve: &BigUint,
@@ -87,17 +211,16 @@ pub trait SafePriceModule:
 
         let current_round = self.blockchain().get_block_round();
         let safe_price_current_index = self.safe_price_current_index().get();
+        let mut price_observations = self.price_observations();
+        let nr_observations = price_observations.len();
         require!(
-            safe_price_current_index <= MAX_OBSERVATIONS,
+            safe_price_current_index <= nr_observations,
             ERROR_SAFE_PRICE_CURRENT_INDEX
         );
 
-        let mut price_observations = self.price_observations();
         let mut last_price_observation = PriceObservation::default();
-        let mut new_index = 1;
-        if !price_observations.is_empty() {
+        if nr_observations > 0 {
             last_price_observation = price_observations.get(safe_price_current_index);
-            new_index = (safe_price_current_index % MAX_OBSERVATIONS) + 1;
         }
 
         if last_price_observation.recording_round == current_round {
@@ -106,17 +229,23 @@ pub trait SafePriceModule:
 
         let new_price_observation = self.compute_new_observation(
             current_round,
+            self.blockchain().get_block_timestamp(),
             first_token_reserve,
             second_token_reserve,
             lp_supply,
             &last_price_observation,
         );
 
-        if price_observations.len() == MAX_OBSERVATIONS {
-            price_observations.set(new_index, &new_price_observation);
-        } else {
+        let new_index = if safe_price_current_index == nr_observations
+            && nr_observations < self.get_price_observations_capacity()
+        {
             price_observations.push(&new_price_observation);
-        }
+            nr_observations + 1
+        } else {
+            let new_index = (safe_price_current_index % nr_observations) + 1;
+            price_observations.set(new_index, &new_price_observation);
+            new_index
+        };
 
         self.safe_price_current_index().set(new_index);
     }
@@ -124,6 +253,7 @@ pub trait SafePriceModule:
     fn compute_new_observation(
         &self,
         new_round: Round,
+        new_timestamp: u64,
         new_first_reserve: &BigUint,
         new_second_reserve: &BigUint,
         new_lp_supply: &BigUint,
@@ -141,8 +271,14 @@ pub trait SafePriceModule:
         new_price_observation.second_token_reserve_accumulated +=
             BigUint::from(new_weight) * new_second_reserve;
         new_price_observation.lp_supply_accumulated += BigUint::from(new_weight) * new_lp_supply;
+
+        let log_price = BigUint::from(compute_log_price(new_first_reserve, new_second_reserve));
+        new_price_observation.log_price_squared_accumulated +=
+            BigUint::from(new_weight) * &log_price * &log_price;
+        new_price_observation.log_price_accumulated += BigUint::from(new_weight) * log_price;
         new_price_observation.weight_accumulated += new_weight;
         new_price_observation.recording_round = new_round;
+        new_price_observation.recording_timestamp = new_timestamp;
 
         new_price_observation
     }
@@ -150,6 +286,9 @@ pub trait SafePriceModule:
     #[storage_mapper("price_observations")]
     fn price_observations(&self) -> VecMapper<PriceObservation<Self::Api>>;
 
+    #[storage_mapper("price_observations_capacity")]
+    fn price_observations_capacity(&self) -> SingleValueMapper<usize>;
+
     #[view(getSafePriceCurrentIndex)]
     #[storage_mapper("safe_price_current_index")]
     fn safe_price_current_index(&self) -> SingleValueMapper<usize>;
@@ -5,9 +5,15 @@ use core::cmp::Ordering;
 
 use crate::{
     amm, config,
-    errors::{ERROR_SAFE_PRICE_OBSERVATION_DOES_NOT_EXIST, ERROR_SAFE_PRICE_SAME_ROUNDS},
+    config::MAX_PERCENTAGE,
+    errors::{
+        ERROR_LOG_PRICE_NOT_RECORDED, ERROR_SAFE_PRICE_OBSERVATION_DOES_NOT_EXIST,
+        ERROR_SAFE_PRICE_SAME_ROUNDS,
+    },
     read_pair_storage,
-    safe_price::{self, PriceObservation, Round, MAX_OBSERVATIONS},
+    safe_price::{self, mul_exp2_fixed_point, PriceObservation, Round, LOG_PRICE_OFFSET},
+    stable_swap,
+    weighted_pool::get_weighted_equivalent,
 };
 
 pub const DEFAULT_SAFE_PRICE_ROUNDS_OFFSET: u64 = 10 * 60;
@@ -25,6 +31,7 @@ pub trait SafePriceViewModule:
     + config::ConfigModule
     + token_send::TokenSendModule
     + amm::AmmModule
+    + stable_swap::StableSwapModule
     + permissions_module::PermissionsModule
     + pausable::PausableModule
     + read_pair_storage::ReadPairStorageModule
@@ -70,12 +77,7 @@ pub trait SafePriceViewModule:
         liquidity: BigUint,
     ) -> MultiValue2<EsdtTokenPayment, EsdtTokenPayment> {
         let current_round = self.blockchain().get_block_round();
-        let round_offset = timestamp_offset / SECONDS_PER_ROUND;
-        require!(
-            round_offset > 0 && round_offset < current_round,
-            ERROR_PARAMETERS
-        );
-        let start_round = current_round - round_offset;
+        let start_round = self.get_round_by_timestamp_offset(&pair_address, timestamp_offset);
 
         self.get_lp_tokens_safe_price(pair_address, start_round, current_round, liquidity)
     }
@@ -190,12 +192,7 @@ pub trait SafePriceViewModule:
         input_payment: EsdtTokenPayment,
     ) -> EsdtTokenPayment {
         let current_round = self.blockchain().get_block_round();
-        let round_offset = timestamp_offset / SECONDS_PER_ROUND;
-        require!(
-            round_offset > 0 && round_offset < current_round,
-            ERROR_PARAMETERS
-        );
-        let start_round = current_round - round_offset;
+        let start_round = self.get_round_by_timestamp_offset(&pair_address, timestamp_offset);
         self.get_safe_price(pair_address, start_round, current_round, input_payment)
     }
 
@@ -280,6 +277,231 @@ pub trait SafePriceViewModule:
         )
     }
 
+    #[label("safe-price-view")]
+    #[view(getGeometricSafePriceByRoundOffset)]
+    fn get_geometric_safe_price_by_round_offset(
+        &self,
+        pair_address: ManagedAddress,
+        round_offset: Round,
+        input_payment: EsdtTokenPayment,
+    ) -> EsdtTokenPayment {
+        let current_round = self.blockchain().get_block_round();
+        require!(
+            round_offset > 0 && round_offset < current_round,
+            ERROR_PARAMETERS
+        );
+        let start_round = current_round - round_offset;
+        self.get_geometric_safe_price(pair_address, start_round, current_round, input_payment)
+    }
+
+    #[label("safe-price-view")]
+    #[view(getGeometricSafePriceByTimestampOffset)]
+    fn get_geometric_safe_price_by_timestamp_offset(
+        &self,
+        pair_address: ManagedAddress,
+        timestamp_offset: u64,
+        input_payment: EsdtTokenPayment,
+    ) -> EsdtTokenPayment {
+        let current_round = self.blockchain().get_block_round();
+        let start_round = self.get_round_by_timestamp_offset(&pair_address, timestamp_offset);
+        self.get_geometric_safe_price(pair_address, start_round, current_round, input_payment)
+    }
+
+    /// Time-weighted geometric mean price between the two rounds. Unlike the arithmetic
+    /// reserves average, a short lived price spike only moves it by its log-return share.
+    #[label("safe-price-view")]
+    #[view(getGeometricSafePrice)]
+    fn get_geometric_safe_price(
+        &self,
+        pair_address: ManagedAddress,
+        start_round: Round,
+        end_round: Round,
+        input_payment: EsdtTokenPayment,
+    ) -> EsdtTokenPayment {
+        let (first_price_observation, last_price_observation) =
+            self.get_price_observations_for_window(&pair_address, start_round, end_round);
+        let mean_log_price =
+            self.compute_mean_log_price(&first_price_observation, &last_price_observation);
+
+        let token_weights_mapper = self.get_token_weights_mapper(pair_address.clone());
+        let (first_token_weight, second_token_weight) = if token_weights_mapper.is_empty() {
+            (1, 1)
+        } else {
+            let token_weights = token_weights_mapper.get();
+            (
+                token_weights.first_token_weight,
+                token_weights.second_token_weight,
+            )
+        };
+
+        let first_token_id = self.get_first_token_id_mapper(pair_address.clone()).get();
+        let second_token_id = self.get_second_token_id_mapper(pair_address).get();
+        if input_payment.token_identifier == first_token_id {
+            let output_amount = mul_exp2_fixed_point(&input_payment.amount, mean_log_price)
+                * first_token_weight
+                / second_token_weight;
+            EsdtTokenPayment::new(second_token_id, 0, output_amount)
+        } else if input_payment.token_identifier == second_token_id {
+            let output_amount = mul_exp2_fixed_point(&input_payment.amount, -mean_log_price)
+                * second_token_weight
+                / first_token_weight;
+            EsdtTokenPayment::new(first_token_id, 0, output_amount)
+        } else {
+            sc_panic!(ERROR_BAD_INPUT_TOKEN);
+        }
+    }
+
+    #[label("safe-price-view")]
+    #[view(getSafePriceVolatilityByRoundOffset)]
+    fn get_safe_price_volatility_by_round_offset(
+        &self,
+        pair_address: ManagedAddress,
+        round_offset: Round,
+    ) -> u64 {
+        let current_round = self.blockchain().get_block_round();
+        require!(
+            round_offset > 0 && round_offset < current_round,
+            ERROR_PARAMETERS
+        );
+        let start_round = current_round - round_offset;
+        self.get_safe_price_volatility(pair_address, start_round, current_round)
+    }
+
+    #[label("safe-price-view")]
+    #[view(getSafePriceVolatilityByTimestampOffset)]
+    fn get_safe_price_volatility_by_timestamp_offset(
+        &self,
+        pair_address: ManagedAddress,
+        timestamp_offset: u64,
+    ) -> u64 {
+        let current_round = self.blockchain().get_block_round();
+        let start_round = self.get_round_by_timestamp_offset(&pair_address, timestamp_offset);
+        self.get_safe_price_volatility(pair_address, start_round, current_round)
+    }
+
+    /// Time-weighted standard deviation of the log price between the two rounds,
+    /// returned as the relative price move it stands for, in MAX_PERCENTAGE units
+    #[label("safe-price-view")]
+    #[view(getSafePriceVolatility)]
+    fn get_safe_price_volatility(
+        &self,
+        pair_address: ManagedAddress,
+        start_round: Round,
+        end_round: Round,
+    ) -> u64 {
+        let (first_price_observation, last_price_observation) =
+            self.get_price_observations_for_window(&pair_address, start_round, end_round);
+        let log_price_std_dev =
+            self.compute_log_price_std_dev(&first_price_observation, &last_price_observation);
+
+        let max_percentage = BigUint::from(MAX_PERCENTAGE);
+        let volatility = mul_exp2_fixed_point(&max_percentage, log_price_std_dev) - max_percentage;
+        volatility.to_u64().unwrap_or(u64::MAX)
+    }
+
+    fn get_price_observations_for_window(
+        &self,
+        pair_address: &ManagedAddress,
+        start_round: Round,
+        end_round: Round,
+    ) -> (PriceObservation<Self::Api>, PriceObservation<Self::Api>) {
+        require!(end_round > start_round, ERROR_PARAMETERS);
+
+        let safe_price_current_index = self
+            .get_safe_price_current_index_mapper(pair_address.clone())
+            .get();
+        let price_observations = self.get_price_observation_mapper(pair_address.clone());
+
+        let oldest_price_observation =
+            self.get_oldest_price_observation(safe_price_current_index, &price_observations);
+        require!(
+            oldest_price_observation.recording_round <= start_round,
+            ERROR_SAFE_PRICE_OBSERVATION_DOES_NOT_EXIST
+        );
+
+        let first_token_id = self.get_first_token_id_mapper(pair_address.clone()).get();
+        let second_token_id = self.get_second_token_id_mapper(pair_address.clone()).get();
+        let first_price_observation = self.get_price_observation(
+            pair_address,
+            &first_token_id,
+            &second_token_id,
+            safe_price_current_index,
+            &price_observations,
+            start_round,
+        );
+        let last_price_observation = self.get_price_observation(
+            pair_address,
+            &first_token_id,
+            &second_token_id,
+            safe_price_current_index,
+            &price_observations,
+            end_round,
+        );
+
+        // observations recorded before the log price was tracked have no log accumulators
+        require!(
+            first_price_observation.log_price_accumulated > 0,
+            ERROR_LOG_PRICE_NOT_RECORDED
+        );
+
+        (first_price_observation, last_price_observation)
+    }
+
+    /// Time-weighted mean of log2(second_token_reserve / first_token_reserve),
+    /// with LOG_PRICE_FRACTIONAL_BITS decimals
+    fn compute_mean_log_price(
+        &self,
+        first_price_observation: &PriceObservation<Self::Api>,
+        last_price_observation: &PriceObservation<Self::Api>,
+    ) -> i64 {
+        let weight_diff =
+            last_price_observation.weight_accumulated - first_price_observation.weight_accumulated;
+        require!(weight_diff > 0, ERROR_SAFE_PRICE_SAME_ROUNDS);
+
+        let log_price_diff = &last_price_observation.log_price_accumulated
+            - &first_price_observation.log_price_accumulated;
+        let mean_log_price = (log_price_diff / weight_diff).to_u64().unwrap_or_default();
+
+        mean_log_price as i64 - LOG_PRICE_OFFSET as i64
+    }
+
+    fn compute_log_price_std_dev(
+        &self,
+        first_price_observation: &PriceObservation<Self::Api>,
+        last_price_observation: &PriceObservation<Self::Api>,
+    ) -> i64 {
+        let weight_diff =
+            last_price_observation.weight_accumulated - first_price_observation.weight_accumulated;
+        require!(weight_diff > 0, ERROR_SAFE_PRICE_SAME_ROUNDS);
+
+        let log_price_diff = &last_price_observation.log_price_accumulated
+            - &first_price_observation.log_price_accumulated;
+        let log_price_squared_diff = &last_price_observation.log_price_squared_accumulated
+            - &first_price_observation.log_price_squared_accumulated;
+
+        // variance * weight^2 = weight * sum(log_price^2) - sum(log_price)^2, kept exact
+        let weighted_squares = log_price_squared_diff * weight_diff;
+        let squared_sum = &log_price_diff * &log_price_diff;
+        if weighted_squares <= squared_sum {
+            return 0;
+        }
+
+        let std_dev = (weighted_squares - squared_sum).sqrt() / weight_diff;
+        std_dev.to_u64().unwrap_or_default() as i64
+    }
+
+    #[label("safe-price-view")]
+    #[view(getOldestPriceObservationRound)]
+    fn get_oldest_price_observation_round(&self, pair_address: ManagedAddress) -> Round {
+        let safe_price_current_index = self
+            .get_safe_price_current_index_mapper(pair_address.clone())
+            .get();
+        let price_observations = self.get_price_observation_mapper(pair_address);
+
+        self.get_oldest_price_observation(safe_price_current_index, &price_observations)
+            .recording_round
+    }
+
     fn compute_weighted_price(
         &self,
         pair_address: &ManagedAddress,
@@ -293,15 +515,35 @@ pub trait SafePriceViewModule:
         let weighted_amounts =
             self.compute_weighted_amounts(first_price_observation, last_price_observation);
 
+        // observations keep the raw reserves, so weighted pools scale the price by the weights
+        let token_weights_mapper = self.get_token_weights_mapper(pair_address.clone());
+        let (first_token_weight, second_token_weight) = if token_weights_mapper.is_empty() {
+            (1, 1)
+        } else {
+            let token_weights = token_weights_mapper.get();
+            (
+                token_weights.first_token_weight,
+                token_weights.second_token_weight,
+            )
+        };
+
         if input_payment.token_identifier == first_token_id {
-            let output_amount = input_payment.amount
-                * weighted_amounts.weighted_second_token_reserve
-                / weighted_amounts.weighted_first_token_reserve;
+            let output_amount = get_weighted_equivalent(
+                first_token_weight,
+                second_token_weight,
+                &input_payment.amount,
+                &weighted_amounts.weighted_first_token_reserve,
+                &weighted_amounts.weighted_second_token_reserve,
+            );
             EsdtTokenPayment::new(second_token_id, 0, output_amount)
         } else if input_payment.token_identifier == second_token_id {
-            let output_amount = input_payment.amount
-                * weighted_amounts.weighted_first_token_reserve
-                / weighted_amounts.weighted_second_token_reserve;
+            let output_amount = get_weighted_equivalent(
+                second_token_weight,
+                first_token_weight,
+                &input_payment.amount,
+                &weighted_amounts.weighted_second_token_reserve,
+                &weighted_amounts.weighted_first_token_reserve,
+            );
             EsdtTokenPayment::new(first_token_id, 0, output_amount)
         } else {
             sc_panic!(ERROR_BAD_INPUT_TOKEN);
@@ -345,8 +587,18 @@ pub trait SafePriceViewModule:
                 .get_pair_reserve_mapper(pair_address.clone(), second_token_id)
                 .get();
             let current_lp_supply = self.get_lp_token_supply_mapper(pair_address.clone()).get();
+            let search_timestamp = if last_observation.recording_timestamp == 0 {
+                0
+            } else {
+                let current_timestamp = self.blockchain().get_block_timestamp();
+                last_observation.recording_timestamp
+                    + (current_timestamp - last_observation.recording_timestamp)
+                        * (search_round - last_observation.recording_round)
+                        / (current_round - last_observation.recording_round)
+            };
             return self.compute_new_observation(
                 search_round,
+                search_timestamp,
                 &first_token_reserve,
                 &second_token_reserve,
                 &current_lp_supply,
@@ -383,14 +635,111 @@ pub trait SafePriceViewModule:
             ERROR_SAFE_PRICE_OBSERVATION_DOES_NOT_EXIST
         );
 
-        // VecMapper index starts at 1
-        let mut oldest_observation_index = 1;
-        if price_observations.len() == MAX_OBSERVATIONS {
-            oldest_observation_index = (current_index % MAX_OBSERVATIONS) + 1
-        }
+        // VecMapper index starts at 1. The oldest observation follows the current one,
+        // or is the first one while the ring did not wrap around yet
+        let oldest_observation_index = (current_index % price_observations.len()) + 1;
         price_observations.get(oldest_observation_index)
     }
 
+    /// Converts a timestamp offset into the round it corresponds to, using the
+    /// timestamps recorded in the observations. A timestamp at which an observation
+    /// was recorded resolves exactly to its round. Between two observations the round
+    /// is interpolated linearly, so it is only exact if the round duration did not change
+    /// in between. Falls back to SECONDS_PER_ROUND for observations recorded
+    /// before timestamps were stored.
+    fn get_round_by_timestamp_offset(
+        &self,
+        pair_address: &ManagedAddress,
+        timestamp_offset: u64,
+    ) -> Round {
+        let current_round = self.blockchain().get_block_round();
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        require!(
+            timestamp_offset > 0 && timestamp_offset < current_timestamp,
+            ERROR_PARAMETERS
+        );
+        let search_timestamp = current_timestamp - timestamp_offset;
+
+        let safe_price_current_index = self
+            .get_safe_price_current_index_mapper(pair_address.clone())
+            .get();
+        let price_observations = self.get_price_observation_mapper(pair_address.clone());
+        require!(
+            !price_observations.is_empty(),
+            ERROR_SAFE_PRICE_OBSERVATION_DOES_NOT_EXIST
+        );
+
+        let last_observation = price_observations.get(safe_price_current_index);
+        if last_observation.recording_timestamp == 0 {
+            return self.get_round_by_seconds_per_round(
+                current_round,
+                current_timestamp,
+                search_timestamp,
+            );
+        }
+
+        if last_observation.recording_timestamp <= search_timestamp {
+            if current_timestamp == last_observation.recording_timestamp {
+                return last_observation.recording_round;
+            }
+
+            return last_observation.recording_round
+                + (current_round - last_observation.recording_round)
+                    * (search_timestamp - last_observation.recording_timestamp)
+                    / (current_timestamp - last_observation.recording_timestamp);
+        }
+
+        // binary search over the ring, from the oldest observation to the newest one,
+        // for the last observation recorded at or before the searched timestamp
+        let nr_observations = price_observations.len();
+        let oldest_observation_index = safe_price_current_index % nr_observations;
+        let observation_at = |position: usize| {
+            price_observations.get((oldest_observation_index + position) % nr_observations + 1)
+        };
+
+        let mut left_position = 0;
+        let mut right_position = nr_observations - 1;
+        require!(
+            observation_at(left_position).recording_timestamp <= search_timestamp,
+            ERROR_SAFE_PRICE_OBSERVATION_DOES_NOT_EXIST
+        );
+        while left_position + 1 < right_position {
+            let middle_position = (left_position + right_position) / 2;
+            if observation_at(middle_position).recording_timestamp <= search_timestamp {
+                left_position = middle_position;
+            } else {
+                right_position = middle_position;
+            }
+        }
+
+        let left_observation = observation_at(left_position);
+        let right_observation = observation_at(right_position);
+        if left_observation.recording_timestamp == 0 {
+            return self.get_round_by_seconds_per_round(
+                right_observation.recording_round,
+                right_observation.recording_timestamp,
+                search_timestamp,
+            );
+        }
+
+        left_observation.recording_round
+            + (right_observation.recording_round - left_observation.recording_round)
+                * (search_timestamp - left_observation.recording_timestamp)
+                / (right_observation.recording_timestamp - left_observation.recording_timestamp)
+    }
+
+    fn get_round_by_seconds_per_round(
+        &self,
+        reference_round: Round,
+        reference_timestamp: u64,
+        search_timestamp: u64,
+    ) -> Round {
+        let round_offset = (reference_timestamp - search_timestamp) / SECONDS_PER_ROUND;
+        require!(round_offset < reference_round, ERROR_PARAMETERS);
+
+        reference_round - round_offset
+    }
+
     fn price_observation_by_binary_search(
         &self,
         current_index: usize,
@@ -433,11 +782,11 @@ pub trait SafePriceViewModule:
         let right_observation;
         if last_found_observation.recording_round < search_round {
             left_observation = last_found_observation;
-            let right_observation_index = (search_index % MAX_OBSERVATIONS) + 1;
+            let right_observation_index = (search_index % price_observations.len()) + 1;
             right_observation = price_observations.get(right_observation_index);
         } else {
             let left_observation_index = if search_index == 1 {
-                MAX_OBSERVATIONS
+                price_observations.len()
             } else {
                 search_index - 1
             };
@@ -464,8 +813,33 @@ pub trait SafePriceViewModule:
         let first_token_reserve_accumulated = first_token_reserve_sum / weight_sum;
         let second_token_reserve_accumulated = second_token_reserve_sum / weight_sum;
         let lp_supply_accumulated = lp_supply_sum / weight_sum;
+
+        // the log accumulators can only be interpolated if both observations carry them,
+        // otherwise they are left empty, so the log price views reject the round
+        let both_log_prices_recorded = left_observation.log_price_accumulated > 0
+            && right_observation.log_price_accumulated > 0;
+        let (log_price_accumulated, log_price_squared_accumulated) = if both_log_prices_recorded {
+            let log_price_sum = BigUint::from(left_weight) * left_observation.log_price_accumulated
+                + BigUint::from(right_weight) * right_observation.log_price_accumulated;
+            let log_price_squared_sum = BigUint::from(left_weight)
+                * left_observation.log_price_squared_accumulated
+                + BigUint::from(right_weight) * right_observation.log_price_squared_accumulated;
+            (
+                log_price_sum / weight_sum,
+                log_price_squared_sum / weight_sum,
+            )
+        } else {
+            (BigUint::zero(), BigUint::zero())
+        };
         let weight_accumulated =
             left_observation.weight_accumulated + search_round - left_observation.recording_round;
+        let recording_timestamp = if left_observation.recording_timestamp == 0 {
+            0
+        } else {
+            (left_weight * left_observation.recording_timestamp
+                + right_weight * right_observation.recording_timestamp)
+                / weight_sum
+        };
 
         PriceObservation {
             first_token_reserve_accumulated,
@@ -473,6 +847,9 @@ pub trait SafePriceViewModule:
             weight_accumulated,
             recording_round: search_round,
             lp_supply_accumulated,
+            log_price_accumulated,
+            log_price_squared_accumulated,
+            recording_timestamp,
         }
     }
 
@@ -0,0 +1,251 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::{config, errors::*};
+
+pub const STABLE_SWAP_N_COINS: u64 = 2;
+pub const MIN_AMPLIFICATION: u64 = 1;
+pub const MAX_AMPLIFICATION: u64 = 1_000_000;
+pub const MAX_AMPLIFICATION_CHANGE: u64 = 10;
+pub const MIN_RAMP_DURATION: u64 = 86_400;
+
+const MAX_NEWTON_ITERATIONS: usize = 255;
+
+#[derive(TypeAbi, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct AmplificationRamp {
+    pub initial_amp: u64,
+    pub future_amp: u64,
+    pub initial_timestamp: u64,
+    pub future_timestamp: u64,
+}
+
+impl AmplificationRamp {
+    pub fn new_fixed(amp: u64, timestamp: u64) -> Self {
+        AmplificationRamp {
+            initial_amp: amp,
+            future_amp: amp,
+            initial_timestamp: timestamp,
+            future_timestamp: timestamp,
+        }
+    }
+
+    pub fn get_amp(&self, current_timestamp: u64) -> u64 {
+        if current_timestamp >= self.future_timestamp {
+            return self.future_amp;
+        }
+        if current_timestamp <= self.initial_timestamp {
+            return self.initial_amp;
+        }
+
+        let elapsed = current_timestamp - self.initial_timestamp;
+        let duration = self.future_timestamp - self.initial_timestamp;
+        if self.future_amp > self.initial_amp {
+            self.initial_amp + (self.future_amp - self.initial_amp) * elapsed / duration
+        } else {
+            self.initial_amp - (self.initial_amp - self.future_amp) * elapsed / duration
+        }
+    }
+}
+
+/// A * n^n, the amplification coefficient as it appears in the invariant
+fn compute_ann<M: ManagedTypeApi>(amp: u64) -> BigUint<M> {
+    BigUint::from(amp) * STABLE_SWAP_N_COINS.pow(STABLE_SWAP_N_COINS as u32)
+}
+
+/// Computes the StableSwap invariant D for two reserves, using Newton's method:
+/// A * n^n * (x + y) + D = A * D * n^n + D^(n + 1) / (n^n * x * y)
+pub fn compute_stable_swap_d<M: ManagedTypeApi>(
+    amp: u64,
+    first_reserve: &BigUint<M>,
+    second_reserve: &BigUint<M>,
+) -> BigUint<M> {
+    if *first_reserve == 0u64 || *second_reserve == 0u64 {
+        return BigUint::zero();
+    }
+
+    let n_coins = BigUint::<M>::from(STABLE_SWAP_N_COINS);
+    let ann = compute_ann::<M>(amp);
+    let sum = first_reserve + second_reserve;
+
+    let mut d = sum.clone();
+    for _ in 0..MAX_NEWTON_ITERATIONS {
+        let mut d_p = d.clone();
+        d_p = d_p * &d / (first_reserve * &n_coins);
+        d_p = d_p * &d / (second_reserve * &n_coins);
+
+        let d_prev = d.clone();
+        let numerator = (&ann * &sum + &d_p * &n_coins) * &d;
+        let denominator = (&ann - 1u64) * &d + (&n_coins + 1u64) * &d_p;
+        d = numerator / denominator;
+
+        if is_within_one(&d, &d_prev) {
+            return d;
+        }
+    }
+
+    M::error_api_impl().signal_error(ERROR_STABLE_SWAP_NOT_CONVERGED);
+}
+
+/// Computes the reserve of the other token that keeps the invariant D,
+/// given the new reserve of one of the tokens
+pub fn compute_stable_swap_y<M: ManagedTypeApi>(
+    amp: u64,
+    new_reserve: &BigUint<M>,
+    d: &BigUint<M>,
+) -> BigUint<M> {
+    let n_coins = BigUint::<M>::from(STABLE_SWAP_N_COINS);
+    let ann = compute_ann::<M>(amp);
+
+    let mut c = d * d / (new_reserve * &n_coins);
+    c = c * d / (&ann * &n_coins);
+    let b = new_reserve + &(d / &ann);
+
+    let mut y = d.clone();
+    for _ in 0..MAX_NEWTON_ITERATIONS {
+        let y_prev = y.clone();
+        let numerator = &y * &y + &c;
+        let denominator = &y * 2u64 + &b - d;
+        y = numerator / denominator;
+
+        if is_within_one(&y, &y_prev) {
+            return y;
+        }
+    }
+
+    M::error_api_impl().signal_error(ERROR_STABLE_SWAP_NOT_CONVERGED);
+}
+
+pub fn get_stable_swap_amount_out<M: ManagedTypeApi>(
+    amp: u64,
+    amount_in: &BigUint<M>,
+    reserve_in: &BigUint<M>,
+    reserve_out: &BigUint<M>,
+) -> BigUint<M> {
+    let d = compute_stable_swap_d(amp, reserve_in, reserve_out);
+    let new_reserve_in = reserve_in + amount_in;
+    let new_reserve_out = compute_stable_swap_y(amp, &new_reserve_in, &d) + 1u64;
+    if &new_reserve_out >= reserve_out {
+        return BigUint::zero();
+    }
+
+    reserve_out - &new_reserve_out
+}
+
+pub fn get_stable_swap_amount_in<M: ManagedTypeApi>(
+    amp: u64,
+    amount_out: &BigUint<M>,
+    reserve_in: &BigUint<M>,
+    reserve_out: &BigUint<M>,
+) -> BigUint<M> {
+    if amount_out >= reserve_out {
+        M::error_api_impl().signal_error(ERROR_NOT_ENOUGH_RESERVE);
+    }
+
+    let d = compute_stable_swap_d(amp, reserve_in, reserve_out);
+    let new_reserve_out = reserve_out - amount_out;
+    let new_reserve_in = compute_stable_swap_y(amp, &new_reserve_out, &d) + 1u64;
+    if &new_reserve_in <= reserve_in {
+        return BigUint::zero();
+    }
+
+    new_reserve_in - reserve_in
+}
+
+fn is_within_one<M: ManagedTypeApi>(first: &BigUint<M>, second: &BigUint<M>) -> bool {
+    if first > second {
+        first - second <= 1u64
+    } else {
+        second - first <= 1u64
+    }
+}
+
+#[multiversx_sc::module]
+pub trait StableSwapModule:
+    config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    #[endpoint(setAmplification)]
+    fn set_amplification(&self, amp: u64) {
+        self.require_caller_has_owner_permissions();
+        require!(
+            self.lp_token_supply().get() == 0,
+            ERROR_INITIAL_LIQUIDITY_ALREADY_ADDED
+        );
+        require!(self.token_weights().is_empty(), ERROR_POOL_TYPE_ALREADY_SET);
+        require!(
+            (MIN_AMPLIFICATION..=MAX_AMPLIFICATION).contains(&amp),
+            ERROR_BAD_AMPLIFICATION
+        );
+
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        self.amplification_ramp()
+            .set(AmplificationRamp::new_fixed(amp, current_timestamp));
+    }
+
+    #[endpoint(rampAmplification)]
+    fn ramp_amplification(&self, future_amp: u64, future_timestamp: u64) {
+        self.require_caller_has_owner_permissions();
+        self.require_stable_swap_pool();
+        require!(
+            (MIN_AMPLIFICATION..=MAX_AMPLIFICATION).contains(&future_amp),
+            ERROR_BAD_AMPLIFICATION
+        );
+
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        require!(
+            future_timestamp >= current_timestamp + MIN_RAMP_DURATION,
+            ERROR_BAD_RAMP_TIMESTAMP
+        );
+
+        let current_amp = self.get_current_amplification();
+        require!(
+            future_amp <= current_amp * MAX_AMPLIFICATION_CHANGE
+                && future_amp * MAX_AMPLIFICATION_CHANGE >= current_amp,
+            ERROR_BAD_AMPLIFICATION
+        );
+
+        self.amplification_ramp().set(AmplificationRamp {
+            initial_amp: current_amp,
+            future_amp,
+            initial_timestamp: current_timestamp,
+            future_timestamp,
+        });
+    }
+
+    #[endpoint(stopRampAmplification)]
+    fn stop_ramp_amplification(&self) {
+        self.require_caller_has_owner_permissions();
+        self.require_stable_swap_pool();
+
+        let current_amp = self.get_current_amplification();
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        self.amplification_ramp()
+            .set(AmplificationRamp::new_fixed(current_amp, current_timestamp));
+    }
+
+    #[view(getAmplification)]
+    fn get_current_amplification(&self) -> u64 {
+        let ramp_mapper = self.amplification_ramp();
+        if ramp_mapper.is_empty() {
+            return 0;
+        }
+
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        ramp_mapper.get().get_amp(current_timestamp)
+    }
+
+    #[inline]
+    fn is_stable_swap_pool(&self) -> bool {
+        !self.amplification_ramp().is_empty()
+    }
+
+    fn require_stable_swap_pool(&self) {
+        require!(self.is_stable_swap_pool(), ERROR_NOT_STABLE_SWAP_POOL);
+    }
+
+    #[view(getAmplificationRamp)]
+    #[storage_mapper("amplification_ramp")]
+    fn amplification_ramp(&self) -> SingleValueMapper<AmplificationRamp>;
+}
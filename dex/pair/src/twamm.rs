@@ -0,0 +1,565 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::{
+    contexts::base::{StorageCache, SwapTokensOrder},
+    errors::*,
+    pair_actions::common_methods,
+};
+
+pub const TWAMM_BLOCK_INTERVAL: u64 = 100;
+pub const MAX_TWAMM_ORDER_BLOCKS: u64 = 1_000_000;
+pub const MAX_TWAMM_INTERVALS_PER_EXECUTION: u64 = 100;
+pub const TWAMM_PRECISION: u64 = 1_000_000_000_000_000_000;
+pub const TWAMM_REWARD_FACTOR_PRECISION: u64 = 1_000_000_000_000_000_000;
+
+pub type TwammOrderId = u64;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub struct TwammOrder<M: ManagedTypeApi> {
+    pub owner: ManagedAddress<M>,
+    pub input_token_id: TokenIdentifier<M>,
+    pub input_amount: BigUint<M>,
+    pub output_token_id: TokenIdentifier<M>,
+    /// input tokens sold per block, multiplied by TWAMM_PRECISION
+    pub sale_rate: BigUint<M>,
+    pub start_block: u64,
+    pub expiry_block: u64,
+    /// the input token's reward factor at the last proceeds withdrawal
+    pub reward_factor: BigUint<M>,
+    /// the input token's refund factor at the last proceeds withdrawal
+    pub refund_factor: BigUint<M>,
+}
+
+#[multiversx_sc::module]
+pub trait TwammModule:
+    crate::liquidity_pool::LiquidityPoolModule
+    + crate::amm::AmmModule
+    + crate::stable_swap::StableSwapModule
+    + crate::events::EventsModule
+    + crate::safe_price::SafePriceModule
+    + crate::fee::FeeModule
+    + crate::config::ConfigModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+    + common_methods::CommonMethodsModule
+{
+    /// Deposits one of the pool tokens, to be sold at a constant rate over the next
+    /// `nr_blocks` blocks. The order ends at the first TWAMM_BLOCK_INTERVAL multiple
+    /// after that, and the proceeds can be withdrawn at any time.
+    #[payable("*")]
+    #[endpoint(placeTwammOrder)]
+    fn place_twamm_order(&self, nr_blocks: u64) -> TwammOrderId {
+        require!(
+            nr_blocks > 0 && nr_blocks <= MAX_TWAMM_ORDER_BLOCKS,
+            ERROR_INVALID_ARGS
+        );
+
+        self.require_no_flash_loan_ongoing();
+        let mut storage_cache = StorageCache::new(self);
+        require!(
+            self.can_swap(storage_cache.contract_state),
+            ERROR_SWAP_NOT_ENABLED
+        );
+
+        let payment = self.call_value().single_esdt();
+        require!(payment.amount > 0, ERROR_ZERO_AMOUNT);
+        let output_token_id = if payment.token_identifier == storage_cache.first_token_id {
+            storage_cache.second_token_id.clone()
+        } else if payment.token_identifier == storage_cache.second_token_id {
+            storage_cache.first_token_id.clone()
+        } else {
+            sc_panic!(ERROR_BAD_PAYMENT_TOKENS);
+        };
+
+        self.update_safe_price(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            &storage_cache.lp_token_supply,
+        );
+        self.execute_twamm_orders(&mut storage_cache);
+
+        let current_block = self.blockchain().get_block_nonce();
+        require!(
+            self.twamm_last_executed_block().get() == current_block,
+            ERROR_TWAMM_ORDERS_NOT_EXECUTED
+        );
+
+        let expiry_block = get_next_twamm_interval_block(current_block + nr_blocks - 1);
+        let order_blocks = expiry_block - current_block;
+        let sale_rate = &payment.amount * TWAMM_PRECISION / order_blocks;
+        require!(sale_rate > 0, ERROR_ZERO_AMOUNT);
+
+        // the amount carried so far belongs to the orders already selling, while the
+        // part of the deposit left out by the sale rate's rounding is carried from now on
+        let sale_rate_mapper = self.twamm_sale_rate(&payment.token_identifier);
+        self.credit_twamm_unsold_amount(&payment.token_identifier, &sale_rate_mapper.get());
+        sale_rate_mapper.update(|total_sale_rate| *total_sale_rate += &sale_rate);
+        self.twamm_unsold_amount(&payment.token_identifier)
+            .update(|unsold_amount| {
+                *unsold_amount += &payment.amount * TWAMM_PRECISION - &sale_rate * order_blocks
+            });
+        self.twamm_sale_rate_ending(&payment.token_identifier, expiry_block)
+            .update(|ending_sale_rate| *ending_sale_rate += &sale_rate);
+
+        let caller = self.blockchain().get_caller();
+        let order = TwammOrder {
+            owner: caller.clone(),
+            reward_factor: self.twamm_reward_factor(&payment.token_identifier).get(),
+            refund_factor: self.twamm_refund_factor(&payment.token_identifier).get(),
+            input_token_id: payment.token_identifier,
+            input_amount: payment.amount,
+            output_token_id,
+            sale_rate,
+            start_block: current_block,
+            expiry_block,
+        };
+
+        let order_id = self.last_twamm_order_id().update(|id| {
+            *id += 1;
+            *id
+        });
+        self.twamm_order(order_id).set(&order);
+        self.user_twamm_order_ids(&caller).insert(order_id);
+
+        self.emit_place_twamm_order_event(order_id, &order);
+
+        order_id
+    }
+
+    /// Sends the proceeds accumulated so far, together with the order's share of the
+    /// input tokens that could not be sold. Expired orders are removed.
+    #[endpoint(withdrawTwammProceeds)]
+    fn withdraw_twamm_proceeds(
+        &self,
+        order_id: TwammOrderId,
+    ) -> MultiValue2<EsdtTokenPayment, EsdtTokenPayment> {
+        let mut order = self.get_caller_twamm_order(order_id);
+
+        self.require_no_flash_loan_ongoing();
+        let mut storage_cache = StorageCache::new(self);
+        self.update_safe_price(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            &storage_cache.lp_token_supply,
+        );
+        self.execute_twamm_orders(&mut storage_cache);
+
+        let proceeds = self.claim_twamm_proceeds(&mut order);
+        let refund = self.claim_twamm_refund(&mut order) / TWAMM_PRECISION;
+        if self.twamm_last_executed_block().get() >= order.expiry_block {
+            self.remove_twamm_order(order_id, &order);
+        } else {
+            self.twamm_order(order_id).set(&order);
+        }
+
+        let refund_payment = EsdtTokenPayment::new(order.input_token_id.clone(), 0, refund);
+        let proceeds_payment = EsdtTokenPayment::new(order.output_token_id.clone(), 0, proceeds);
+        self.send()
+            .direct_non_zero_esdt_payment(&order.owner, &refund_payment);
+        self.send()
+            .direct_non_zero_esdt_payment(&order.owner, &proceeds_payment);
+
+        self.emit_withdraw_twamm_order_event(order_id, &order, &proceeds_payment.amount);
+
+        (refund_payment, proceeds_payment).into()
+    }
+
+    /// Stops the order, sending back the unsold input tokens, including the order's share
+    /// of the amount carried forward, together with the proceeds
+    #[endpoint(cancelTwammOrder)]
+    fn cancel_twamm_order(
+        &self,
+        order_id: TwammOrderId,
+    ) -> MultiValue2<EsdtTokenPayment, EsdtTokenPayment> {
+        let mut order = self.get_caller_twamm_order(order_id);
+
+        self.require_no_flash_loan_ongoing();
+        let mut storage_cache = StorageCache::new(self);
+        self.update_safe_price(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            &storage_cache.lp_token_supply,
+        );
+        self.execute_twamm_orders(&mut storage_cache);
+
+        let last_executed_block = self.twamm_last_executed_block().get();
+        let mut unsold_amount = BigUint::zero();
+        if last_executed_block < order.expiry_block {
+            let sale_rate_mapper = self.twamm_sale_rate(&order.input_token_id);
+            self.credit_twamm_unsold_amount(&order.input_token_id, &sale_rate_mapper.get());
+            sale_rate_mapper.update(|total_sale_rate| *total_sale_rate -= &order.sale_rate);
+            self.twamm_sale_rate_ending(&order.input_token_id, order.expiry_block)
+                .update(|ending_sale_rate| *ending_sale_rate -= &order.sale_rate);
+
+            unsold_amount = &order.sale_rate * (order.expiry_block - last_executed_block);
+        }
+        unsold_amount += self.claim_twamm_refund(&mut order);
+        unsold_amount /= TWAMM_PRECISION;
+
+        let proceeds = self.claim_twamm_proceeds(&mut order);
+        self.remove_twamm_order(order_id, &order);
+
+        let unsold_payment = EsdtTokenPayment::new(order.input_token_id.clone(), 0, unsold_amount);
+        let proceeds_payment = EsdtTokenPayment::new(order.output_token_id.clone(), 0, proceeds);
+        self.send()
+            .direct_non_zero_esdt_payment(&order.owner, &unsold_payment);
+        self.send()
+            .direct_non_zero_esdt_payment(&order.owner, &proceeds_payment);
+
+        self.emit_cancel_twamm_order_event(order_id, &order, &proceeds_payment.amount);
+
+        (unsold_payment, proceeds_payment).into()
+    }
+
+    /// Keeper entry point. Settles the virtual orders up to the current block,
+    /// in case the pair was not used for a long time.
+    #[endpoint(executeTwammOrders)]
+    fn execute_twamm_orders_endpoint(&self) {
+        self.require_no_flash_loan_ongoing();
+        let mut storage_cache = StorageCache::new(self);
+        require!(
+            self.can_swap(storage_cache.contract_state),
+            ERROR_SWAP_NOT_ENABLED
+        );
+
+        self.update_safe_price(
+            &storage_cache.first_token_reserve,
+            &storage_cache.second_token_reserve,
+            &storage_cache.lp_token_supply,
+        );
+
+        self.execute_twamm_orders(&mut storage_cache);
+    }
+
+    /// Sells the TWAMM orders against the pool, from the last executed block up to the
+    /// current one, one interval at a time. The amounts sold by the two sides are first
+    /// matched against each other at the spot price, and only the rest is swapped, leaving
+    /// the fee in the reserves. Amounts that could not be sold are carried forward,
+    /// until the orders selling change, and are then credited back to them.
+    fn execute_twamm_orders(&self, storage_cache: &mut StorageCache<Self>) {
+        let current_block = self.blockchain().get_block_nonce();
+        let last_executed_block_mapper = self.twamm_last_executed_block();
+        let mut last_executed_block = last_executed_block_mapper.get();
+        if last_executed_block >= current_block {
+            return;
+        }
+        if last_executed_block == 0 {
+            last_executed_block_mapper.set(current_block);
+            return;
+        }
+
+        let first_token_id = storage_cache.first_token_id.clone();
+        let second_token_id = storage_cache.second_token_id.clone();
+        for _ in 0..MAX_TWAMM_INTERVALS_PER_EXECUTION {
+            if self.twamm_sale_rate(&first_token_id).is_empty()
+                && self.twamm_sale_rate(&second_token_id).is_empty()
+            {
+                last_executed_block = current_block;
+                break;
+            }
+
+            let interval_end_block = get_next_twamm_interval_block(last_executed_block);
+            let execution_end_block = core::cmp::min(interval_end_block, current_block);
+            let nr_blocks = execution_end_block - last_executed_block;
+
+            self.sell_twamm_orders(storage_cache, &first_token_id, &second_token_id, nr_blocks);
+
+            last_executed_block = execution_end_block;
+            if execution_end_block == interval_end_block {
+                self.end_twamm_orders(&first_token_id, interval_end_block);
+                self.end_twamm_orders(&second_token_id, interval_end_block);
+            }
+            if last_executed_block == current_block {
+                break;
+            }
+        }
+
+        last_executed_block_mapper.set(last_executed_block);
+    }
+
+    fn sell_twamm_orders(
+        &self,
+        storage_cache: &mut StorageCache<Self>,
+        first_token_id: &TokenIdentifier,
+        second_token_id: &TokenIdentifier,
+        nr_blocks: u64,
+    ) {
+        let first_sale_rate = self.twamm_sale_rate(first_token_id).get();
+        let second_sale_rate = self.twamm_sale_rate(second_token_id).get();
+        let mut first_amount_in =
+            self.take_twamm_amount_in(first_token_id, &first_sale_rate, nr_blocks);
+        let mut second_amount_in =
+            self.take_twamm_amount_in(second_token_id, &second_sale_rate, nr_blocks);
+        let mut first_proceeds = BigUint::zero();
+        let mut second_proceeds = BigUint::zero();
+
+        if first_amount_in > 0 && second_amount_in > 0 {
+            let second_amount_in_first = self.get_equivalent_amount(
+                &second_amount_in,
+                &storage_cache.second_token_reserve,
+                &storage_cache.first_token_reserve,
+                SwapTokensOrder::ReverseOrder,
+            );
+            if first_amount_in >= second_amount_in_first {
+                first_amount_in -= &second_amount_in_first;
+                first_proceeds += &second_amount_in;
+                second_proceeds += second_amount_in_first;
+                second_amount_in = BigUint::zero();
+            } else {
+                let first_amount_in_second = core::cmp::min(
+                    self.get_equivalent_amount(
+                        &first_amount_in,
+                        &storage_cache.first_token_reserve,
+                        &storage_cache.second_token_reserve,
+                        SwapTokensOrder::PoolOrder,
+                    ),
+                    second_amount_in.clone(),
+                );
+                second_amount_in -= &first_amount_in_second;
+                second_proceeds += &first_amount_in;
+                first_proceeds += first_amount_in_second;
+                first_amount_in = BigUint::zero();
+            }
+        }
+
+        first_proceeds += self.swap_twamm_amount(
+            storage_cache,
+            first_token_id,
+            first_amount_in,
+            SwapTokensOrder::PoolOrder,
+        );
+        second_proceeds += self.swap_twamm_amount(
+            storage_cache,
+            second_token_id,
+            second_amount_in,
+            SwapTokensOrder::ReverseOrder,
+        );
+
+        self.add_twamm_proceeds(first_token_id, &first_proceeds, &first_sale_rate);
+        self.add_twamm_proceeds(second_token_id, &second_proceeds, &second_sale_rate);
+    }
+
+    /// The whole input tokens sold over `nr_blocks`, together with the amount carried forward.
+    /// The fraction left is carried forward again.
+    fn take_twamm_amount_in(
+        &self,
+        input_token_id: &TokenIdentifier,
+        sale_rate: &BigUint,
+        nr_blocks: u64,
+    ) -> BigUint {
+        if *sale_rate == 0 {
+            return BigUint::zero();
+        }
+
+        let unsold_amount_mapper = self.twamm_unsold_amount(input_token_id);
+        let total_amount = sale_rate * nr_blocks + unsold_amount_mapper.get();
+        unsold_amount_mapper.set(&total_amount % TWAMM_PRECISION);
+
+        total_amount / TWAMM_PRECISION
+    }
+
+    /// Swaps the amount against the pool. If the swap is not possible,
+    /// the amount is carried forward.
+    fn swap_twamm_amount(
+        &self,
+        storage_cache: &mut StorageCache<Self>,
+        input_token_id: &TokenIdentifier,
+        amount_in: BigUint,
+        swap_tokens_order: SwapTokensOrder,
+    ) -> BigUint {
+        if amount_in == 0 {
+            return BigUint::zero();
+        }
+
+        let reserve_in = storage_cache.get_reserve_in(swap_tokens_order);
+        let reserve_out = storage_cache.get_reserve_out(swap_tokens_order);
+        let amount_out = if *reserve_in == 0 {
+            BigUint::zero()
+        } else {
+            self.get_amount_out(&amount_in, reserve_in, reserve_out, swap_tokens_order)
+        };
+        if amount_out == 0 || &amount_out >= reserve_out {
+            self.twamm_unsold_amount(input_token_id)
+                .update(|unsold_amount| *unsold_amount += amount_in * TWAMM_PRECISION);
+
+            return BigUint::zero();
+        }
+
+        *storage_cache.get_mut_reserve_in(swap_tokens_order) += amount_in;
+        *storage_cache.get_mut_reserve_out(swap_tokens_order) -= &amount_out;
+
+        amount_out
+    }
+
+    fn add_twamm_proceeds(
+        &self,
+        input_token_id: &TokenIdentifier,
+        proceeds: &BigUint,
+        sale_rate: &BigUint,
+    ) {
+        if *proceeds == 0 {
+            return;
+        }
+
+        self.twamm_reward_factor(input_token_id)
+            .update(|reward_factor| {
+                *reward_factor +=
+                    proceeds * TWAMM_PRECISION * TWAMM_REWARD_FACTOR_PRECISION / sale_rate
+            });
+    }
+
+    fn end_twamm_orders(&self, input_token_id: &TokenIdentifier, block: u64) {
+        let ending_sale_rate = self.twamm_sale_rate_ending(input_token_id, block).take();
+        if ending_sale_rate == 0 {
+            return;
+        }
+
+        let sale_rate_mapper = self.twamm_sale_rate(input_token_id);
+        self.credit_twamm_unsold_amount(input_token_id, &sale_rate_mapper.get());
+        sale_rate_mapper.update(|total_sale_rate| *total_sale_rate -= ending_sale_rate);
+
+        let reward_factor = self.twamm_reward_factor(input_token_id).get();
+        self.twamm_reward_factor_at_block(input_token_id, block)
+            .set(reward_factor);
+        let refund_factor = self.twamm_refund_factor(input_token_id).get();
+        self.twamm_refund_factor_at_block(input_token_id, block)
+            .set(refund_factor);
+    }
+
+    /// Credits the amount carried forward to the orders selling since it was carried,
+    /// proportionally with their sale rates. Called whenever these orders change.
+    fn credit_twamm_unsold_amount(&self, input_token_id: &TokenIdentifier, sale_rate: &BigUint) {
+        if *sale_rate == 0 {
+            return;
+        }
+
+        let unsold_amount = self.twamm_unsold_amount(input_token_id).take();
+        if unsold_amount == 0 {
+            return;
+        }
+
+        self.twamm_refund_factor(input_token_id)
+            .update(|refund_factor| {
+                *refund_factor += unsold_amount * TWAMM_REWARD_FACTOR_PRECISION / sale_rate
+            });
+    }
+
+    fn claim_twamm_proceeds(&self, order: &mut TwammOrder<Self::Api>) -> BigUint {
+        let last_executed_block = self.twamm_last_executed_block().get();
+        let reward_factor = if last_executed_block >= order.expiry_block {
+            self.twamm_reward_factor_at_block(&order.input_token_id, order.expiry_block)
+                .get()
+        } else {
+            self.twamm_reward_factor(&order.input_token_id).get()
+        };
+
+        let proceeds = &order.sale_rate * &(&reward_factor - &order.reward_factor)
+            / TWAMM_PRECISION
+            / TWAMM_REWARD_FACTOR_PRECISION;
+        order.reward_factor = reward_factor;
+
+        proceeds
+    }
+
+    /// The order's share of the amounts credited back, multiplied by TWAMM_PRECISION
+    fn claim_twamm_refund(&self, order: &mut TwammOrder<Self::Api>) -> BigUint {
+        let last_executed_block = self.twamm_last_executed_block().get();
+        let refund_factor = if last_executed_block >= order.expiry_block {
+            self.twamm_refund_factor_at_block(&order.input_token_id, order.expiry_block)
+                .get()
+        } else {
+            self.twamm_refund_factor(&order.input_token_id).get()
+        };
+
+        let refund = &order.sale_rate * &(&refund_factor - &order.refund_factor)
+            / TWAMM_REWARD_FACTOR_PRECISION;
+        order.refund_factor = refund_factor;
+
+        refund
+    }
+
+    fn get_caller_twamm_order(&self, order_id: TwammOrderId) -> TwammOrder<Self::Api> {
+        let order_mapper = self.twamm_order(order_id);
+        require!(!order_mapper.is_empty(), ERROR_TWAMM_ORDER_NOT_FOUND);
+
+        let order = order_mapper.get();
+        let caller = self.blockchain().get_caller();
+        require!(order.owner == caller, ERROR_NOT_TWAMM_ORDER_OWNER);
+
+        order
+    }
+
+    fn remove_twamm_order(&self, order_id: TwammOrderId, order: &TwammOrder<Self::Api>) {
+        self.twamm_order(order_id).clear();
+        self.user_twamm_order_ids(&order.owner)
+            .swap_remove(&order_id);
+    }
+
+    #[view(getTwammOrder)]
+    fn get_twamm_order(&self, order_id: TwammOrderId) -> TwammOrder<Self::Api> {
+        let order_mapper = self.twamm_order(order_id);
+        require!(!order_mapper.is_empty(), ERROR_TWAMM_ORDER_NOT_FOUND);
+
+        order_mapper.get()
+    }
+
+    #[view(getUserTwammOrderIds)]
+    #[storage_mapper("user_twamm_order_ids")]
+    fn user_twamm_order_ids(&self, user: &ManagedAddress) -> UnorderedSetMapper<TwammOrderId>;
+
+    #[storage_mapper("twamm_order")]
+    fn twamm_order(&self, order_id: TwammOrderId) -> SingleValueMapper<TwammOrder<Self::Api>>;
+
+    #[storage_mapper("last_twamm_order_id")]
+    fn last_twamm_order_id(&self) -> SingleValueMapper<TwammOrderId>;
+
+    #[view(getTwammSaleRate)]
+    #[storage_mapper("twamm_sale_rate")]
+    fn twamm_sale_rate(&self, input_token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("twamm_sale_rate_ending")]
+    fn twamm_sale_rate_ending(
+        &self,
+        input_token_id: &TokenIdentifier,
+        block: u64,
+    ) -> SingleValueMapper<BigUint>;
+
+    /// Output tokens earned per input token sold each block,
+    /// multiplied by TWAMM_REWARD_FACTOR_PRECISION
+    #[storage_mapper("twamm_reward_factor")]
+    fn twamm_reward_factor(&self, input_token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    /// Input tokens not sold yet, multiplied by TWAMM_PRECISION
+    #[storage_mapper("twamm_unsold_amount")]
+    fn twamm_unsold_amount(&self, input_token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    /// Input tokens credited back per input token sold each block,
+    /// multiplied by TWAMM_REWARD_FACTOR_PRECISION
+    #[storage_mapper("twamm_refund_factor")]
+    fn twamm_refund_factor(&self, input_token_id: &TokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("twamm_refund_factor_at_block")]
+    fn twamm_refund_factor_at_block(
+        &self,
+        input_token_id: &TokenIdentifier,
+        block: u64,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("twamm_reward_factor_at_block")]
+    fn twamm_reward_factor_at_block(
+        &self,
+        input_token_id: &TokenIdentifier,
+        block: u64,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getTwammLastExecutedBlock)]
+    #[storage_mapper("twamm_last_executed_block")]
+    fn twamm_last_executed_block(&self) -> SingleValueMapper<u64>;
+}
+
+/// The first TWAMM_BLOCK_INTERVAL multiple strictly greater than `block`
+pub fn get_next_twamm_interval_block(block: u64) -> u64 {
+    (block / TWAMM_BLOCK_INTERVAL + 1) * TWAMM_BLOCK_INTERVAL
+}
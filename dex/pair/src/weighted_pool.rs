@@ -0,0 +1,173 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::{config, errors::*, stable_swap};
+
+pub const TOTAL_TOKEN_WEIGHT: u64 = 100;
+pub const TOKEN_WEIGHT_STEP: u64 = 5;
+pub const WEIGHTED_MATH_PRECISION: u64 = 1_000_000_000_000_000_000;
+
+#[derive(
+    TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, Copy, PartialEq, Debug,
+)]
+pub struct TokenWeights {
+    pub first_token_weight: u64,
+    pub second_token_weight: u64,
+}
+
+impl TokenWeights {
+    /// Weights divided by their greatest common divisor, used as exponents
+    /// by the weighted product invariant. E.g. 80/20 becomes 4/1.
+    pub fn get_reduced_weights(&self) -> (u64, u64) {
+        let mut a = self.first_token_weight;
+        let mut b = self.second_token_weight;
+        while b != 0 {
+            let remainder = a % b;
+            a = b;
+            b = remainder;
+        }
+
+        (self.first_token_weight / a, self.second_token_weight / a)
+    }
+}
+
+/// Computes the weighted product invariant x^w1 * y^w2, using the reduced weights
+pub fn compute_weighted_k<M: ManagedTypeApi>(
+    token_weights: &TokenWeights,
+    first_reserve: &BigUint<M>,
+    second_reserve: &BigUint<M>,
+) -> BigUint<M> {
+    let (first_exponent, second_exponent) = token_weights.get_reduced_weights();
+
+    first_reserve.pow(first_exponent as u32) * second_reserve.pow(second_exponent as u32)
+}
+
+/// Weighted geometric mean of the reserves, (x^w1 * y^w2)^(1 / (w1 + w2)),
+/// which grows linearly with the liquidity
+pub fn compute_weighted_liquidity<M: ManagedTypeApi>(
+    token_weights: &TokenWeights,
+    first_reserve: &BigUint<M>,
+    second_reserve: &BigUint<M>,
+) -> BigUint<M> {
+    let (first_exponent, second_exponent) = token_weights.get_reduced_weights();
+    let k = compute_weighted_k(token_weights, first_reserve, second_reserve);
+
+    nth_root_floor(&k, first_exponent + second_exponent)
+}
+
+/// out = reserve_out * (1 - (reserve_in / (reserve_in + amount_in)) ^ (weight_in / weight_out))
+pub fn get_weighted_amount_out<M: ManagedTypeApi>(
+    weight_in: u64,
+    weight_out: u64,
+    amount_in: &BigUint<M>,
+    reserve_in: &BigUint<M>,
+    reserve_out: &BigUint<M>,
+) -> BigUint<M> {
+    let new_reserve_in = reserve_in + amount_in;
+    let ratio_pow = pow_ratio_up(reserve_in, &new_reserve_in, weight_in, weight_out);
+    let precision = BigUint::<M>::from(WEIGHTED_MATH_PRECISION);
+    if ratio_pow >= precision {
+        return BigUint::zero();
+    }
+
+    reserve_out * &(&precision - &ratio_pow) / precision
+}
+
+/// in = reserve_in * ((reserve_out / (reserve_out - amount_out)) ^ (weight_out / weight_in) - 1)
+pub fn get_weighted_amount_in<M: ManagedTypeApi>(
+    weight_in: u64,
+    weight_out: u64,
+    amount_out: &BigUint<M>,
+    reserve_in: &BigUint<M>,
+    reserve_out: &BigUint<M>,
+) -> BigUint<M> {
+    if amount_out >= reserve_out {
+        M::error_api_impl().signal_error(ERROR_NOT_ENOUGH_RESERVE);
+    }
+
+    let new_reserve_out = reserve_out - amount_out;
+    let ratio_pow = pow_ratio_up(reserve_out, &new_reserve_out, weight_out, weight_in);
+    let precision = BigUint::<M>::from(WEIGHTED_MATH_PRECISION);
+
+    reserve_in * &(ratio_pow - &precision) / precision + 1u64
+}
+
+/// Spot price equivalent: amount_in * (reserve_out / weight_out) / (reserve_in / weight_in)
+pub fn get_weighted_equivalent<M: ManagedTypeApi>(
+    weight_in: u64,
+    weight_out: u64,
+    amount_in: &BigUint<M>,
+    reserve_in: &BigUint<M>,
+    reserve_out: &BigUint<M>,
+) -> BigUint<M> {
+    amount_in * reserve_out * weight_in / (reserve_in * weight_out)
+}
+
+/// Computes (numerator / denominator) ^ (exponent_numerator / exponent_denominator),
+/// scaled by WEIGHTED_MATH_PRECISION and rounded up
+fn pow_ratio_up<M: ManagedTypeApi>(
+    numerator: &BigUint<M>,
+    denominator: &BigUint<M>,
+    exponent_numerator: u64,
+    exponent_denominator: u64,
+) -> BigUint<M> {
+    let weights = TokenWeights {
+        first_token_weight: exponent_numerator,
+        second_token_weight: exponent_denominator,
+    };
+    let (power, root) = weights.get_reduced_weights();
+
+    let precision = BigUint::<M>::from(WEIGHTED_MATH_PRECISION);
+    let radicand =
+        numerator.pow(power as u32) * precision.pow(root as u32) / denominator.pow(power as u32);
+
+    nth_root_floor(&radicand, root) + 1u64
+}
+
+fn nth_root_floor<M: ManagedTypeApi>(value: &BigUint<M>, n: u64) -> BigUint<M> {
+    if n == 1 || *value == 0u64 {
+        return value.clone();
+    }
+
+    let start_exponent = value.log2() / (n as u32) + 1;
+    let mut root = BigUint::<M>::from(2u64).pow(start_exponent);
+    loop {
+        let next_root = (&root * (n - 1) + value / &root.pow((n - 1) as u32)) / n;
+        if next_root >= root {
+            return root;
+        }
+
+        root = next_root;
+    }
+}
+
+#[multiversx_sc::module]
+pub trait WeightedPoolModule:
+    config::ConfigModule
+    + stable_swap::StableSwapModule
+    + token_send::TokenSendModule
+    + permissions_module::PermissionsModule
+    + pausable::PausableModule
+{
+    #[endpoint(setTokenWeights)]
+    fn set_token_weights(&self, first_token_weight: u64, second_token_weight: u64) {
+        self.require_caller_has_owner_permissions();
+        require!(
+            self.lp_token_supply().get() == 0,
+            ERROR_INITIAL_LIQUIDITY_ALREADY_ADDED
+        );
+        require!(!self.is_stable_swap_pool(), ERROR_POOL_TYPE_ALREADY_SET);
+        require!(
+            first_token_weight > 0
+                && second_token_weight > 0
+                && first_token_weight + second_token_weight == TOTAL_TOKEN_WEIGHT
+                && first_token_weight.is_multiple_of(TOKEN_WEIGHT_STEP),
+            ERROR_BAD_TOKEN_WEIGHTS
+        );
+
+        self.token_weights().set(TokenWeights {
+            first_token_weight,
+            second_token_weight,
+        });
+    }
+}
@@ -1,6 +1,8 @@
 #![allow(deprecated)]
 
 mod pair_setup;
+use energy_factory_mock::EnergyFactoryMock;
+use energy_query::EnergyQueryModule;
 use fees_collector::{
     config::ConfigModule, fees_accumulation::FeesAccumulationModule, FeesCollector,
 };
@@ -13,18 +15,29 @@ use multiversx_sc::{
         top_encode_to_vec_u8,
     },
     storage::mappers::StorageTokenWrapper,
-    types::{BigUint, EsdtLocalRole, MultiValueEncoded},
+    types::{BigUint, EsdtLocalRole, EsdtTokenPayment, MultiValueEncoded},
 };
 use multiversx_sc_scenario::{
-    managed_address, managed_biguint, managed_token_id, managed_token_id_wrapped, rust_biguint,
-    whitebox_legacy::TxTokenTransfer, DebugApi,
+    managed_address, managed_biguint, managed_buffer, managed_token_id, managed_token_id_wrapped,
+    rust_biguint, whitebox_legacy::TxTokenTransfer, DebugApi,
 };
 use pair::{
-    config::MAX_PERCENTAGE,
+    config::{ConfigModule as PairConfigModule, MAX_PERCENTAGE},
+    dynamic_fee::DynamicFeeModule,
     fee::FeeModule,
+    fee_discount::FeeDiscountModule,
+    flash_loan::{FlashLoanModule, OngoingFlashLoan},
+    limit_orders::{LimitOrdersModule, MAX_LIMIT_ORDERS_PER_SIDE},
     locking_wrapper::LockingWrapperModule,
-    pair_actions::swap::SwapModule,
+    pair_actions::{
+        add_liq::AddLiquidityModule, remove_liq::RemoveLiquidityModule, swap::SwapModule,
+        views::ViewsModule,
+    },
     safe_price::{PriceObservation, Round, SafePriceModule},
+    safe_price_view::SafePriceViewModule,
+    stable_swap::StableSwapModule,
+    twamm::TwammModule,
+    weighted_pool::WeightedPoolModule,
 };
 use pair_setup::*;
 use simple_lock::{
@@ -106,6 +119,1895 @@ fn test_perfect_swap_fixed_output() {
     );
 }
 
+#[test]
+fn test_stable_swap_fixed_input() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_amplification(100);
+            },
+        )
+        .assert_ok();
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    // a constant product pool would only give 90_669 for the same input
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 100_000, MEX_TOKEN_ID, 99_000, 99_650);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_amplification(200);
+            },
+        )
+        .assert_user_error("Initial liquidity was already added");
+}
+
+#[test]
+fn test_stable_swap_fixed_output() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_amplification(100);
+            },
+        )
+        .assert_ok();
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup.swap_fixed_output(WEGLD_TOKEN_ID, 100_000, MEX_TOKEN_ID, 99_000, 651);
+}
+
+#[test]
+fn test_stable_swap_amplification_ramp() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.ramp_amplification(200, 172_800);
+            },
+        )
+        .assert_user_error("Not a stable swap pool");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_amplification(0);
+            },
+        )
+        .assert_user_error("Bad amplification coefficient");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_amplification(100);
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.ramp_amplification(200, 3_600);
+            },
+        )
+        .assert_user_error("Bad amplification ramp timestamp");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.ramp_amplification(1_001, 172_800);
+            },
+        )
+        .assert_user_error("Bad amplification coefficient");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.ramp_amplification(200, 172_800);
+            },
+        )
+        .assert_ok();
+
+    pair_setup.b_mock.set_block_timestamp(86_400);
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(sc.get_current_amplification(), 150);
+        })
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.stop_ramp_amplification();
+            },
+        )
+        .assert_ok();
+
+    pair_setup.b_mock.set_block_timestamp(172_800);
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(sc.get_current_amplification(), 150);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn test_weighted_pool_swap_fixed_input() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_token_weights(80, 20);
+            },
+        )
+        .assert_ok();
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    // with equal reserves, the 80% weighted token is worth 4 times the other one
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let equivalent =
+                sc.get_equivalent(managed_token_id!(WEGLD_TOKEN_ID), managed_biguint!(1_000));
+            assert_eq!(equivalent, managed_biguint!(4_000));
+
+            let amount_out =
+                sc.get_amount_out_view(managed_token_id!(WEGLD_TOKEN_ID), managed_biguint!(1_000));
+            assert_eq!(amount_out, managed_biguint!(3_978));
+        })
+        .assert_ok();
+
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 3_900, 3_978);
+}
+
+#[test]
+fn test_weighted_pool_swap_fixed_output() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_token_weights(80, 20);
+            },
+        )
+        .assert_ok();
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let amount_in =
+                sc.get_amount_in_view(managed_token_id!(MEX_TOKEN_ID), managed_biguint!(900));
+            assert_eq!(amount_in, managed_biguint!(227));
+        })
+        .assert_ok();
+
+    pair_setup.swap_fixed_output(WEGLD_TOKEN_ID, 1_000, MEX_TOKEN_ID, 900, 773);
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |_sc| {
+            let _ = pair::weighted_pool::get_weighted_amount_in::<DebugApi>(
+                20,
+                80,
+                &managed_biguint!(1_001_000),
+                &managed_biguint!(1_001_000),
+                &managed_biguint!(1_001_000),
+            );
+        })
+        .assert_user_error("Not enough reserve");
+}
+
+#[test]
+fn test_weighted_pool_bad_weights() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_token_weights(80, 30);
+            },
+        )
+        .assert_user_error("Bad token weights");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_token_weights(83, 17);
+            },
+        )
+        .assert_user_error("Bad token weights");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_amplification(100);
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_token_weights(80, 20);
+            },
+        )
+        .assert_user_error("Pool type already set");
+}
+
+#[test]
+fn test_dynamic_fee() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_dynamic_fee_config(300, 1_000, 2, 10_000);
+            },
+        )
+        .assert_user_error("Bad dynamic fee config");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_dynamic_fee_config(300, 1_000, 5, 10_000);
+            },
+        )
+        .assert_ok();
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup.b_mock.set_block_round(10);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 100_000, MEX_TOKEN_ID, 1, 90_669);
+
+    pair_setup.b_mock.set_block_round(20);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 100_000, MEX_TOKEN_ID, 1, 75_589);
+
+    // not enough observations yet, the fee stays at the lower bound
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(sc.get_volatility_view(), 0);
+            assert_eq!(sc.total_fee_percent().get(), 300);
+        })
+        .assert_ok();
+
+    // the volatility is above the upper bound, so the swap is charged the maximum fee
+    pair_setup.b_mock.set_block_round(30);
+    pair_setup.swap_fixed_input(MEX_TOKEN_ID, 150_000, WEGLD_TOKEN_ID, 1, 181_388);
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(sc.get_volatility_view(), 15_938);
+            assert_eq!(sc.total_fee_percent().get(), 1_000);
+            assert_eq!(sc.dynamic_fee_last_update_round().get(), 30);
+        })
+        .assert_ok();
+
+    // adding liquidity also refreshes the fee, once per round
+    pair_setup.b_mock.set_block_round(35);
+    pair_setup.add_liquidity(1_000, 1, 1_000, 1, 980, 1_000, 965);
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(sc.dynamic_fee_last_update_round().get(), 35);
+        })
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_fee_percent(400, 50);
+            },
+        )
+        .assert_user_error("Dynamic fee mode is enabled");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.disable_dynamic_fee();
+            },
+        )
+        .assert_ok();
+
+    // the fee set before enabling the dynamic fee mode is restored
+    pair_setup.b_mock.set_block_round(40);
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert!(sc.dynamic_fee_config().is_empty());
+            assert_eq!(sc.total_fee_percent().get(), 300);
+        })
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_fee_percent(400, 50);
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn test_limit_orders() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(10_000),
+            |sc| {
+                let order_id = sc.place_limit_order(managed_biguint!(11_000));
+                assert_eq!(order_id, 1);
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(10_000),
+            |sc| {
+                let order_id = sc.place_limit_order(managed_biguint!(20_000));
+                assert_eq!(order_id, 2);
+            },
+        )
+        .assert_ok();
+
+    // the pool only gives 9_871 WEGLD for 10_000 MEX, none of the orders can be filled yet
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                let nr_filled_orders =
+                    sc.fill_limit_orders_endpoint(managed_token_id!(MEX_TOKEN_ID), 10);
+                assert_eq!(nr_filled_orders, 0);
+            },
+        )
+        .assert_ok();
+
+    // buying MEX moves the price above the first order's limit, which is filled right away
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 100_000, MEX_TOKEN_ID, 1, 90_669);
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let _ = sc.get_limit_order(1);
+        })
+        .assert_user_error("Limit order not found");
+
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_WEGLD_TOKENS - 1_001_000 - 100_000 + 11_927),
+    );
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_MEX_TOKENS - 1_001_000 - 20_000 + 90_669),
+    );
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                let _ = sc.cancel_limit_order(2);
+            },
+        )
+        .assert_user_error("Not the limit order owner");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                let payment = sc.cancel_limit_order(2);
+                assert_eq!(payment.token_identifier, managed_token_id!(MEX_TOKEN_ID));
+                assert_eq!(payment.amount, managed_biguint!(10_000));
+            },
+        )
+        .assert_ok();
+
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_MEX_TOKENS - 1_001_000 - 10_000 + 90_669),
+    );
+}
+
+#[test]
+fn test_limit_orders_sorted_by_limit_price() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    for min_amount_out in [20_000u64, 11_000, 15_000, 11_000] {
+        pair_setup
+            .b_mock
+            .execute_esdt_transfer(
+                &pair_setup.user_address,
+                &pair_setup.pair_wrapper,
+                MEX_TOKEN_ID,
+                0,
+                &rust_biguint!(10_000),
+                |sc| {
+                    let _ = sc.place_limit_order(managed_biguint!(min_amount_out));
+                },
+            )
+            .assert_ok();
+    }
+
+    // dust orders are rejected, so they cannot clog the front of the book
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(1_000),
+            |sc| {
+                let _ = sc.place_limit_order(managed_biguint!(1));
+            },
+        )
+        .assert_user_error("Limit order too small");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                let _ = sc.cancel_limit_order(3);
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let order_ids: Vec<u64> = sc
+                .limit_order_ids(&managed_token_id!(MEX_TOKEN_ID))
+                .iter()
+                .map(|node| node.into_value())
+                .collect();
+            assert_eq!(order_ids, vec![2, 4, 1]);
+        })
+        .assert_ok();
+
+    // the book size is capped per side
+    for _ in 3..MAX_LIMIT_ORDERS_PER_SIDE {
+        pair_setup
+            .b_mock
+            .execute_esdt_transfer(
+                &pair_setup.user_address,
+                &pair_setup.pair_wrapper,
+                MEX_TOKEN_ID,
+                0,
+                &rust_biguint!(10_000),
+                |sc| {
+                    let _ = sc.place_limit_order(managed_biguint!(30_000));
+                },
+            )
+            .assert_ok();
+    }
+
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(10_000),
+            |sc| {
+                let _ = sc.place_limit_order(managed_biguint!(30_000));
+            },
+        )
+        .assert_user_error("Limit order book full");
+
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(10_000),
+            |sc| {
+                let _ = sc.place_limit_order(managed_biguint!(30_000));
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn test_add_liquidity_single_token() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    // 48_883 WEGLD are swapped for 46_473 MEX, the rest is added at the new pool ratio
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let (lp_payment, first_payment, second_payment) = sc
+                    .add_liquidity_single_token(managed_biguint!(48_000))
+                    .into_tuple();
+                assert_eq!(lp_payment.amount, managed_biguint!(48_735));
+                assert_eq!(first_payment.amount, managed_biguint!(51_115));
+                assert_eq!(second_payment.amount, managed_biguint!(46_473));
+            },
+        )
+        .assert_ok();
+
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        LP_TOKEN_ID,
+        &rust_biguint!(1_000_000 + 48_735),
+    );
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_WEGLD_TOKENS - 1_001_000 - 100_000 + 2),
+    );
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_MEX_TOKENS - 1_001_000),
+    );
+
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let _ = sc.add_liquidity_single_token(managed_biguint!(100_000));
+            },
+        )
+        .assert_user_error("Slippage exceeded");
+}
+
+#[test]
+fn test_add_liquidity_single_token_weighted_pool() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_token_weights(80, 20);
+            },
+        )
+        .assert_ok();
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    // the swapped part is found against the weighted invariant, so the whole deposit is added
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let (lp_payment, first_payment, second_payment) = sc
+                    .add_liquidity_single_token(managed_biguint!(1))
+                    .into_tuple();
+                assert_eq!(lp_payment.amount, managed_biguint!(79_181));
+                assert_eq!(first_payment.amount, managed_biguint!(80_708));
+                assert_eq!(second_payment.amount, managed_biguint!(73_377));
+            },
+        )
+        .assert_ok();
+
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_WEGLD_TOKENS - 1_001_000 - 100_000),
+    );
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_MEX_TOKENS - 1_001_000),
+    );
+}
+
+#[test]
+fn test_remove_liquidity_single_token() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            LP_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let _ = sc.remove_liquidity_single_token(
+                    managed_token_id!(WEGLD_TOKEN_ID),
+                    managed_biguint!(190_000),
+                );
+            },
+        )
+        .assert_user_error("Slippage exceeded");
+
+    // 100_000 WEGLD are removed directly, the 100_000 MEX removed are swapped for 89_766 WEGLD
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            LP_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let output_payment = sc.remove_liquidity_single_token(
+                    managed_token_id!(WEGLD_TOKEN_ID),
+                    managed_biguint!(189_000),
+                );
+                assert_eq!(
+                    output_payment.token_identifier,
+                    managed_token_id!(WEGLD_TOKEN_ID)
+                );
+                assert_eq!(output_payment.amount, managed_biguint!(189_766));
+            },
+        )
+        .assert_ok();
+
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        LP_TOKEN_ID,
+        &rust_biguint!(1_000_000 - 100_000),
+    );
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_WEGLD_TOKENS - 1_001_000 + 189_766),
+    );
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_MEX_TOKENS - 1_001_000),
+    );
+}
+
+#[test]
+fn test_flash_loan_config() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.flash_loan(
+                    managed_token_id!(WEGLD_TOKEN_ID),
+                    managed_biguint!(100_000),
+                    managed_buffer!(b"callback"),
+                    MultiValueEncoded::new(),
+                );
+            },
+        )
+        .assert_user_error("Flash loans are disabled");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_flash_loan_config(0, true);
+            },
+        )
+        .assert_user_error("Bad percents");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_flash_loan_config(90, true);
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.flash_loan(
+                    managed_token_id!(WEGLD_TOKEN_ID),
+                    managed_biguint!(100_000),
+                    managed_buffer!(b"callback"),
+                    MultiValueEncoded::new(),
+                );
+            },
+        )
+        .assert_user_error("Not whitelisted");
+
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                sc.repay_flash_loan();
+            },
+        )
+        .assert_user_error("No flash loan ongoing");
+
+    let owner_address = pair_setup.owner_address.clone();
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.ongoing_flash_loan().set(OngoingFlashLoan {
+                    borrower: managed_address!(&owner_address),
+                    token_id: managed_token_id!(WEGLD_TOKEN_ID),
+                    amount_to_repay: managed_biguint!(100_090),
+                    amount_repaid: managed_biguint!(0),
+                });
+            },
+        )
+        .assert_ok();
+
+    // only the borrower can repay, so nobody else can take over the loan
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(100_090),
+            |sc| {
+                sc.repay_flash_loan();
+            },
+        )
+        .assert_user_error("Not the flash loan borrower");
+
+    let user_address = pair_setup.user_address.clone();
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.ongoing_flash_loan().update(|ongoing_flash_loan| {
+                    ongoing_flash_loan.borrower = managed_address!(&user_address)
+                });
+            },
+        )
+        .assert_ok();
+
+    // repaying in the other token would turn the loan into a swap without fee
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_090),
+            |sc| {
+                sc.repay_flash_loan();
+            },
+        )
+        .assert_user_error("Bad payment tokens");
+
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(50_000),
+            |sc| {
+                sc.repay_flash_loan();
+                assert_eq!(
+                    sc.ongoing_flash_loan().get().amount_repaid,
+                    managed_biguint!(50_000)
+                );
+                // the repayment is only added to the reserve once the loan ends
+                assert_eq!(
+                    sc.pair_reserve(&managed_token_id!(WEGLD_TOKEN_ID)).get(),
+                    managed_biguint!(1_001_000)
+                );
+            },
+        )
+        .assert_ok();
+
+    // the pool is locked until the loan is repaid
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(10_000),
+            |sc| {
+                let _ = sc.swap_tokens_fixed_input(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_biguint!(1),
+                    OptionalValue::None,
+                );
+            },
+        )
+        .assert_user_error("Flash loan ongoing");
+}
+
+#[test]
+fn test_geometric_safe_price_and_volatility() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let pair_address = pair_setup.pair_wrapper.address_ref().clone();
+
+    pair_setup.b_mock.set_block_round(10);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup.b_mock.set_block_round(20);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 100_000, MEX_TOKEN_ID, 1, 90_669);
+
+    pair_setup.b_mock.set_block_round(30);
+    pair_setup.swap_fixed_input(MEX_TOKEN_ID, 100_000, WEGLD_TOKEN_ID, 1, 108_679);
+
+    pair_setup.b_mock.set_block_round(40);
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            // a single interval, priced at 910_331 MEX for 1_101_000 WEGLD
+            let output_payment = sc.get_geometric_safe_price(
+                managed_address!(&pair_address),
+                20,
+                30,
+                EsdtTokenPayment::new(
+                    managed_token_id!(WEGLD_TOKEN_ID),
+                    0,
+                    managed_biguint!(1_000),
+                ),
+            );
+            assert_eq!(
+                output_payment.token_identifier,
+                managed_token_id!(MEX_TOKEN_ID)
+            );
+            assert_eq!(output_payment.amount, managed_biguint!(826));
+
+            let output_payment = sc.get_geometric_safe_price(
+                managed_address!(&pair_address),
+                20,
+                30,
+                EsdtTokenPayment::new(managed_token_id!(MEX_TOKEN_ID), 0, managed_biguint!(1_000)),
+            );
+            assert_eq!(
+                output_payment.token_identifier,
+                managed_token_id!(WEGLD_TOKEN_ID)
+            );
+            assert_eq!(output_payment.amount, managed_biguint!(1_209));
+
+            let volatility = sc.get_safe_price_volatility(managed_address!(&pair_address), 20, 30);
+            assert_eq!(volatility, 0);
+
+            // the geometric mean of 910_331 / 1_101_000 and 1_010_331 / 992_321
+            let output_payment = sc.get_geometric_safe_price(
+                managed_address!(&pair_address),
+                20,
+                40,
+                EsdtTokenPayment::new(
+                    managed_token_id!(WEGLD_TOKEN_ID),
+                    0,
+                    managed_biguint!(1_000),
+                ),
+            );
+            assert_eq!(output_payment.amount, managed_biguint!(917));
+
+            let volatility =
+                sc.get_safe_price_volatility_by_round_offset(managed_address!(&pair_address), 20);
+            assert_eq!(volatility, 10_968);
+        })
+        .assert_ok();
+
+    // an observation recorded before the log price was tracked
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut price_observations = sc.price_observations();
+                let mut price_observation = price_observations.get(2);
+                price_observation.log_price_accumulated = BigUint::zero();
+                price_observation.log_price_squared_accumulated = BigUint::zero();
+                price_observations.set(2, &price_observation);
+            },
+        )
+        .assert_ok();
+
+    // it cannot be interpolated with a newer one
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let _ = sc.get_geometric_safe_price(
+                managed_address!(&pair_address),
+                25,
+                40,
+                EsdtTokenPayment::new(
+                    managed_token_id!(WEGLD_TOKEN_ID),
+                    0,
+                    managed_biguint!(1_000),
+                ),
+            );
+        })
+        .assert_user_error("Log price not recorded for the given rounds");
+}
+
+#[test]
+fn test_safe_price_by_timestamp_offset() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let pair_address = pair_setup.pair_wrapper.address_ref().clone();
+
+    // rounds last 10 seconds here, instead of the default 6
+    pair_setup.b_mock.set_block_round(10);
+    pair_setup.b_mock.set_block_timestamp(50);
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup.b_mock.set_block_round(20);
+    pair_setup.b_mock.set_block_timestamp(100);
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 100_000, MEX_TOKEN_ID, 1, 90_669);
+
+    pair_setup.b_mock.set_block_round(30);
+    pair_setup.b_mock.set_block_timestamp(200);
+    pair_setup.swap_fixed_input(MEX_TOKEN_ID, 100_000, WEGLD_TOKEN_ID, 1, 108_679);
+
+    pair_setup.b_mock.set_block_round(40);
+    pair_setup.b_mock.set_block_timestamp(300);
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let price_observation =
+                sc.get_price_observation_view(managed_address!(&pair_address), 30);
+            assert_eq!(price_observation.recording_timestamp, 200);
+
+            let input_payment = EsdtTokenPayment::new(
+                managed_token_id!(WEGLD_TOKEN_ID),
+                0,
+                managed_biguint!(1_000),
+            );
+
+            // 200 seconds ago is exactly round 20
+            let output_payment = sc.get_safe_price_by_timestamp_offset(
+                managed_address!(&pair_address),
+                200,
+                input_payment.clone(),
+            );
+            assert_eq!(output_payment.amount, managed_biguint!(917));
+            assert_eq!(
+                output_payment,
+                sc.get_safe_price(
+                    managed_address!(&pair_address),
+                    20,
+                    40,
+                    input_payment.clone()
+                )
+            );
+
+            // 150 seconds ago falls halfway between the rounds 20 and 30
+            let output_payment = sc.get_safe_price_by_timestamp_offset(
+                managed_address!(&pair_address),
+                150,
+                input_payment.clone(),
+            );
+            assert_eq!(
+                output_payment,
+                sc.get_safe_price(managed_address!(&pair_address), 25, 40, input_payment)
+            );
+        })
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let input_payment = EsdtTokenPayment::new(
+                managed_token_id!(WEGLD_TOKEN_ID),
+                0,
+                managed_biguint!(1_000),
+            );
+            let _ = sc.get_safe_price_by_timestamp_offset(
+                managed_address!(&pair_address),
+                250,
+                input_payment,
+            );
+        })
+        .assert_user_error("The price observation does not exist");
+}
+
+#[test]
+fn test_price_observations_capacity() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let pair_address = pair_setup.pair_wrapper.address_ref().clone();
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    // only the owner can shrink the ring
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_price_observations_capacity(10);
+            },
+        )
+        .assert_user_error("Permission denied");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_price_observations_capacity(10);
+            },
+        )
+        .assert_ok();
+
+    for round in 1..=12 {
+        pair_setup.b_mock.set_block_round(round);
+        pair_setup
+            .b_mock
+            .execute_esdt_transfer(
+                &pair_setup.user_address,
+                &pair_setup.pair_wrapper,
+                WEGLD_TOKEN_ID,
+                0,
+                &rust_biguint!(1_000),
+                |sc| {
+                    let _ = sc.swap_tokens_fixed_input(
+                        managed_token_id!(MEX_TOKEN_ID),
+                        managed_biguint!(1),
+                        OptionalValue::None,
+                    );
+                },
+            )
+            .assert_ok();
+    }
+
+    // the rounds 1 and 2 were overwritten
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(sc.price_observations().len(), 10);
+            assert_eq!(sc.safe_price_current_index().get(), 2);
+            assert_eq!(
+                sc.get_oldest_price_observation_round(managed_address!(&pair_address)),
+                3
+            );
+        })
+        .assert_ok();
+
+    // anyone can grow the ring, which happens once the newest observation reaches its end
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_price_observations_capacity(12);
+            },
+        )
+        .assert_ok();
+
+    for round in 13..=22 {
+        pair_setup.b_mock.set_block_round(round);
+        pair_setup
+            .b_mock
+            .execute_esdt_transfer(
+                &pair_setup.user_address,
+                &pair_setup.pair_wrapper,
+                WEGLD_TOKEN_ID,
+                0,
+                &rust_biguint!(1_000),
+                |sc| {
+                    let _ = sc.swap_tokens_fixed_input(
+                        managed_token_id!(MEX_TOKEN_ID),
+                        managed_biguint!(1),
+                        OptionalValue::None,
+                    );
+                },
+            )
+            .assert_ok();
+    }
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(sc.price_observations().len(), 12);
+            assert_eq!(sc.safe_price_current_index().get(), 12);
+            assert_eq!(
+                sc.get_oldest_price_observation_round(managed_address!(&pair_address)),
+                11
+            );
+        })
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_price_observations_capacity(11);
+            },
+        )
+        .assert_user_error("Bad price observations capacity");
+}
+
+#[test]
+fn test_lp_fee_mode() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+    let treasury = pair_setup.b_mock.create_user_account(&rust_zero);
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_lp_fee_on(managed_address!(&treasury));
+            },
+        )
+        .assert_user_error("Permission denied");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_lp_fee_on(managed_address!(&treasury));
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert!(sc.is_lp_fee_mode_enabled());
+            assert_eq!(sc.root_k_last().get(), managed_biguint!(1_001_000));
+        })
+        .assert_ok();
+
+    // the whole fee stays in the reserves, sqrt(1_101_000 * 910_331) = 1_001_136
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 100_000, MEX_TOKEN_ID, 90_000, 90_669);
+    pair_setup
+        .b_mock
+        .check_esdt_balance(&treasury, LP_TOKEN_ID, &rust_zero);
+
+    // 1_001_000 * 136 * 50 / (1_001_136 * 250 + 1_001_000 * 50) = 22 LP for the treasury
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            LP_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let _ = sc.remove_liquidity(managed_biguint!(1), managed_biguint!(1));
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .check_esdt_balance(&treasury, LP_TOKEN_ID, &rust_biguint!(22));
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        WEGLD_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_WEGLD_TOKENS - 1_001_000 - 100_000 + 109_987),
+    );
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(sc.lp_token_supply().get(), managed_biguint!(901_022));
+            assert_eq!(sc.root_k_last().get(), managed_biguint!(901_125));
+        })
+        .assert_ok();
+
+    // the fee of the internal swap is left for the next mint, like a regular swap fee
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            LP_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let _ = sc.remove_liquidity_single_token(
+                    managed_token_id!(WEGLD_TOKEN_ID),
+                    managed_biguint!(1),
+                );
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            let first_token_reserve = sc.pair_reserve(&managed_token_id!(WEGLD_TOKEN_ID)).get();
+            let second_token_reserve = sc.pair_reserve(&managed_token_id!(MEX_TOKEN_ID)).get();
+            let root_k = (first_token_reserve * second_token_reserve).sqrt();
+            assert!(sc.root_k_last().get() < root_k);
+        })
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_lp_fee_off();
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert!(sc.lp_fee_treasury().is_empty());
+            assert_eq!(sc.root_k_last().get(), managed_biguint!(0));
+        })
+        .assert_ok();
+}
+
+#[test]
+fn test_lp_fee_mode_ignores_amplification_ramp() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+    let treasury = pair_setup.b_mock.create_user_account(&rust_zero);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_amplification(100);
+                sc.set_lp_fee_on(managed_address!(&treasury));
+            },
+        )
+        .assert_ok();
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 500_000, MEX_TOKEN_ID, 1, 496_870);
+
+    // the fee from the swap is minted on the next liquidity change
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            LP_TOKEN_ID,
+            0,
+            &rust_biguint!(1_000),
+            |sc| {
+                let _ = sc.remove_liquidity(managed_biguint!(1), managed_biguint!(1));
+            },
+        )
+        .assert_ok();
+    let treasury_lp_balance = pair_setup
+        .b_mock
+        .get_esdt_balance(&treasury, LP_TOKEN_ID, 0);
+    assert!(treasury_lp_balance > rust_zero);
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.ramp_amplification(1_000, 86_400);
+            },
+        )
+        .assert_ok();
+
+    // raising the amplification raises the invariant of the imbalanced reserves,
+    // but no fees were earned, so nothing is minted
+    pair_setup.b_mock.set_block_timestamp(86_400);
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            LP_TOKEN_ID,
+            0,
+            &rust_biguint!(1_000),
+            |sc| {
+                let _ = sc.remove_liquidity(managed_biguint!(1), managed_biguint!(1));
+            },
+        )
+        .assert_ok();
+    pair_setup
+        .b_mock
+        .check_esdt_balance(&treasury, LP_TOKEN_ID, &treasury_lp_balance);
+}
+
+#[test]
+fn test_energy_fee_discount() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+    let energy_factory_wrapper = pair_setup.b_mock.create_sc_account(
+        &rust_zero,
+        Some(&pair_setup.owner_address),
+        energy_factory_mock::contract_obj,
+        "energy factory path",
+    );
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    let user_address = pair_setup.user_address.clone();
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &energy_factory_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_user_energy(
+                    managed_address!(&user_address),
+                    managed_biguint!(1_000),
+                    managed_biguint!(1_000),
+                );
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_energy_factory_address(managed_address!(
+                    energy_factory_wrapper.address_ref()
+                ));
+
+                let mut tiers = MultiValueEncoded::new();
+                tiers.push((managed_biguint!(2_000), 5_000).into());
+                tiers.push((managed_biguint!(500), 10_000).into());
+                sc.set_fee_discount_tiers(tiers);
+            },
+        )
+        .assert_user_error("Bad fee discount tiers");
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                sc.set_energy_factory_address(managed_address!(
+                    energy_factory_wrapper.address_ref()
+                ));
+
+                let mut tiers = MultiValueEncoded::new();
+                tiers.push((managed_biguint!(500), 5_000).into());
+                tiers.push((managed_biguint!(2_000), 10_000).into());
+                sc.set_fee_discount_tiers(tiers);
+            },
+        )
+        .assert_ok();
+
+    // 5% off the 250 LP fee, the 50 special fee is kept
+    let owner_address = pair_setup.owner_address.clone();
+    pair_setup
+        .b_mock
+        .execute_query(&pair_setup.pair_wrapper, |sc| {
+            assert_eq!(
+                sc.get_user_total_fee_percent(managed_address!(&user_address)),
+                288
+            );
+            assert_eq!(
+                sc.get_user_total_fee_percent(managed_address!(&owner_address)),
+                300
+            );
+        })
+        .assert_ok();
+
+    // 90_669 without the discount
+    pair_setup.swap_fixed_input(WEGLD_TOKEN_ID, 100_000, MEX_TOKEN_ID, 90_000, 90_679);
+
+    // only the router may swap on behalf of a user
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(1_000),
+            |sc| {
+                let _ = sc.swap_tokens_fixed_input(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_biguint!(1),
+                    OptionalValue::Some(managed_address!(&owner_address)),
+                );
+            },
+        )
+        .assert_user_error("Only the router may swap on behalf of a user");
+
+    // the router swaps with the discount of the original caller, 75_588 without it
+    pair_setup
+        .b_mock
+        .set_esdt_balance(&owner_address, WEGLD_TOKEN_ID, &rust_biguint!(100_000));
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &owner_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let ret = sc.swap_tokens_fixed_input(
+                    managed_token_id!(MEX_TOKEN_ID),
+                    managed_biguint!(1),
+                    OptionalValue::Some(managed_address!(&user_address)),
+                );
+                assert_eq!(ret.amount, managed_biguint!(75_596));
+            },
+        )
+        .assert_ok();
+}
+
+#[test]
+fn test_twamm_orders() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    // sold over blocks 50 - 200, as orders end at multiples of 100 blocks
+    pair_setup.b_mock.set_block_nonce(50);
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(10_000),
+            |sc| {
+                let order_id = sc.place_twamm_order(100);
+                assert_eq!(order_id, 1);
+
+                let order = sc.get_twamm_order(order_id);
+                assert_eq!(order.expiry_block, 200);
+            },
+        )
+        .assert_ok();
+
+    // 3_333 WEGLD sold until block 100, then 1_666 more until block 125
+    pair_setup.b_mock.set_block_nonce(125);
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                let (_, proceeds) = sc.withdraw_twamm_proceeds(1).into_tuple();
+                assert_eq!(proceeds.token_identifier, managed_token_id!(MEX_TOKEN_ID));
+                assert_eq!(proceeds.amount, managed_biguint!(4_959));
+            },
+        )
+        .assert_ok();
+
+    pair_setup.b_mock.set_block_nonce(250);
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                // the whole deposit was sold, nothing is left to send back
+                let (refund, proceeds) = sc.withdraw_twamm_proceeds(1).into_tuple();
+                assert_eq!(refund.amount, managed_biguint!(0));
+                assert_eq!(proceeds.amount, managed_biguint!(4_910));
+
+                assert_eq!(
+                    sc.pair_reserve(&managed_token_id!(WEGLD_TOKEN_ID)).get(),
+                    managed_biguint!(1_011_000)
+                );
+                assert_eq!(
+                    sc.pair_reserve(&managed_token_id!(MEX_TOKEN_ID)).get(),
+                    managed_biguint!(991_129)
+                );
+                assert_eq!(
+                    sc.twamm_sale_rate(&managed_token_id!(WEGLD_TOKEN_ID)).get(),
+                    managed_biguint!(0)
+                );
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                let _ = sc.withdraw_twamm_proceeds(1);
+            },
+        )
+        .assert_user_error("TWAMM order not found");
+
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(20_000),
+            |sc| {
+                let order_id = sc.place_twamm_order(100);
+                assert_eq!(order_id, 2);
+            },
+        )
+        .assert_ok();
+
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.owner_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                let _ = sc.cancel_twamm_order(2);
+            },
+        )
+        .assert_user_error("Not the TWAMM order owner");
+
+    // 6_666 WEGLD sold until block 300, the rest is sent back
+    pair_setup.b_mock.set_block_nonce(300);
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                let (unsold_payment, proceeds_payment) = sc.cancel_twamm_order(2).into_tuple();
+                assert_eq!(unsold_payment.amount, managed_biguint!(13_333));
+                assert_eq!(proceeds_payment.amount, managed_biguint!(6_471));
+                assert_eq!(
+                    sc.twamm_sale_rate(&managed_token_id!(WEGLD_TOKEN_ID)).get(),
+                    managed_biguint!(0)
+                );
+            },
+        )
+        .assert_ok();
+
+    pair_setup.b_mock.check_esdt_balance(
+        &pair_setup.user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(USER_TOTAL_MEX_TOKENS - 1_001_000 + 4_959 + 4_910 + 6_471),
+    );
+}
+
+#[test]
+fn test_twamm_orders_netting() {
+    let mut pair_setup = PairSetup::new(pair::contract_obj);
+    let rust_zero = rust_biguint!(0u64);
+
+    pair_setup.add_liquidity(
+        1_001_000, 1_000_000, 1_001_000, 1_000_000, 1_000_000, 1_001_000, 1_001_000,
+    );
+
+    pair_setup.b_mock.set_block_nonce(100);
+    for token_id in [WEGLD_TOKEN_ID, MEX_TOKEN_ID] {
+        pair_setup
+            .b_mock
+            .execute_esdt_transfer(
+                &pair_setup.user_address,
+                &pair_setup.pair_wrapper,
+                token_id,
+                0,
+                &rust_biguint!(10_000),
+                |sc| {
+                    let _ = sc.place_twamm_order(100);
+                },
+            )
+            .assert_ok();
+    }
+
+    // the two sides are matched against each other, nothing goes through the pool
+    pair_setup.b_mock.set_block_nonce(200);
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                let (_, proceeds) = sc.withdraw_twamm_proceeds(1).into_tuple();
+                assert_eq!(proceeds.amount, managed_biguint!(10_000));
+
+                let (_, proceeds) = sc.withdraw_twamm_proceeds(2).into_tuple();
+                assert_eq!(proceeds.amount, managed_biguint!(10_000));
+
+                assert_eq!(
+                    sc.pair_reserve(&managed_token_id!(WEGLD_TOKEN_ID)).get(),
+                    managed_biguint!(1_001_000)
+                );
+                assert_eq!(
+                    sc.pair_reserve(&managed_token_id!(MEX_TOKEN_ID)).get(),
+                    managed_biguint!(1_001_000)
+                );
+            },
+        )
+        .assert_ok();
+
+    // the amounts too small to be sold in an interval are carried forward, so 4 WEGLD
+    // are sold two at a time and the last one is sent back once the order ends
+    pair_setup
+        .b_mock
+        .execute_esdt_transfer(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            WEGLD_TOKEN_ID,
+            0,
+            &rust_biguint!(5),
+            |sc| {
+                let order_id = sc.place_twamm_order(1_000);
+                assert_eq!(order_id, 3);
+            },
+        )
+        .assert_ok();
+
+    pair_setup.b_mock.set_block_nonce(1_200);
+    pair_setup
+        .b_mock
+        .execute_tx(
+            &pair_setup.user_address,
+            &pair_setup.pair_wrapper,
+            &rust_zero,
+            |sc| {
+                let (refund, proceeds) = sc.withdraw_twamm_proceeds(3).into_tuple();
+                assert_eq!(refund.amount, managed_biguint!(1));
+                assert_eq!(proceeds.amount, managed_biguint!(2));
+
+                assert_eq!(
+                    sc.pair_reserve(&managed_token_id!(WEGLD_TOKEN_ID)).get(),
+                    managed_biguint!(1_001_004)
+                );
+            },
+        )
+        .assert_ok();
+}
+
 #[test]
 fn test_safe_price_observation_decoding() {
     let mut pair_setup = PairSetup::new(pair::contract_obj);
@@ -128,6 +2030,7 @@ fn test_safe_price_observation_decoding() {
                 new_observation.lp_supply_accumulated,
                 managed_biguint!(0u64)
             );
+            assert_eq!(new_observation.recording_timestamp, 0);
 
             new_observation.lp_supply_accumulated = managed_biguint!(2u64);
             sc.price_observations().push(&new_observation.clone());
@@ -994,7 +2897,7 @@ fn test_both_legacy_and_new_safe_price_from_other_contract() {
 }
 
 // Test is commented as it needs a variable change in order to run succesfully
-// In order to run the test with the current setup, MAX_OBSERVATIONS const must be set to 100
+// In order to run the test with the current setup, the observations capacity must be set to 100
 // This is necessary as using the MAINNET variable requires too many operations for a unit test
 // #[test]
 // fn test_safe_price_max_length() {
@@ -1203,6 +3106,7 @@ fn test_locked_asset() {
                 let ret = sc.swap_tokens_fixed_input(
                     managed_token_id!(WEGLD_TOKEN_ID),
                     managed_biguint!(10),
+                    OptionalValue::None,
                 );
 
                 assert_eq!(ret.token_identifier, managed_token_id!(LOCKED_TOKEN_ID));
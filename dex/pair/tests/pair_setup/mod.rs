@@ -1,4 +1,4 @@
-use multiversx_sc::codec::multi_types::MultiValue3;
+use multiversx_sc::codec::multi_types::{MultiValue3, OptionalValue};
 use multiversx_sc::types::{
     Address, BigUint, EsdtLocalRole, EsdtTokenPayment, ManagedAddress, MultiValueEncoded,
 };
@@ -199,6 +199,7 @@ where
                     let ret = sc.swap_tokens_fixed_input(
                         managed_token_id!(desired_token_id),
                         managed_biguint!(desired_amount_min),
+                        OptionalValue::None,
                     );
 
                     assert_eq!(ret.token_identifier, managed_token_id!(desired_token_id));
@@ -238,6 +239,7 @@ where
                     let ret = sc.swap_tokens_fixed_output(
                         managed_token_id!(desired_token_id),
                         managed_biguint!(desired_amount),
+                        OptionalValue::None,
                     );
 
                     let (desired_token_output, payment_token_residuum) = ret.into_tuple();
@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           63
+// Endpoints:                          114
 // Async Callback (empty):               1
-// Total number of exported functions:  66
+// Total number of exported functions:  117
 
 #![no_std]
 
@@ -22,17 +22,22 @@ multiversx_sc_wasm_adapter::endpoints! {
         upgrade => upgrade
         setLpTokenIdentifier => set_lp_token_identifier
         getFeeState => is_fee_enabled
+        isLpFeeModeEnabled => is_lp_fee_mode_enabled
         whitelist => whitelist_endpoint
         removeWhitelist => remove_whitelist
         addTrustedSwapPair => add_trusted_swap_pair
         removeTrustedSwapPair => remove_trusted_swap_pair
         setupFeesCollector => setup_fees_collector
         setFeeOn => set_fee_on
+        setLpFeeOn => set_lp_fee_on
+        setLpFeeOff => set_lp_fee_off
         getFeeDestinations => get_fee_destinations
         getTrustedSwapPairs => get_trusted_swap_pairs
         getWhitelistedManagedAddresses => get_whitelisted_managed_addresses
         getFeesCollectorAddress => fees_collector_address
         getFeesCollectorCutPercentage => fees_collector_cut_percentage
+        getLpFeeTreasury => lp_fee_treasury
+        getRootKLast => root_k_last
         setStateActiveNoSwaps => set_state_active_no_swaps
         setFeePercents => set_fee_percent
         getLpTokenIdentifier => get_lp_token_identifier
@@ -44,9 +49,46 @@ multiversx_sc_wasm_adapter::endpoints! {
         getTotalSupply => lp_token_supply
         getInitialLiquidtyAdder => initial_liquidity_adder
         getReserve => pair_reserve
+        getTokenWeights => token_weights
+        setAmplification => set_amplification
+        rampAmplification => ramp_amplification
+        stopRampAmplification => stop_ramp_amplification
+        getAmplification => get_current_amplification
+        getAmplificationRamp => amplification_ramp
+        setTokenWeights => set_token_weights
+        setPriceObservationsCapacity => set_price_observations_capacity
+        getPriceObservationsCapacity => get_price_observations_capacity
         getSafePriceCurrentIndex => safe_price_current_index
         updateAndGetTokensForGivenPositionWithSafePrice => update_and_get_tokens_for_given_position_with_safe_price
         updateAndGetSafePrice => update_and_get_safe_price
+        setDynamicFeeConfig => set_dynamic_fee_config
+        disableDynamicFee => disable_dynamic_fee
+        getVolatility => get_volatility_view
+        getDynamicFeeConfig => dynamic_fee_config
+        placeLimitOrder => place_limit_order
+        cancelLimitOrder => cancel_limit_order
+        fillLimitOrders => fill_limit_orders_endpoint
+        getLimitOrder => get_limit_order
+        getUserLimitOrders => get_user_limit_orders
+        getLimitOrderIds => limit_order_ids
+        setFlashLoanConfig => set_flash_loan_config
+        disableFlashLoans => disable_flash_loans
+        flashLoan => flash_loan
+        repayFlashLoan => repay_flash_loan
+        getFlashLoanConfig => flash_loan_config
+        placeTwammOrder => place_twamm_order
+        withdrawTwammProceeds => withdraw_twamm_proceeds
+        cancelTwammOrder => cancel_twamm_order
+        executeTwammOrders => execute_twamm_orders_endpoint
+        getTwammOrder => get_twamm_order
+        getUserTwammOrderIds => user_twamm_order_ids
+        getTwammSaleRate => twamm_sale_rate
+        getTwammLastExecutedBlock => twamm_last_executed_block
+        setFeeDiscountTiers => set_fee_discount_tiers
+        getUserTotalFeePercent => get_user_total_fee_percent
+        getFeeDiscountTiers => fee_discount_tiers
+        setEnergyFactoryAddress => set_energy_factory_address
+        getEnergyFactoryAddress => energy_factory_address
         setLockingDeadlineEpoch => set_locking_deadline_epoch
         setLockingScAddress => set_locking_sc_address
         setUnlockEpoch => set_unlock_epoch
@@ -64,7 +106,9 @@ multiversx_sc_wasm_adapter::endpoints! {
         getState => state
         addInitialLiquidity => add_initial_liquidity
         addLiquidity => add_liquidity
+        addLiquiditySingleToken => add_liquidity_single_token
         removeLiquidity => remove_liquidity
+        removeLiquiditySingleToken => remove_liquidity_single_token
         removeLiquidityAndBuyBackAndBurnToken => remove_liquidity_and_burn_token
         swapNoFeeAndForward => swap_no_fee
         swapTokensFixedInput => swap_tokens_fixed_input
@@ -83,6 +127,13 @@ multiversx_sc_wasm_adapter::endpoints! {
         getSafePriceByTimestampOffset => get_safe_price_by_timestamp_offset
         getSafePrice => get_safe_price
         getPriceObservation => get_price_observation_view
+        getGeometricSafePriceByRoundOffset => get_geometric_safe_price_by_round_offset
+        getGeometricSafePriceByTimestampOffset => get_geometric_safe_price_by_timestamp_offset
+        getGeometricSafePrice => get_geometric_safe_price
+        getSafePriceVolatilityByRoundOffset => get_safe_price_volatility_by_round_offset
+        getSafePriceVolatilityByTimestampOffset => get_safe_price_volatility_by_timestamp_offset
+        getSafePriceVolatility => get_safe_price_volatility
+        getOldestPriceObservationRound => get_oldest_price_observation_round
     )
 }
 
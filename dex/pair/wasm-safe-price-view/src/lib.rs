@@ -5,9 +5,9 @@
 ////////////////////////////////////////////////////
 
 // Init:                                 1
-// Endpoints:                            9
+// Endpoints:                           16
 // Async Callback (empty):               1
-// Total number of exported functions:  11
+// Total number of exported functions:  18
 
 #![no_std]
 
@@ -28,6 +28,13 @@ multiversx_sc_wasm_adapter::external_view_endpoints! {
         getSafePriceByTimestampOffset => get_safe_price_by_timestamp_offset
         getSafePrice => get_safe_price
         getPriceObservation => get_price_observation_view
+        getGeometricSafePriceByRoundOffset => get_geometric_safe_price_by_round_offset
+        getGeometricSafePriceByTimestampOffset => get_geometric_safe_price_by_timestamp_offset
+        getGeometricSafePrice => get_geometric_safe_price
+        getSafePriceVolatilityByRoundOffset => get_safe_price_volatility_by_round_offset
+        getSafePriceVolatilityByTimestampOffset => get_safe_price_volatility_by_timestamp_offset
+        getSafePriceVolatility => get_safe_price_volatility
+        getOldestPriceObservationRound => get_oldest_price_observation_round
     )
 }
 
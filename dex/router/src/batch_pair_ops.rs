@@ -0,0 +1,198 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::{config, events, factory, multi_pair_swap, pair_registry, path_finder};
+use pair::fee::ProxyTrait as _;
+use pair::read_pair_storage;
+use pausable::ProxyTrait as _;
+
+pub static PAUSE_OPERATION: &[u8] = b"pause";
+pub static RESUME_OPERATION: &[u8] = b"resume";
+pub static SET_FEE_ON_OPERATION: &[u8] = b"setFeeOn";
+pub static SET_FEE_OFF_OPERATION: &[u8] = b"setFeeOff";
+pub static UPGRADE_OPERATION: &[u8] = b"upgrade";
+
+const UPGRADE_CONTRACT_FUNC_NAME: &str = "upgradeContract";
+
+/// The pairs a batch operation applies to: either the given pairs, or the pairs holding
+/// the token among the registry entries selected by offset and limit, the same way as
+/// in the paginated registry views.
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub enum PairsSelection<M: ManagedTypeApi> {
+    Addresses(ManagedVec<M, ManagedAddress<M>>),
+    ContainingToken(TokenIdentifier<M>, usize, usize),
+}
+
+/// Batch variants of the pair administration endpoints. Each batch is applied
+/// atomically: if the operation fails for any of the pairs, the whole transaction fails.
+#[multiversx_sc::module]
+pub trait BatchPairOpsModule:
+    config::ConfigModule
+    + read_pair_storage::ReadPairStorageModule
+    + factory::FactoryModule
+    + path_finder::PathFinderModule
+    + pair_registry::PairRegistryModule
+    + multi_pair_swap::MultiPairSwap
+    + token_send::TokenSendModule
+    + events::EventsModule
+{
+    #[only_owner]
+    #[endpoint(pausePairs)]
+    fn pause_pairs(
+        &self,
+        selection: PairsSelection<Self::Api>,
+    ) -> MultiValueEncoded<ManagedAddress> {
+        let pair_addresses = self.get_selected_pairs(selection);
+        for pair_address in pair_addresses.iter() {
+            let _: IgnoreValue = self
+                .pair_contract_proxy(pair_address.clone_value())
+                .pause()
+                .execute_on_dest_context();
+
+            self.emit_pair_admin_operation_event(
+                PAUSE_OPERATION.into(),
+                pair_address.clone_value(),
+            );
+        }
+
+        pair_addresses.into()
+    }
+
+    #[only_owner]
+    #[endpoint(resumePairs)]
+    fn resume_pairs(
+        &self,
+        selection: PairsSelection<Self::Api>,
+    ) -> MultiValueEncoded<ManagedAddress> {
+        let pair_addresses = self.get_selected_pairs(selection);
+        for pair_address in pair_addresses.iter() {
+            let _: IgnoreValue = self
+                .pair_contract_proxy(pair_address.clone_value())
+                .resume()
+                .execute_on_dest_context();
+
+            self.emit_pair_admin_operation_event(
+                RESUME_OPERATION.into(),
+                pair_address.clone_value(),
+            );
+        }
+
+        pair_addresses.into()
+    }
+
+    #[only_owner]
+    #[endpoint(setFeeOnForPairs)]
+    fn set_fee_on_for_pairs(
+        &self,
+        fee_to_address: ManagedAddress,
+        fee_token: TokenIdentifier,
+        selection: PairsSelection<Self::Api>,
+    ) -> MultiValueEncoded<ManagedAddress> {
+        require!(self.is_active(), "Not active");
+
+        let pair_addresses = self.get_selected_pairs(selection);
+        for pair_address in pair_addresses.iter() {
+            let _: IgnoreValue = self
+                .pair_contract_proxy(pair_address.clone_value())
+                .set_fee_on(true, fee_to_address.clone(), fee_token.clone())
+                .execute_on_dest_context();
+
+            self.emit_pair_admin_operation_event(
+                SET_FEE_ON_OPERATION.into(),
+                pair_address.clone_value(),
+            );
+        }
+
+        pair_addresses.into()
+    }
+
+    #[only_owner]
+    #[endpoint(setFeeOffForPairs)]
+    fn set_fee_off_for_pairs(
+        &self,
+        fee_to_address: ManagedAddress,
+        fee_token: TokenIdentifier,
+        selection: PairsSelection<Self::Api>,
+    ) -> MultiValueEncoded<ManagedAddress> {
+        require!(self.is_active(), "Not active");
+
+        let pair_addresses = self.get_selected_pairs(selection);
+        for pair_address in pair_addresses.iter() {
+            let _: IgnoreValue = self
+                .pair_contract_proxy(pair_address.clone_value())
+                .set_fee_on(false, fee_to_address.clone(), fee_token.clone())
+                .execute_on_dest_context();
+
+            self.emit_pair_admin_operation_event(
+                SET_FEE_OFF_OPERATION.into(),
+                pair_address.clone_value(),
+            );
+        }
+
+        pair_addresses.into()
+    }
+
+    /// Upgrades the selected pairs to the given code. Upgrading from the pair template, as
+    /// `upgradePair` does, is an asynchronous call and only one fits in a transaction, so
+    /// the batch calls the `upgradeContract` built-in function synchronously for each pair.
+    #[only_owner]
+    #[endpoint(upgradePairs)]
+    fn upgrade_pairs(
+        &self,
+        code: ManagedBuffer,
+        selection: PairsSelection<Self::Api>,
+    ) -> MultiValueEncoded<ManagedAddress> {
+        require!(self.is_active(), "Not active");
+
+        let code_metadata =
+            CodeMetadata::UPGRADEABLE | CodeMetadata::READABLE | CodeMetadata::PAYABLE_BY_SC;
+        let pair_addresses = self.get_selected_pairs(selection);
+        for pair_address in pair_addresses.iter() {
+            self.tx()
+                .to(pair_address.clone_value())
+                .raw_call(UPGRADE_CONTRACT_FUNC_NAME)
+                .argument(&code)
+                .argument(&code_metadata)
+                .sync_call();
+
+            self.emit_pair_admin_operation_event(
+                UPGRADE_OPERATION.into(),
+                pair_address.clone_value(),
+            );
+        }
+
+        pair_addresses.into()
+    }
+
+    fn get_selected_pairs(
+        &self,
+        selection: PairsSelection<Self::Api>,
+    ) -> ManagedVec<ManagedAddress> {
+        let pair_addresses = match selection {
+            PairsSelection::Addresses(pair_addresses) => {
+                for pair_address in pair_addresses.iter() {
+                    self.check_is_pair_sc(&pair_address);
+                }
+
+                pair_addresses
+            }
+            PairsSelection::ContainingToken(token_id, offset, limit) => {
+                self.require_valid_page_size(limit);
+
+                let mut pair_addresses = ManagedVec::new();
+                self.for_each_registered_pair(offset, limit, |pair_tokens, pair_address| {
+                    if pair_tokens.first_token_id == token_id
+                        || pair_tokens.second_token_id == token_id
+                    {
+                        pair_addresses.push(pair_address);
+                    }
+                });
+
+                pair_addresses
+            }
+        };
+        require!(!pair_addresses.is_empty(), "No pairs selected");
+
+        pair_addresses
+    }
+}
@@ -1,7 +1,10 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
-use crate::{enable_swap_by_user::EnableSwapByUserConfig, factory::PairTokens};
+use crate::{
+    enable_swap_by_user::EnableSwapByUserConfig,
+    factory::{FeeTierPairTokens, PairTokens},
+};
 use pair::read_pair_storage;
 
 #[multiversx_sc::module]
@@ -28,12 +31,31 @@ pub trait ConfigModule: read_pair_storage::ReadPairStorageModule {
             pair_map_address_opt = self.pair_map().get(&reverse_pair_tokens);
         }
 
-        require!(pair_map_address_opt.is_some(), "Not a pair SC");
+        if pair_map_address_opt.as_ref() == Some(pair_address) {
+            return;
+        }
+
+        let fee_tier_mapper = self.pair_fee_tier(pair_address);
+        require!(!fee_tier_mapper.is_empty(), "Not a pair SC");
 
-        unsafe {
-            let pair_map_address = pair_map_address_opt.unwrap_unchecked();
-            require!(&pair_map_address == pair_address, "Not a pair SC");
+        let fee_tier = fee_tier_mapper.get();
+        let mut fee_tier_pair_address_opt = self.fee_tier_pair_map().get(&FeeTierPairTokens {
+            first_token_id: first_token_id.clone(),
+            second_token_id: second_token_id.clone(),
+            fee_tier,
+        });
+        if fee_tier_pair_address_opt.is_none() {
+            fee_tier_pair_address_opt = self.fee_tier_pair_map().get(&FeeTierPairTokens {
+                first_token_id: second_token_id,
+                second_token_id: first_token_id,
+                fee_tier,
+            });
         }
+
+        require!(
+            fee_tier_pair_address_opt.as_ref() == Some(pair_address),
+            "Not a pair SC"
+        );
     }
 
     #[view(getPairCreationEnabled)]
@@ -63,6 +85,32 @@ pub trait ConfigModule: read_pair_storage::ReadPairStorageModule {
     #[storage_mapper("pair_map")]
     fn pair_map(&self) -> MapMapper<PairTokens<Self::Api>, ManagedAddress>;
 
+    /// Maps each allowed total fee percent to its special fee percent
+    #[storage_mapper("fee_tiers")]
+    fn fee_tiers(&self) -> MapMapper<u64, u64>;
+
+    #[storage_mapper("fee_tier_pair_map")]
+    fn fee_tier_pair_map(&self) -> MapMapper<FeeTierPairTokens<Self::Api>, ManagedAddress>;
+
+    /// Fee tier pairs that are not the default pair of their tokens
+    #[storage_mapper("non_default_fee_tier_pairs_count")]
+    fn non_default_fee_tier_pairs_count(&self) -> SingleValueMapper<usize>;
+
+    /// Pairs registered with the fee tier, which cannot be removed while this is not zero
+    #[view(getFeeTierPairsCount)]
+    #[storage_mapper("fee_tier_pairs_count")]
+    fn fee_tier_pairs_count(&self, fee_tier: u64) -> SingleValueMapper<usize>;
+
+    #[view(getPairFeeTier)]
+    #[storage_mapper("pair_fee_tier")]
+    fn pair_fee_tier(&self, pair_address: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    /// Block timestamp at which the router deployed the pair. Empty for pairs
+    /// deployed before the timestamp was recorded.
+    #[view(getPairCreationTimestamp)]
+    #[storage_mapper("pair_creation_timestamp")]
+    fn pair_creation_timestamp(&self, pair_address: &ManagedAddress) -> SingleValueMapper<u64>;
+
     #[view(getPairTemplateAddress)]
     #[storage_mapper("pair_template_address")]
     fn pair_template_address(&self) -> SingleValueMapper<ManagedAddress>;
@@ -4,15 +4,20 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
+pub mod batch_pair_ops;
 pub mod config;
 pub mod enable_swap_by_user;
 mod events;
 pub mod factory;
 pub mod multi_pair_swap;
+pub mod pair_registry;
+pub mod path_finder;
 
+use energy_query::ProxyTrait as _;
 use factory::PairTokens;
 use pair::config::ProxyTrait as _;
 use pair::fee::ProxyTrait as _;
+use pair::stable_swap::ProxyTrait as _;
 use pair::{read_pair_storage, ProxyTrait as _};
 use pausable::ProxyTrait as _;
 
@@ -31,6 +36,9 @@ pub trait Router:
     + factory::FactoryModule
     + events::EventsModule
     + multi_pair_swap::MultiPairSwap
+    + path_finder::PathFinderModule
+    + pair_registry::PairRegistryModule
+    + batch_pair_ops::BatchPairOpsModule
     + token_send::TokenSendModule
     + enable_swap_by_user::EnableSwapByUserModule
 {
@@ -86,26 +94,9 @@ pub trait Router:
         opt_fee_percents: OptionalValue<MultiValue2<u64, u64>>,
         mut admins: MultiValueEncoded<ManagedAddress>,
     ) -> ManagedAddress {
-        require!(self.is_active(), "Not active");
         let owner = self.owner().get();
         let caller = self.blockchain().get_caller();
-
-        if caller != owner {
-            require!(
-                self.pair_creation_enabled().get(),
-                "Pair creation is disabled"
-            );
-        }
-
-        require!(first_token_id != second_token_id, "Identical tokens");
-        require!(
-            first_token_id.is_valid_esdt_identifier(),
-            "First Token ID is not a valid esdt token ID"
-        );
-        require!(
-            second_token_id.is_valid_esdt_identifier(),
-            "Second Token ID is not a valid esdt token ID"
-        );
+        self.require_can_create_pair(&caller, &owner, &first_token_id, &second_token_id);
         let pair_address = self.get_pair(first_token_id.clone(), second_token_id.clone());
         require!(pair_address.is_zero(), "Pair already exists");
 
@@ -151,12 +142,140 @@ pub trait Router:
         address
     }
 
+    /// Deploys a pair using one of the fee tiers configured by the owner. Several pairs
+    /// may exist for the same tokens, one per fee tier.
+    #[endpoint(createPairWithFeeTier)]
+    fn create_pair_with_fee_tier_endpoint(
+        &self,
+        first_token_id: TokenIdentifier,
+        second_token_id: TokenIdentifier,
+        initial_liquidity_adder: ManagedAddress,
+        fee_tier: u64,
+        mut admins: MultiValueEncoded<ManagedAddress>,
+    ) -> ManagedAddress {
+        let owner = self.owner().get();
+        let caller = self.blockchain().get_caller();
+        self.require_can_create_pair(&caller, &owner, &first_token_id, &second_token_id);
+
+        admins.push(caller.clone());
+
+        let address = self.create_fee_tier_pair(
+            &first_token_id,
+            &second_token_id,
+            &owner,
+            fee_tier,
+            &initial_liquidity_adder,
+            admins,
+        );
+
+        let special_fee_percent = self.fee_tiers().get(&fee_tier).unwrap_or_default();
+        self.emit_create_pair_event(
+            caller,
+            first_token_id,
+            second_token_id,
+            fee_tier,
+            special_fee_percent,
+            address.clone(),
+        );
+        address
+    }
+
+    /// Checks shared by `createPair` and `createPairWithFeeTier`. Users other than
+    /// the owner may only create pairs while pair creation is enabled.
+    fn require_can_create_pair(
+        &self,
+        caller: &ManagedAddress,
+        owner: &ManagedAddress,
+        first_token_id: &TokenIdentifier,
+        second_token_id: &TokenIdentifier,
+    ) {
+        require!(self.is_active(), "Not active");
+        if caller != owner {
+            require!(
+                self.pair_creation_enabled().get(),
+                "Pair creation is disabled"
+            );
+        }
+
+        require!(first_token_id != second_token_id, "Identical tokens");
+        require!(
+            first_token_id.is_valid_esdt_identifier(),
+            "First Token ID is not a valid esdt token ID"
+        );
+        require!(
+            second_token_id.is_valid_esdt_identifier(),
+            "Second Token ID is not a valid esdt token ID"
+        );
+    }
+
+    #[only_owner]
+    #[endpoint(createStablePair)]
+    fn create_stable_pair_endpoint(
+        &self,
+        first_token_id: TokenIdentifier,
+        second_token_id: TokenIdentifier,
+        initial_liquidity_adder: ManagedAddress,
+        total_fee_percent: u64,
+        special_fee_percent: u64,
+        amplification: u64,
+        mut admins: MultiValueEncoded<ManagedAddress>,
+    ) -> ManagedAddress {
+        require!(self.is_active(), "Not active");
+
+        require!(first_token_id != second_token_id, "Identical tokens");
+        require!(
+            first_token_id.is_valid_esdt_identifier(),
+            "First Token ID is not a valid esdt token ID"
+        );
+        require!(
+            second_token_id.is_valid_esdt_identifier(),
+            "Second Token ID is not a valid esdt token ID"
+        );
+        let pair_address = self.get_pair(first_token_id.clone(), second_token_id.clone());
+        require!(pair_address.is_zero(), "Pair already exists");
+        require!(
+            total_fee_percent >= special_fee_percent && total_fee_percent < MAX_TOTAL_FEE_PERCENT,
+            "Bad percents"
+        );
+
+        let owner = self.owner().get();
+        let caller = self.blockchain().get_caller();
+        admins.push(caller.clone());
+
+        let address = self.create_pair(
+            &first_token_id,
+            &second_token_id,
+            &owner,
+            total_fee_percent,
+            special_fee_percent,
+            &initial_liquidity_adder,
+            admins,
+        );
+
+        let _: IgnoreValue = self
+            .pair_contract_proxy(address.clone())
+            .set_amplification(amplification)
+            .execute_on_dest_context();
+
+        self.emit_create_pair_event(
+            caller,
+            first_token_id,
+            second_token_id,
+            total_fee_percent,
+            special_fee_percent,
+            address.clone(),
+        );
+        address
+    }
+
+    /// Upgrades the default pair of the tokens, or their pair with the given fee tier
     #[only_owner]
     #[endpoint(upgradePair)]
     fn upgrade_pair_endpoint(
         &self,
         first_token_id: TokenIdentifier,
         second_token_id: TokenIdentifier,
+        opt_fee_tier: OptionalValue<u64>,
     ) {
         require!(self.is_active(), "Not active");
 
@@ -169,7 +288,12 @@ pub trait Router:
             second_token_id.is_valid_esdt_identifier(),
             "Second Token ID is not a valid esdt token ID"
         );
-        let pair_address = self.get_pair(first_token_id.clone(), second_token_id.clone());
+        let pair_address = match opt_fee_tier {
+            OptionalValue::Some(fee_tier) => {
+                self.get_pair_by_fee_tier(first_token_id, second_token_id, fee_tier)
+            }
+            OptionalValue::None => self.get_pair(first_token_id, second_token_id),
+        };
         require!(!pair_address.is_zero(), "Pair does not exists");
 
         self.upgrade_pair(pair_address);
@@ -259,6 +383,8 @@ pub trait Router:
             .call_and_exit()
     }
 
+    /// Removes the default pair of the tokens. Their pair with the
+    /// lowest fee tier, if there is any, becomes the new default pair.
     #[only_owner]
     #[endpoint(removePair)]
     fn remove_pair(
@@ -292,15 +418,81 @@ pub trait Router:
             pair_address = self
                 .pair_map()
                 .remove(&PairTokens {
-                    first_token_id: second_token_id,
-                    second_token_id: first_token_id,
+                    first_token_id: second_token_id.clone(),
+                    second_token_id: first_token_id.clone(),
                 })
                 .unwrap_or_else(ManagedAddress::zero);
         }
 
+        self.unregister_fee_tier_pair(&pair_address);
+        self.promote_fee_tier_pair(&first_token_id, &second_token_id);
+
+        pair_address
+    }
+
+    #[only_owner]
+    #[endpoint(removePairByFeeTier)]
+    fn remove_pair_by_fee_tier(
+        &self,
+        first_token_id: TokenIdentifier,
+        second_token_id: TokenIdentifier,
+        fee_tier: u64,
+    ) -> ManagedAddress {
+        require!(self.is_active(), "Not active");
+
+        let pair_address =
+            self.get_pair_by_fee_tier(first_token_id.clone(), second_token_id.clone(), fee_tier);
+        require!(!pair_address.is_zero(), "Pair does not exists");
+
+        let default_pair_address = self.get_pair(first_token_id, second_token_id);
+        require!(
+            pair_address != default_pair_address,
+            "Use removePair for the default pair"
+        );
+
+        self.unregister_fee_tier_pair(&pair_address);
+        self.non_default_fee_tier_pairs_count()
+            .update(|count| *count -= 1);
+
         pair_address
     }
 
+    /// Allows pairs to be created with the given total fee percent,
+    /// which also identifies the fee tier.
+    #[only_owner]
+    #[endpoint(addFeeTier)]
+    fn add_fee_tier(&self, total_fee_percent: u64, special_fee_percent: u64) {
+        require!(
+            total_fee_percent >= special_fee_percent && total_fee_percent < MAX_TOTAL_FEE_PERCENT,
+            "Bad percents"
+        );
+
+        self.fee_tiers()
+            .insert(total_fee_percent, special_fee_percent);
+    }
+
+    #[only_owner]
+    #[endpoint(removeFeeTier)]
+    fn remove_fee_tier(&self, total_fee_percent: u64) {
+        require!(
+            self.fee_tier_pairs_count(total_fee_percent).get() == 0,
+            "Fee tier still has pairs"
+        );
+
+        let removed = self.fee_tiers().remove(&total_fee_percent);
+        require!(removed.is_some(), "Unknown fee tier");
+    }
+
+    #[view(getFeeTiers)]
+    fn get_fee_tiers(&self) -> MultiValueEncoded<MultiValue2<u64, u64>> {
+        let mut result = MultiValueEncoded::new();
+        for (total_fee_percent, special_fee_percent) in self.fee_tiers().iter() {
+            result.push((total_fee_percent, special_fee_percent).into());
+        }
+
+        result
+    }
+
     #[only_owner]
     #[endpoint(setFeeOn)]
     fn set_fee_on(
@@ -335,6 +527,22 @@ pub trait Router:
             .execute_on_dest_context();
     }
 
+    /// Sets the energy factory that the pair queries for the fee discounts of its swaps.
+    #[only_owner]
+    #[endpoint(setPairEnergyFactoryAddress)]
+    fn set_pair_energy_factory_address(
+        &self,
+        pair_address: ManagedAddress,
+        energy_factory_address: ManagedAddress,
+    ) {
+        self.check_is_pair_sc(&pair_address);
+
+        let _: IgnoreValue = self
+            .pair_contract_proxy(pair_address)
+            .set_energy_factory_address(energy_factory_address)
+            .execute_on_dest_context();
+    }
+
     #[callback]
     fn lp_token_issue_callback(
         &self,
@@ -229,9 +229,22 @@ pub trait EnableSwapByUserModule:
     }
 
     fn set_fee_percents(&self, pair_address: ManagedAddress) {
+        let mut total_fee_percent = USER_DEFINED_TOTAL_FEE_PERCENT;
+        let mut special_fee_percent = DEFAULT_SPECIAL_FEE_PERCENT;
+
+        // pairs deployed with a fee tier keep the fees chosen at creation
+        let fee_tier_mapper = self.pair_fee_tier(&pair_address);
+        if !fee_tier_mapper.is_empty() {
+            let fee_tier = fee_tier_mapper.get();
+            if let Some(tier_special_fee_percent) = self.fee_tiers().get(&fee_tier) {
+                total_fee_percent = fee_tier;
+                special_fee_percent = tier_special_fee_percent;
+            }
+        }
+
         let _: IgnoreValue = self
             .user_pair_proxy(pair_address)
-            .set_fee_percent(USER_DEFINED_TOTAL_FEE_PERCENT, DEFAULT_SPECIAL_FEE_PERCENT)
+            .set_fee_percent(total_fee_percent, special_fee_percent)
             .execute_on_dest_context();
     }
 
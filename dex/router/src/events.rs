@@ -22,6 +22,16 @@ pub struct UserPairSwapEnabledEvent<M: ManagedTypeApi> {
     pair_address: ManagedAddress<M>,
 }
 
+#[derive(TypeAbi, TopEncode)]
+pub struct PairAdminOperationEvent<M: ManagedTypeApi> {
+    caller: ManagedAddress<M>,
+    operation: ManagedBuffer<M>,
+    pair_address: ManagedAddress<M>,
+    block: u64,
+    epoch: u64,
+    timestamp: u64,
+}
+
 #[multiversx_sc::module]
 pub trait EventsModule {
     fn emit_create_pair_event(
@@ -75,6 +85,29 @@ pub trait EventsModule {
         )
     }
 
+    fn emit_pair_admin_operation_event(
+        &self,
+        operation: ManagedBuffer,
+        pair_address: ManagedAddress,
+    ) {
+        let caller = self.blockchain().get_caller();
+        let epoch = self.blockchain().get_block_epoch();
+        self.pair_admin_operation_event(
+            operation.clone(),
+            pair_address.clone(),
+            caller.clone(),
+            epoch,
+            PairAdminOperationEvent {
+                caller,
+                operation,
+                pair_address,
+                block: self.blockchain().get_block_nonce(),
+                epoch,
+                timestamp: self.blockchain().get_block_timestamp(),
+            },
+        )
+    }
+
     #[event("create_pair")]
     fn create_pair_event(
         self,
@@ -94,4 +127,14 @@ pub trait EventsModule {
         #[indexed] epoch: u64,
         swap_enabled_event: UserPairSwapEnabledEvent<Self::Api>,
     );
+
+    #[event("pairAdminOperation")]
+    fn pair_admin_operation_event(
+        &self,
+        #[indexed] operation: ManagedBuffer,
+        #[indexed] pair_address: ManagedAddress,
+        #[indexed] caller: ManagedAddress,
+        #[indexed] epoch: u64,
+        pair_admin_operation_event: PairAdminOperationEvent<Self::Api>,
+    );
 }
@@ -12,11 +12,18 @@ pub struct PairTokens<M: ManagedTypeApi> {
     pub second_token_id: TokenIdentifier<M>,
 }
 
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, TypeAbi)]
+pub struct FeeTierPairTokens<M: ManagedTypeApi> {
+    pub first_token_id: TokenIdentifier<M>,
+    pub second_token_id: TokenIdentifier<M>,
+    pub fee_tier: u64,
+}
+
 #[derive(ManagedVecItem, TopEncode, TopDecode, PartialEq, TypeAbi)]
 pub struct PairContractMetadata<M: ManagedTypeApi> {
-    first_token_id: TokenIdentifier<M>,
-    second_token_id: TokenIdentifier<M>,
-    address: ManagedAddress<M>,
+    pub first_token_id: TokenIdentifier<M>,
+    pub second_token_id: TokenIdentifier<M>,
+    pub address: ManagedAddress<M>,
 }
 
 #[multiversx_sc::module]
@@ -42,6 +49,128 @@ pub trait FactoryModule: config::ConfigModule + read_pair_storage::ReadPairStora
         special_fee_percent: u64,
         initial_liquidity_adder: &ManagedAddress,
         admins: MultiValueEncoded<ManagedAddress>,
+    ) -> ManagedAddress {
+        let new_address = self.deploy_pair(
+            first_token_id,
+            second_token_id,
+            owner,
+            total_fee_percent,
+            special_fee_percent,
+            initial_liquidity_adder,
+            admins,
+        );
+
+        self.pair_map().insert(
+            PairTokens {
+                first_token_id: first_token_id.clone(),
+                second_token_id: second_token_id.clone(),
+            },
+            new_address.clone(),
+        );
+
+        new_address
+    }
+
+    /// Deploys a pair with one of the configured fee tiers. The first pair created for
+    /// a token pair is also its default pair, returned by `getPair`.
+    fn create_fee_tier_pair(
+        &self,
+        first_token_id: &TokenIdentifier,
+        second_token_id: &TokenIdentifier,
+        owner: &ManagedAddress,
+        fee_tier: u64,
+        initial_liquidity_adder: &ManagedAddress,
+        admins: MultiValueEncoded<ManagedAddress>,
+    ) -> ManagedAddress {
+        let special_fee_percent_opt = self.fee_tiers().get(&fee_tier);
+        require!(special_fee_percent_opt.is_some(), "Unknown fee tier");
+
+        let pair_address =
+            self.get_pair_by_fee_tier(first_token_id.clone(), second_token_id.clone(), fee_tier);
+        require!(pair_address.is_zero(), "Pair already exists");
+
+        let special_fee_percent = unsafe { special_fee_percent_opt.unwrap_unchecked() };
+        let default_pair_address = self.get_pair(first_token_id.clone(), second_token_id.clone());
+        let new_address = if default_pair_address.is_zero() {
+            self.create_pair(
+                first_token_id,
+                second_token_id,
+                owner,
+                fee_tier,
+                special_fee_percent,
+                initial_liquidity_adder,
+                admins,
+            )
+        } else {
+            self.non_default_fee_tier_pairs_count()
+                .update(|count| *count += 1);
+            self.deploy_pair(
+                first_token_id,
+                second_token_id,
+                owner,
+                fee_tier,
+                special_fee_percent,
+                initial_liquidity_adder,
+                admins,
+            )
+        };
+        self.register_fee_tier_pair(first_token_id, second_token_id, fee_tier, &new_address);
+
+        new_address
+    }
+
+    fn register_fee_tier_pair(
+        &self,
+        first_token_id: &TokenIdentifier,
+        second_token_id: &TokenIdentifier,
+        fee_tier: u64,
+        pair_address: &ManagedAddress,
+    ) {
+        self.fee_tier_pair_map().insert(
+            FeeTierPairTokens {
+                first_token_id: first_token_id.clone(),
+                second_token_id: second_token_id.clone(),
+                fee_tier,
+            },
+            pair_address.clone(),
+        );
+        self.pair_fee_tier(pair_address).set(fee_tier);
+        self.fee_tier_pairs_count(fee_tier)
+            .update(|count| *count += 1);
+    }
+
+    fn unregister_fee_tier_pair(&self, pair_address: &ManagedAddress) {
+        let fee_tier_mapper = self.pair_fee_tier(pair_address);
+        if fee_tier_mapper.is_empty() {
+            return;
+        }
+
+        let fee_tier = fee_tier_mapper.take();
+        self.fee_tier_pairs_count(fee_tier)
+            .update(|count| *count -= 1);
+        let first_token_id = self.get_first_token_id_mapper(pair_address.clone()).get();
+        let second_token_id = self.get_second_token_id_mapper(pair_address.clone()).get();
+        let _ = self.fee_tier_pair_map().remove(&FeeTierPairTokens {
+            first_token_id: first_token_id.clone(),
+            second_token_id: second_token_id.clone(),
+            fee_tier,
+        });
+        let _ = self.fee_tier_pair_map().remove(&FeeTierPairTokens {
+            first_token_id: second_token_id,
+            second_token_id: first_token_id,
+            fee_tier,
+        });
+    }
+
+    fn deploy_pair(
+        &self,
+        first_token_id: &TokenIdentifier,
+        second_token_id: &TokenIdentifier,
+        owner: &ManagedAddress,
+        total_fee_percent: u64,
+        special_fee_percent: u64,
+        initial_liquidity_adder: &ManagedAddress,
+        admins: MultiValueEncoded<ManagedAddress>,
     ) -> ManagedAddress {
         require!(
             !self.pair_template_address().is_empty(),
@@ -65,13 +194,8 @@ pub trait FactoryModule: config::ConfigModule + read_pair_storage::ReadPairStora
                 CodeMetadata::UPGRADEABLE | CodeMetadata::READABLE | CodeMetadata::PAYABLE_BY_SC,
             );
 
-        self.pair_map().insert(
-            PairTokens {
-                first_token_id: first_token_id.clone(),
-                second_token_id: second_token_id.clone(),
-            },
-            new_address.clone(),
-        );
+        self.pair_creation_timestamp(&new_address)
+            .set(self.blockchain().get_block_timestamp());
         self.pair_temporary_owner().insert(
             new_address.clone(),
             (
@@ -100,6 +224,11 @@ pub trait FactoryModule: config::ConfigModule + read_pair_storage::ReadPairStora
         for pair in self.pair_map().values() {
             result.push(pair);
         }
+        for (tokens, pair) in self.fee_tier_pair_map().iter() {
+            if !self.is_default_pair(&tokens, &pair) {
+                result.push(pair);
+            }
+        }
         result
     }
 
@@ -123,9 +252,75 @@ pub trait FactoryModule: config::ConfigModule + read_pair_storage::ReadPairStora
             };
             result.push(pair_metadata);
         }
+        for (k, v) in self.fee_tier_pair_map().iter() {
+            if self.is_default_pair(&k, &v) {
+                continue;
+            }
+
+            let pair_metadata = PairContractMetadata {
+                first_token_id: k.first_token_id,
+                second_token_id: k.second_token_id,
+                address: v,
+            };
+            result.push(pair_metadata);
+        }
         result
     }
 
+    /// Fee tier pairs that are also the default pair of their tokens are listed in both maps
+    fn is_default_pair(
+        &self,
+        tokens: &FeeTierPairTokens<Self::Api>,
+        pair_address: &ManagedAddress,
+    ) -> bool {
+        let default_pair_address = self.get_pair(
+            tokens.first_token_id.clone(),
+            tokens.second_token_id.clone(),
+        );
+
+        &default_pair_address == pair_address
+    }
+
+    /// Makes the pair with the lowest fee tier the default pair of the tokens, if there is any
+    fn promote_fee_tier_pair(
+        &self,
+        first_token_id: &TokenIdentifier,
+        second_token_id: &TokenIdentifier,
+    ) {
+        let mut new_default_pair_address = ManagedAddress::zero();
+        let mut lowest_fee_tier = u64::MAX;
+        for fee_tier in self.fee_tiers().keys() {
+            if fee_tier >= lowest_fee_tier {
+                continue;
+            }
+
+            let pair_address = self.get_pair_by_fee_tier(
+                first_token_id.clone(),
+                second_token_id.clone(),
+                fee_tier,
+            );
+            if !pair_address.is_zero() {
+                new_default_pair_address = pair_address;
+                lowest_fee_tier = fee_tier;
+            }
+        }
+
+        if new_default_pair_address.is_zero() {
+            return;
+        }
+
+        self.non_default_fee_tier_pairs_count()
+            .update(|count| *count -= 1);
+
+        self.pair_map().insert(
+            PairTokens {
+                first_token_id: first_token_id.clone(),
+                second_token_id: second_token_id.clone(),
+            },
+            new_default_pair_address,
+        );
+    }
+
     #[view(getPair)]
     fn get_pair(
         &self,
@@ -152,6 +347,35 @@ pub trait FactoryModule: config::ConfigModule + read_pair_storage::ReadPairStora
         address
     }
 
+    #[view(getPairByFeeTier)]
+    fn get_pair_by_fee_tier(
+        &self,
+        first_token_id: TokenIdentifier,
+        second_token_id: TokenIdentifier,
+        fee_tier: u64,
+    ) -> ManagedAddress {
+        let mut address = self
+            .fee_tier_pair_map()
+            .get(&FeeTierPairTokens {
+                first_token_id: first_token_id.clone(),
+                second_token_id: second_token_id.clone(),
+                fee_tier,
+            })
+            .unwrap_or_else(ManagedAddress::zero);
+
+        if address.is_zero() {
+            address = self
+                .fee_tier_pair_map()
+                .get(&FeeTierPairTokens {
+                    first_token_id: second_token_id,
+                    second_token_id: first_token_id,
+                    fee_tier,
+                })
+                .unwrap_or_else(ManagedAddress::zero);
+        }
+        address
+    }
+
     fn get_pair_temporary_owner(&self, pair_address: &ManagedAddress) -> Option<ManagedAddress> {
         let result = self.pair_temporary_owner().get(pair_address);
 
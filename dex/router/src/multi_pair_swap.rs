@@ -2,12 +2,25 @@ multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
 use super::factory;
-use crate::config;
+use crate::{
+    config,
+    path_finder::{self, SwapRoute, MAX_ROUTE_INTERMEDIATE_TOKENS},
+};
 use pair::{pair_actions::swap::ProxyTrait as _, read_pair_storage};
 
 type SwapOperationType<M> =
     MultiValue4<ManagedAddress<M>, ManagedBuffer<M>, TokenIdentifier<M>, BigUint<M>>;
 
+pub type SplitRouteType<M> = MultiValue2<BigUint<M>, ManagedVec<M, SwapOperation<M>>>;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, ManagedVecItem, Clone)]
+pub struct SwapOperation<M: ManagedTypeApi> {
+    pub pair_address: ManagedAddress<M>,
+    pub function: ManagedBuffer<M>,
+    pub token_wanted: TokenIdentifier<M>,
+    pub amount_wanted: BigUint<M>,
+}
+
 pub const SWAP_TOKENS_FIXED_INPUT_FUNC_NAME: &[u8] = b"swapTokensFixedInput";
 pub const SWAP_TOKENS_FIXED_OUTPUT_FUNC_NAME: &[u8] = b"swapTokensFixedOutput";
 
@@ -16,6 +29,7 @@ pub trait MultiPairSwap:
     config::ConfigModule
     + read_pair_storage::ReadPairStorageModule
     + factory::FactoryModule
+    + path_finder::PathFinderModule
     + token_send::TokenSendModule
 {
     #[payable("*")]
@@ -23,6 +37,50 @@ pub trait MultiPairSwap:
     fn multi_pair_swap(
         &self,
         swap_operations: MultiValueEncoded<SwapOperationType<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        let (mut payments, last_payment) = self.perform_multi_pair_swap(swap_operations);
+
+        let caller = self.blockchain().get_caller();
+        payments.push(last_payment);
+        self.send().direct_multi(&caller, &payments);
+
+        payments
+    }
+
+    #[payable("*")]
+    #[endpoint(multiPairSwapWithDeadline)]
+    fn multi_pair_swap_with_deadline(
+        &self,
+        min_final_amount: BigUint,
+        deadline_timestamp: u64,
+        swap_operations: MultiValueEncoded<SwapOperationType<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment> {
+        require!(
+            self.blockchain().get_block_timestamp() <= deadline_timestamp,
+            "Deadline exceeded"
+        );
+        require!(
+            min_final_amount > 0u64,
+            "Invalid min final amount. Should not be zero"
+        );
+
+        let (mut payments, last_payment) = self.perform_multi_pair_swap(swap_operations);
+        require!(last_payment.amount >= min_final_amount, "Slippage exceeded");
+
+        let caller = self.blockchain().get_caller();
+        payments.push(last_payment);
+        self.send().direct_multi(&caller, &payments);
+
+        payments
+    }
+
+    #[payable("*")]
+    #[endpoint(multiPairSwapSplit)]
+    fn multi_pair_swap_split(
+        &self,
+        token_out: TokenIdentifier,
+        amount_out_min: BigUint,
+        split_routes: MultiValueEncoded<SplitRouteType<Self::Api>>,
     ) -> ManagedVec<EsdtTokenPayment> {
         require!(self.is_active(), "Not active");
 
@@ -30,49 +88,200 @@ pub trait MultiPairSwap:
         require!(nonce == 0, "Invalid nonce. Should be zero");
         require!(amount > 0u64, "Invalid amount. Should not be zero");
         require!(
-            !swap_operations.is_empty(),
-            "Invalid swap operations chain. Should not be empty"
+            !split_routes.is_empty(),
+            "Invalid split routes. Should not be empty"
         );
 
-        let swap_fixed_input_endpoint = ManagedBuffer::from(SWAP_TOKENS_FIXED_INPUT_FUNC_NAME);
-        let swap_fixed_output_endpoint = ManagedBuffer::from(SWAP_TOKENS_FIXED_OUTPUT_FUNC_NAME);
+        let caller = self.blockchain().get_caller();
+        let mut payments = ManagedVec::new();
+        let mut total_amount_in = BigUint::zero();
+        let mut total_amount_out = BigUint::zero();
+
+        for split_route in split_routes.into_iter() {
+            let (route_amount_in, swap_operations) = split_route.into_tuple();
+            require!(
+                route_amount_in > 0u64,
+                "Invalid route amount. Should not be zero"
+            );
+            require!(
+                !swap_operations.is_empty(),
+                "Invalid swap operations chain. Should not be empty"
+            );
+
+            total_amount_in += &route_amount_in;
+            let mut last_payment = EsdtTokenPayment::new(token_id.clone(), nonce, route_amount_in);
+            for swap_operation in swap_operations.iter() {
+                last_payment = self.execute_swap_operation(
+                    last_payment,
+                    swap_operation.pair_address,
+                    swap_operation.function,
+                    swap_operation.token_wanted,
+                    swap_operation.amount_wanted,
+                    &mut payments,
+                );
+            }
+
+            require!(
+                last_payment.token_identifier == token_out,
+                "Invalid route output token"
+            );
+            total_amount_out += last_payment.amount;
+        }
+
+        require!(
+            total_amount_in == amount,
+            "Route amounts do not match the payment"
+        );
+        require!(total_amount_out >= amount_out_min, "Slippage exceeded");
+
+        payments.push(EsdtTokenPayment::new(token_out, 0, total_amount_out));
+        self.send().direct_multi(&caller, &payments);
+
+        payments
+    }
+
+    /// Swaps through the best route found by `getBestPathFixedInput`, but only searches
+    /// the pairs between the payment token, `token_out` and the given intermediate tokens,
+    /// e.g. the intermediate tokens of the route returned by the view.
+    #[payable("*")]
+    #[endpoint(swapAutoRouted)]
+    fn swap_auto_routed(
+        &self,
+        token_out: TokenIdentifier,
+        amount_out_min: BigUint,
+        max_hops: usize,
+        intermediate_tokens: MultiValueEncoded<TokenIdentifier>,
+    ) -> EsdtTokenPayment {
+        require!(self.is_active(), "Not active");
+
+        let (token_id, nonce, amount) = self.call_value().single_esdt().into_tuple();
+        require!(nonce == 0, "Invalid nonce. Should be zero");
+        require!(amount > 0u64, "Invalid amount. Should not be zero");
+        require!(
+            amount_out_min > 0u64,
+            "Invalid amount out min. Should not be zero"
+        );
+
+        require!(
+            intermediate_tokens.len() <= MAX_ROUTE_INTERMEDIATE_TOKENS,
+            "Too many intermediate tokens"
+        );
+
+        let mut route_tokens = ManagedVec::new();
+        route_tokens.push(token_id.clone());
+        route_tokens.push(token_out.clone());
+        for intermediate_token in intermediate_tokens {
+            if !route_tokens.contains(&intermediate_token) {
+                route_tokens.push(intermediate_token);
+            }
+        }
+
+        let pair_graph = self.load_pair_subgraph(&route_tokens);
+        let route = self.find_best_route(&pair_graph, &token_id, &amount, &token_out, max_hops);
+        require!(!route.hops.is_empty(), "No swap route found");
+
+        let payment = EsdtTokenPayment::new(token_id, nonce, amount);
+        let last_payment = self.execute_swap_route(&route, payment);
+        require!(last_payment.amount >= amount_out_min, "Slippage exceeded");
 
         let caller = self.blockchain().get_caller();
+        self.send().direct_esdt(
+            &caller,
+            &last_payment.token_identifier,
+            last_payment.token_nonce,
+            &last_payment.amount,
+        );
+
+        last_payment
+    }
+
+    fn perform_multi_pair_swap(
+        &self,
+        swap_operations: MultiValueEncoded<SwapOperationType<Self::Api>>,
+    ) -> (ManagedVec<EsdtTokenPayment>, EsdtTokenPayment) {
+        require!(self.is_active(), "Not active");
+
+        let (token_id, nonce, amount) = self.call_value().single_esdt().into_tuple();
+        require!(nonce == 0, "Invalid nonce. Should be zero");
+        require!(amount > 0u64, "Invalid amount. Should not be zero");
+        require!(
+            !swap_operations.is_empty(),
+            "Invalid swap operations chain. Should not be empty"
+        );
+
         let mut payments = ManagedVec::new();
         let mut last_payment = EsdtTokenPayment::new(token_id, nonce, amount);
 
         for entry in swap_operations.into_iter() {
             let (pair_address, function, token_wanted, amount_wanted) = entry.into_tuple();
-            self.check_is_pair_sc(&pair_address);
-
-            if function == swap_fixed_input_endpoint {
-                last_payment = self.actual_swap_fixed_input(
-                    pair_address,
-                    last_payment.token_identifier,
-                    last_payment.amount,
-                    token_wanted,
-                    amount_wanted,
-                );
-            } else if function == swap_fixed_output_endpoint {
-                let (payment, residuum) = self.actual_swap_fixed_output(
-                    pair_address,
-                    last_payment.token_identifier,
-                    last_payment.amount,
-                    token_wanted,
-                    amount_wanted,
-                );
+            last_payment = self.execute_swap_operation(
+                last_payment,
+                pair_address,
+                function,
+                token_wanted,
+                amount_wanted,
+                &mut payments,
+            );
+        }
 
-                last_payment = payment;
-                payments.push(residuum);
-            } else {
-                sc_panic!("Invalid function to call");
-            }
+        (payments, last_payment)
+    }
+
+    fn execute_swap_operation(
+        &self,
+        last_payment: EsdtTokenPayment,
+        pair_address: ManagedAddress,
+        function: ManagedBuffer,
+        token_wanted: TokenIdentifier,
+        amount_wanted: BigUint,
+        residuum_payments: &mut ManagedVec<EsdtTokenPayment>,
+    ) -> EsdtTokenPayment {
+        self.check_is_pair_sc(&pair_address);
+
+        let swap_fixed_input_endpoint = ManagedBuffer::from(SWAP_TOKENS_FIXED_INPUT_FUNC_NAME);
+        let swap_fixed_output_endpoint = ManagedBuffer::from(SWAP_TOKENS_FIXED_OUTPUT_FUNC_NAME);
+        if function == swap_fixed_input_endpoint {
+            self.actual_swap_fixed_input(
+                pair_address,
+                last_payment.token_identifier,
+                last_payment.amount,
+                token_wanted,
+                amount_wanted,
+            )
+        } else if function == swap_fixed_output_endpoint {
+            let (payment, residuum) = self.actual_swap_fixed_output(
+                pair_address,
+                last_payment.token_identifier,
+                last_payment.amount,
+                token_wanted,
+                amount_wanted,
+            );
+            residuum_payments.push(residuum);
+
+            payment
+        } else {
+            sc_panic!("Invalid function to call");
         }
+    }
 
-        payments.push(last_payment);
-        self.send().direct_multi(&caller, &payments);
+    fn execute_swap_route(
+        &self,
+        route: &SwapRoute<Self::Api>,
+        payment: EsdtTokenPayment,
+    ) -> EsdtTokenPayment {
+        let mut last_payment = payment;
+        for hop in route.hops.iter() {
+            last_payment = self.actual_swap_fixed_input(
+                hop.pair_address,
+                last_payment.token_identifier,
+                last_payment.amount,
+                hop.token_out,
+                // no per hop minimum, the caller's minimum is checked on the final output
+                BigUint::from(1u64),
+            );
+        }
 
-        payments
+        last_payment
     }
 
     fn actual_swap_fixed_input(
@@ -83,8 +292,9 @@ pub trait MultiPairSwap:
         token_out: TokenIdentifier,
         amount_out_min: BigUint,
     ) -> EsdtTokenPayment<Self::Api> {
+        let caller = self.blockchain().get_caller();
         self.pair_contract_proxy(pair_address)
-            .swap_tokens_fixed_input(token_out, amount_out_min)
+            .swap_tokens_fixed_input(token_out, amount_out_min, OptionalValue::Some(caller))
             .with_esdt_transfer((token_in, 0, amount_in))
             .execute_on_dest_context()
     }
@@ -97,9 +307,10 @@ pub trait MultiPairSwap:
         token_out: TokenIdentifier,
         amount_out: BigUint,
     ) -> (EsdtTokenPayment<Self::Api>, EsdtTokenPayment<Self::Api>) {
+        let caller = self.blockchain().get_caller();
         let call_result: MultiValue2<EsdtTokenPayment<Self::Api>, EsdtTokenPayment<Self::Api>> =
             self.pair_contract_proxy(pair_address)
-                .swap_tokens_fixed_output(token_out, amount_out)
+                .swap_tokens_fixed_output(token_out, amount_out, OptionalValue::Some(caller))
                 .with_esdt_transfer((token_in, 0, amount_in_max))
                 .execute_on_dest_context();
 
@@ -0,0 +1,214 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::{
+    config,
+    factory::{PairContractMetadata, PairTokens},
+};
+use pair::read_pair_storage;
+use pausable::State;
+
+pub const MAX_PAIRS_PAGE_SIZE: usize = 100;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct PairRegistryEntry<M: ManagedTypeApi> {
+    pub address: ManagedAddress<M>,
+    pub first_token_id: TokenIdentifier<M>,
+    pub second_token_id: TokenIdentifier<M>,
+    pub state: State,
+    pub first_token_reserve: BigUint<M>,
+    pub second_token_reserve: BigUint<M>,
+    pub lp_token_supply: BigUint<M>,
+    pub total_fee_percent: u64,
+    pub special_fee_percent: u64,
+    pub creation_timestamp: u64,
+}
+
+#[multiversx_sc::module]
+pub trait PairRegistryModule:
+    config::ConfigModule + read_pair_storage::ReadPairStorageModule
+{
+    /// Returns the addresses of the pairs among the `limit` registry entries starting at
+    /// `offset`. See `getPairRegistrySize` for how the registry is laid out.
+    #[view(getPairsAddressesPaginated)]
+    fn get_pairs_addresses_paginated(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> MultiValueEncoded<ManagedAddress> {
+        self.require_valid_page_size(limit);
+
+        let mut result = MultiValueEncoded::new();
+        self.for_each_registered_pair(offset, limit, |_, pair_address| {
+            result.push(pair_address);
+        });
+
+        result
+    }
+
+    #[view(getPairContractMetadataPaginated)]
+    fn get_pair_contract_metadata_paginated(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> MultiValueEncoded<PairContractMetadata<Self::Api>> {
+        self.require_valid_page_size(limit);
+
+        let mut result = MultiValueEncoded::new();
+        self.for_each_registered_pair(offset, limit, |pair_tokens, pair_address| {
+            result.push(PairContractMetadata {
+                first_token_id: pair_tokens.first_token_id,
+                second_token_id: pair_tokens.second_token_id,
+                address: pair_address,
+            });
+        });
+
+        result
+    }
+
+    /// Returns the full metadata of the pairs matching the filters among the `limit` registry
+    /// entries starting at `offset`. Pairs can be filtered by a minimum creation timestamp
+    /// (0 for no filter), by one of their tokens and by state.
+    #[view(getPairsMetadataPaginated)]
+    fn get_pairs_metadata_paginated(
+        &self,
+        offset: usize,
+        limit: usize,
+        created_since: u64,
+        opt_token_id: OptionalValue<TokenIdentifier>,
+        opt_state: OptionalValue<State>,
+    ) -> MultiValueEncoded<PairRegistryEntry<Self::Api>> {
+        self.require_valid_page_size(limit);
+
+        let token_id_filter = opt_token_id.into_option();
+        let state_filter = opt_state.into_option();
+        let mut result = MultiValueEncoded::new();
+        self.for_each_registered_pair(offset, limit, |pair_tokens, pair_address| {
+            if let Some(token_id) = &token_id_filter {
+                if &pair_tokens.first_token_id != token_id
+                    && &pair_tokens.second_token_id != token_id
+                {
+                    return;
+                }
+            }
+
+            let state = self.get_pair_state_mapper(pair_address.clone()).get();
+            if let Some(state_filter) = state_filter {
+                if state != state_filter {
+                    return;
+                }
+            }
+
+            let creation_timestamp = self.pair_creation_timestamp(&pair_address).get();
+            if creation_timestamp < created_since {
+                return;
+            }
+
+            result.push(self.get_pair_registry_entry(
+                pair_tokens,
+                pair_address,
+                state,
+                creation_timestamp,
+            ));
+        });
+
+        result
+    }
+
+    #[view(getPairsCount)]
+    fn get_pairs_count(&self) -> usize {
+        self.pair_map().len() + self.non_default_fee_tier_pairs_count().get()
+    }
+
+    /// The number of registry entries the paginated views go through: the default pairs,
+    /// followed by the fee tier pairs. A fee tier pair that is also the default pair of its
+    /// tokens is only returned with the default pairs, so pages may hold fewer pairs than
+    /// their `limit` even before the end of the registry.
+    #[view(getPairRegistrySize)]
+    fn get_pair_registry_size(&self) -> usize {
+        self.pair_map().len() + self.fee_tier_pair_map().len()
+    }
+
+    fn require_valid_page_size(&self, limit: usize) {
+        require!(
+            limit > 0 && limit <= MAX_PAIRS_PAGE_SIZE,
+            "Invalid page size"
+        );
+    }
+
+    /// Calls `f` for every pair among the `limit` registry entries starting at `offset`
+    fn for_each_registered_pair<F>(&self, offset: usize, limit: usize, mut f: F)
+    where
+        F: FnMut(PairTokens<Self::Api>, ManagedAddress),
+    {
+        let pair_map = self.pair_map();
+        let default_pairs_count = pair_map.len();
+        let mut remaining = limit;
+        if offset < default_pairs_count {
+            for pair_tokens in pair_map.keys().skip(offset).take(limit) {
+                let pair_address = unsafe { pair_map.get(&pair_tokens).unwrap_unchecked() };
+                f(pair_tokens, pair_address);
+                remaining -= 1;
+            }
+        }
+
+        if remaining == 0 {
+            return;
+        }
+
+        let fee_tier_pair_map = self.fee_tier_pair_map();
+        let fee_tier_pairs_offset = offset.saturating_sub(default_pairs_count);
+        for fee_tier_pair_tokens in fee_tier_pair_map
+            .keys()
+            .skip(fee_tier_pairs_offset)
+            .take(remaining)
+        {
+            let pair_address = unsafe {
+                fee_tier_pair_map
+                    .get(&fee_tier_pair_tokens)
+                    .unwrap_unchecked()
+            };
+            let pair_tokens = PairTokens {
+                first_token_id: fee_tier_pair_tokens.first_token_id,
+                second_token_id: fee_tier_pair_tokens.second_token_id,
+            };
+            if pair_map.get(&pair_tokens).as_ref() == Some(&pair_address) {
+                continue;
+            }
+
+            f(pair_tokens, pair_address);
+        }
+    }
+
+    fn get_pair_registry_entry(
+        &self,
+        pair_tokens: PairTokens<Self::Api>,
+        pair_address: ManagedAddress,
+        state: State,
+        creation_timestamp: u64,
+    ) -> PairRegistryEntry<Self::Api> {
+        let first_token_reserve = self
+            .get_pair_reserve_mapper(pair_address.clone(), &pair_tokens.first_token_id)
+            .get();
+        let second_token_reserve = self
+            .get_pair_reserve_mapper(pair_address.clone(), &pair_tokens.second_token_id)
+            .get();
+
+        PairRegistryEntry {
+            state,
+            first_token_reserve,
+            second_token_reserve,
+            lp_token_supply: self.get_lp_token_supply_mapper(pair_address.clone()).get(),
+            total_fee_percent: self
+                .get_total_fee_percent_mapper(pair_address.clone())
+                .get(),
+            special_fee_percent: self
+                .get_special_fee_percent_mapper(pair_address.clone())
+                .get(),
+            creation_timestamp,
+            first_token_id: pair_tokens.first_token_id,
+            second_token_id: pair_tokens.second_token_id,
+            address: pair_address,
+        }
+    }
+}
@@ -0,0 +1,545 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+use crate::{
+    config,
+    factory::{FeeTierPairTokens, PairTokens},
+};
+use pair::{config::MAX_PERCENTAGE, read_pair_storage, stable_swap, weighted_pool};
+use pausable::State;
+
+pub const MAX_SWAP_ROUTE_HOPS: usize = 4;
+pub const MAX_ROUTE_INTERMEDIATE_TOKENS: usize = 4;
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, ManagedVecItem, Clone)]
+pub struct SwapRouteHop<M: ManagedTypeApi> {
+    pub pair_address: ManagedAddress<M>,
+    pub token_in: TokenIdentifier<M>,
+    pub token_out: TokenIdentifier<M>,
+    pub amount_out: BigUint<M>,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, Clone)]
+pub struct SwapRoute<M: ManagedTypeApi> {
+    pub hops: ManagedVec<M, SwapRouteHop<M>>,
+    pub amount_out: BigUint<M>,
+}
+
+impl<M: ManagedTypeApi> SwapRoute<M> {
+    pub fn empty() -> Self {
+        SwapRoute {
+            hops: ManagedVec::new(),
+            amount_out: BigUint::zero(),
+        }
+    }
+}
+
+#[derive(ManagedVecItem, Clone)]
+pub struct PairEdge<M: ManagedTypeApi> {
+    pub pair_address: ManagedAddress<M>,
+    pub first_token_index: usize,
+    pub second_token_index: usize,
+    pub first_token_reserve: BigUint<M>,
+    pub second_token_reserve: BigUint<M>,
+    pub total_fee_percent: u64,
+    pub amplification: u64,
+    pub first_token_weight: u64,
+    pub second_token_weight: u64,
+}
+
+impl<M: ManagedTypeApi> PairEdge<M> {
+    pub fn get_other_token_index(&self, token_index: usize) -> usize {
+        if token_index == self.first_token_index {
+            self.second_token_index
+        } else {
+            self.first_token_index
+        }
+    }
+
+    pub fn get_reserves(&self, token_in_index: usize) -> (&BigUint<M>, &BigUint<M>) {
+        if token_in_index == self.first_token_index {
+            (&self.first_token_reserve, &self.second_token_reserve)
+        } else {
+            (&self.second_token_reserve, &self.first_token_reserve)
+        }
+    }
+
+    pub fn is_weighted(&self) -> bool {
+        self.first_token_weight != 0
+    }
+
+    pub fn get_weights(&self, token_in_index: usize) -> (u64, u64) {
+        if token_in_index == self.first_token_index {
+            (self.first_token_weight, self.second_token_weight)
+        } else {
+            (self.second_token_weight, self.first_token_weight)
+        }
+    }
+}
+
+#[derive(ManagedVecItem, Clone)]
+pub struct RouteNode<M: ManagedTypeApi> {
+    pub amount: BigUint<M>,
+    pub prev_token_index: usize,
+    pub edge_index: usize,
+}
+
+impl<M: ManagedTypeApi> Default for RouteNode<M> {
+    fn default() -> Self {
+        RouteNode {
+            amount: BigUint::zero(),
+            prev_token_index: 0,
+            edge_index: 0,
+        }
+    }
+}
+
+pub struct PairGraph<M: ManagedTypeApi> {
+    pub tokens: ManagedVec<M, TokenIdentifier<M>>,
+    pub edges: ManagedVec<M, PairEdge<M>>,
+}
+
+impl<M: ManagedTypeApi> PairGraph<M> {
+    pub fn new() -> Self {
+        PairGraph {
+            tokens: ManagedVec::new(),
+            edges: ManagedVec::new(),
+        }
+    }
+
+    pub fn find_token_index(&self, token_id: &TokenIdentifier<M>) -> Option<usize> {
+        self.tokens.find(token_id)
+    }
+
+    pub fn get_or_insert_token_index(&mut self, token_id: TokenIdentifier<M>) -> usize {
+        match self.find_token_index(&token_id) {
+            Some(index) => index,
+            None => {
+                self.tokens.push(token_id);
+                self.tokens.len() - 1
+            }
+        }
+    }
+}
+
+impl<M: ManagedTypeApi> Default for PairGraph<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[multiversx_sc::module]
+pub trait PathFinderModule:
+    config::ConfigModule + read_pair_storage::ReadPairStorageModule
+{
+    #[view(getBestPathFixedInput)]
+    fn get_best_path_fixed_input(
+        &self,
+        token_in: TokenIdentifier,
+        amount_in: BigUint,
+        token_out: TokenIdentifier,
+        max_hops: usize,
+    ) -> SwapRoute<Self::Api> {
+        let pair_graph = self.load_pair_graph();
+        let route = self.find_best_route(&pair_graph, &token_in, &amount_in, &token_out, max_hops);
+        require!(!route.hops.is_empty(), "No swap route found");
+
+        route
+    }
+
+    fn load_pair_graph(&self) -> PairGraph<Self::Api> {
+        let mut pair_graph = PairGraph::new();
+        for (pair_tokens, pair_address) in self.pair_map().iter() {
+            self.add_pair_edge(
+                &mut pair_graph,
+                pair_tokens.first_token_id,
+                pair_tokens.second_token_id,
+                pair_address,
+            );
+        }
+
+        // the default pair of each token pair was already added above
+        for (pair_tokens, pair_address) in self.fee_tier_pair_map().iter() {
+            let default_pair_address_opt = self.pair_map().get(&PairTokens {
+                first_token_id: pair_tokens.first_token_id.clone(),
+                second_token_id: pair_tokens.second_token_id.clone(),
+            });
+            if default_pair_address_opt.as_ref() == Some(&pair_address) {
+                continue;
+            }
+
+            self.add_pair_edge(
+                &mut pair_graph,
+                pair_tokens.first_token_id,
+                pair_tokens.second_token_id,
+                pair_address,
+            );
+        }
+
+        pair_graph
+    }
+
+    /// Same as `load_pair_graph`, but only with the pairs between the given tokens,
+    /// so the storage reads do not grow with the pair registry
+    fn load_pair_subgraph(
+        &self,
+        route_tokens: &ManagedVec<TokenIdentifier>,
+    ) -> PairGraph<Self::Api> {
+        let mut pair_graph = PairGraph::new();
+        for i in 0..route_tokens.len() {
+            let first_token_id = route_tokens.get(i).clone_value();
+            for j in (i + 1)..route_tokens.len() {
+                let second_token_id = route_tokens.get(j).clone_value();
+                let default_pair_address_opt =
+                    self.find_route_pair(&first_token_id, &second_token_id);
+                if let Some(pair_address) = &default_pair_address_opt {
+                    self.add_pair_edge(
+                        &mut pair_graph,
+                        first_token_id.clone(),
+                        second_token_id.clone(),
+                        pair_address.clone(),
+                    );
+                }
+
+                for fee_tier in self.fee_tiers().keys() {
+                    let pair_address = match self.find_route_fee_tier_pair(
+                        &first_token_id,
+                        &second_token_id,
+                        fee_tier,
+                    ) {
+                        Some(pair_address) => pair_address,
+                        None => continue,
+                    };
+                    if default_pair_address_opt.as_ref() == Some(&pair_address) {
+                        continue;
+                    }
+
+                    self.add_pair_edge(
+                        &mut pair_graph,
+                        first_token_id.clone(),
+                        second_token_id.clone(),
+                        pair_address,
+                    );
+                }
+            }
+        }
+
+        pair_graph
+    }
+
+    fn find_route_pair(
+        &self,
+        first_token_id: &TokenIdentifier,
+        second_token_id: &TokenIdentifier,
+    ) -> Option<ManagedAddress> {
+        let pair_map = self.pair_map();
+        pair_map
+            .get(&PairTokens {
+                first_token_id: first_token_id.clone(),
+                second_token_id: second_token_id.clone(),
+            })
+            .or_else(|| {
+                pair_map.get(&PairTokens {
+                    first_token_id: second_token_id.clone(),
+                    second_token_id: first_token_id.clone(),
+                })
+            })
+    }
+
+    fn find_route_fee_tier_pair(
+        &self,
+        first_token_id: &TokenIdentifier,
+        second_token_id: &TokenIdentifier,
+        fee_tier: u64,
+    ) -> Option<ManagedAddress> {
+        let fee_tier_pair_map = self.fee_tier_pair_map();
+        fee_tier_pair_map
+            .get(&FeeTierPairTokens {
+                first_token_id: first_token_id.clone(),
+                second_token_id: second_token_id.clone(),
+                fee_tier,
+            })
+            .or_else(|| {
+                fee_tier_pair_map.get(&FeeTierPairTokens {
+                    first_token_id: second_token_id.clone(),
+                    second_token_id: first_token_id.clone(),
+                    fee_tier,
+                })
+            })
+    }
+
+    fn add_pair_edge(
+        &self,
+        pair_graph: &mut PairGraph<Self::Api>,
+        first_token_id: TokenIdentifier,
+        second_token_id: TokenIdentifier,
+        pair_address: ManagedAddress,
+    ) {
+        let pair_state = self.get_pair_state_mapper(pair_address.clone()).get();
+        if pair_state != State::Active {
+            return;
+        }
+
+        let first_token_reserve = self
+            .get_pair_reserve_mapper(pair_address.clone(), &first_token_id)
+            .get();
+        let second_token_reserve = self
+            .get_pair_reserve_mapper(pair_address.clone(), &second_token_id)
+            .get();
+        if first_token_reserve == 0 || second_token_reserve == 0 {
+            return;
+        }
+
+        let total_fee_percent = self
+            .get_total_fee_percent_mapper(pair_address.clone())
+            .get();
+        let amplification = self.get_pair_amplification(pair_address.clone());
+        let token_weights_mapper = self.get_token_weights_mapper(pair_address.clone());
+        let (first_token_weight, second_token_weight) = if token_weights_mapper.is_empty() {
+            (0, 0)
+        } else {
+            let token_weights = token_weights_mapper.get();
+            (
+                token_weights.first_token_weight,
+                token_weights.second_token_weight,
+            )
+        };
+        let first_token_index = pair_graph.get_or_insert_token_index(first_token_id);
+        let second_token_index = pair_graph.get_or_insert_token_index(second_token_id);
+
+        pair_graph.edges.push(PairEdge {
+            pair_address,
+            first_token_index,
+            second_token_index,
+            first_token_reserve,
+            second_token_reserve,
+            total_fee_percent,
+            amplification,
+            first_token_weight,
+            second_token_weight,
+        });
+    }
+
+    fn get_pair_amplification(&self, pair_address: ManagedAddress) -> u64 {
+        let ramp_mapper = self.get_amplification_ramp_mapper(pair_address);
+        if ramp_mapper.is_empty() {
+            return 0;
+        }
+
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        ramp_mapper.get().get_amp(current_timestamp)
+    }
+
+    /// Walks the pair graph level by level, keeping for every token the best amount
+    /// reachable with exactly `level` hops. A pair is never used twice on the same route.
+    fn find_best_route(
+        &self,
+        pair_graph: &PairGraph<Self::Api>,
+        token_in: &TokenIdentifier,
+        amount_in: &BigUint,
+        token_out: &TokenIdentifier,
+        max_hops: usize,
+    ) -> SwapRoute<Self::Api> {
+        require!(token_in != token_out, "Identical tokens");
+        require!(*amount_in > 0u64, "Invalid amount. Should not be zero");
+        require!(
+            max_hops > 0 && max_hops <= MAX_SWAP_ROUTE_HOPS,
+            "Invalid number of hops"
+        );
+
+        let token_in_index = match pair_graph.find_token_index(token_in) {
+            Some(index) => index,
+            None => return SwapRoute::empty(),
+        };
+        let token_out_index = match pair_graph.find_token_index(token_out) {
+            Some(index) => index,
+            None => return SwapRoute::empty(),
+        };
+
+        let nr_tokens = pair_graph.tokens.len();
+        let mut route_nodes = ManagedVec::<Self::Api, RouteNode<Self::Api>>::new();
+        for _ in 0..(max_hops + 1) * nr_tokens {
+            route_nodes.push(RouteNode::default());
+        }
+        let _ = route_nodes.set(
+            token_in_index,
+            &RouteNode {
+                amount: amount_in.clone(),
+                prev_token_index: token_in_index,
+                edge_index: 0,
+            },
+        );
+
+        let mut best_level = 0;
+        let mut best_amount_out = BigUint::zero();
+        for level in 1..=max_hops {
+            for (edge_index, edge) in pair_graph.edges.iter().enumerate() {
+                for from_token_index in [edge.first_token_index, edge.second_token_index] {
+                    if from_token_index == token_out_index
+                        || edge.get_other_token_index(from_token_index) == token_in_index
+                    {
+                        continue;
+                    }
+
+                    self.relax_route_edge(
+                        &mut route_nodes,
+                        nr_tokens,
+                        level,
+                        edge_index,
+                        &edge,
+                        from_token_index,
+                    );
+                }
+            }
+
+            let token_out_node = route_nodes.get(level * nr_tokens + token_out_index);
+            if token_out_node.amount > best_amount_out {
+                best_amount_out = token_out_node.amount;
+                best_level = level;
+            }
+        }
+
+        if best_level == 0 {
+            return SwapRoute::empty();
+        }
+
+        self.build_route(
+            pair_graph,
+            &route_nodes,
+            nr_tokens,
+            best_level,
+            token_out_index,
+        )
+    }
+
+    fn relax_route_edge(
+        &self,
+        route_nodes: &mut ManagedVec<Self::Api, RouteNode<Self::Api>>,
+        nr_tokens: usize,
+        level: usize,
+        edge_index: usize,
+        edge: &PairEdge<Self::Api>,
+        from_token_index: usize,
+    ) {
+        let from_node = route_nodes.get((level - 1) * nr_tokens + from_token_index);
+        if from_node.amount == 0
+            || self.is_edge_on_route(
+                route_nodes,
+                nr_tokens,
+                level - 1,
+                from_token_index,
+                edge_index,
+            )
+        {
+            return;
+        }
+
+        let to_token_index = edge.get_other_token_index(from_token_index);
+        let (_, reserve_out) = edge.get_reserves(from_token_index);
+        let amount_out = self.simulate_amount_out(edge, from_token_index, &from_node.amount);
+        if amount_out == 0 || &amount_out >= reserve_out {
+            return;
+        }
+
+        let to_node_index = level * nr_tokens + to_token_index;
+        if amount_out > route_nodes.get(to_node_index).amount {
+            let _ = route_nodes.set(
+                to_node_index,
+                &RouteNode {
+                    amount: amount_out,
+                    prev_token_index: from_token_index,
+                    edge_index,
+                },
+            );
+        }
+    }
+
+    fn is_edge_on_route(
+        &self,
+        route_nodes: &ManagedVec<Self::Api, RouteNode<Self::Api>>,
+        nr_tokens: usize,
+        mut level: usize,
+        mut token_index: usize,
+        edge_index: usize,
+    ) -> bool {
+        while level > 0 {
+            let node = route_nodes.get(level * nr_tokens + token_index);
+            if node.edge_index == edge_index {
+                return true;
+            }
+
+            token_index = node.prev_token_index;
+            level -= 1;
+        }
+
+        false
+    }
+
+    fn build_route(
+        &self,
+        pair_graph: &PairGraph<Self::Api>,
+        route_nodes: &ManagedVec<Self::Api, RouteNode<Self::Api>>,
+        nr_tokens: usize,
+        mut level: usize,
+        mut token_index: usize,
+    ) -> SwapRoute<Self::Api> {
+        let amount_out = route_nodes.get(level * nr_tokens + token_index).amount;
+
+        let mut reversed_hops = ManagedVec::<Self::Api, SwapRouteHop<Self::Api>>::new();
+        while level > 0 {
+            let node = route_nodes.get(level * nr_tokens + token_index);
+            let edge = pair_graph.edges.get(node.edge_index);
+            reversed_hops.push(SwapRouteHop {
+                pair_address: edge.pair_address,
+                token_in: pair_graph.tokens.get(node.prev_token_index).clone_value(),
+                token_out: pair_graph.tokens.get(token_index).clone_value(),
+                amount_out: node.amount,
+            });
+
+            token_index = node.prev_token_index;
+            level -= 1;
+        }
+
+        let mut hops = ManagedVec::new();
+        for i in (0..reversed_hops.len()).rev() {
+            hops.push(reversed_hops.get(i));
+        }
+
+        SwapRoute { hops, amount_out }
+    }
+
+    fn simulate_amount_out(
+        &self,
+        edge: &PairEdge<Self::Api>,
+        token_in_index: usize,
+        amount_in: &BigUint,
+    ) -> BigUint {
+        let (reserve_in, reserve_out) = edge.get_reserves(token_in_index);
+        let amount_in_with_fee = amount_in * (MAX_PERCENTAGE - edge.total_fee_percent);
+        if edge.amplification != 0 {
+            let amount_in_after_fee = amount_in_with_fee / MAX_PERCENTAGE;
+            return stable_swap::get_stable_swap_amount_out(
+                edge.amplification,
+                &amount_in_after_fee,
+                reserve_in,
+                reserve_out,
+            );
+        }
+        if edge.is_weighted() {
+            let (weight_in, weight_out) = edge.get_weights(token_in_index);
+            let amount_in_after_fee = amount_in_with_fee / MAX_PERCENTAGE;
+            return weighted_pool::get_weighted_amount_out(
+                weight_in,
+                weight_out,
+                &amount_in_after_fee,
+                reserve_in,
+                reserve_out,
+            );
+        }
+
+        let numerator = &amount_in_with_fee * reserve_out;
+        let denominator = (reserve_in * MAX_PERCENTAGE) + amount_in_with_fee;
+
+        numerator / denominator
+    }
+}
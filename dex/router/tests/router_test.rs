@@ -2,7 +2,7 @@
 
 mod router_setup;
 use multiversx_sc::{
-    codec::multi_types::OptionalValue,
+    codec::multi_types::{MultiValue2, MultiValue4, OptionalValue},
     storage::mappers::StorageTokenWrapper,
     types::{
         Address, EgldOrEsdtTokenIdentifier, EsdtLocalRole, ManagedAddress, ManagedVec,
@@ -15,14 +15,26 @@ use pair::{
 };
 use pausable::{PausableModule, State};
 use router::{
-    config::ConfigModule, enable_swap_by_user::EnableSwapByUserModule, factory::PairTokens,
-    multi_pair_swap::SWAP_TOKENS_FIXED_INPUT_FUNC_NAME, Router,
+    batch_pair_ops::{BatchPairOpsModule, PairsSelection},
+    config::ConfigModule,
+    enable_swap_by_user::EnableSwapByUserModule,
+    factory::{FactoryModule, PairContractMetadata, PairTokens},
+    multi_pair_swap::{
+        MultiPairSwap, SplitRouteType, SwapOperation, SWAP_TOKENS_FIXED_INPUT_FUNC_NAME,
+    },
+    pair_registry::{PairRegistryEntry, PairRegistryModule},
+    path_finder::PathFinderModule,
+    Router,
 };
 use router_setup::*;
 
 use multiversx_sc_scenario::{
-    managed_address, managed_biguint, managed_token_id, managed_token_id_wrapped, rust_biguint,
-    whitebox_legacy::BlockchainStateWrapper, whitebox_legacy::TxTokenTransfer, DebugApi,
+    managed_address, managed_biguint, managed_buffer, managed_token_id, managed_token_id_wrapped,
+    rust_biguint,
+    scenario_format::{interpret_trait::InterpreterContext, value_interpreter::interpret_string},
+    whitebox_legacy::BlockchainStateWrapper,
+    whitebox_legacy::TxTokenTransfer,
+    DebugApi,
 };
 use simple_lock::{
     locked_token::{LockedTokenAttributes, LockedTokenModule},
@@ -113,7 +125,7 @@ fn test_router_upgrade_pair() {
         .execute_tx(&owner, &router_wrapper, &rust_zero, |sc| {
             let first_token_id = managed_token_id!(CUSTOM_TOKEN_ID);
             let second_token_id = managed_token_id!(USDC_TOKEN_ID);
-            sc.upgrade_pair_endpoint(first_token_id, second_token_id);
+            sc.upgrade_pair_endpoint(first_token_id, second_token_id, OptionalValue::None);
         })
         .assert_ok();
 
@@ -125,6 +137,275 @@ fn test_router_upgrade_pair() {
         .assert_ok();
 }
 
+#[test]
+fn test_router_create_pair_with_fee_tier() {
+    let rust_zero = rust_biguint!(0u64);
+    let mut b_mock = BlockchainStateWrapper::new();
+    let owner = b_mock.create_user_account(&rust_zero);
+    let user = b_mock.create_user_account(&rust_zero);
+
+    let router_wrapper = b_mock.create_sc_account(
+        &rust_zero,
+        Some(&owner),
+        router::contract_obj,
+        ROUTER_WASM_PATH,
+    );
+
+    let pair_template_wrapper = b_mock.create_sc_account(
+        &rust_zero,
+        Some(router_wrapper.address_ref()),
+        pair::contract_obj,
+        PAIR_WASM_PATH,
+    );
+
+    b_mock
+        .execute_tx(&owner, &pair_template_wrapper, &rust_zero, |sc| {
+            sc.init(
+                managed_token_id!(CUSTOM_TOKEN_ID),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_address!(&Address::zero()),
+                managed_address!(&owner),
+                0,
+                0,
+                managed_address!(&user),
+                MultiValueEncoded::<DebugApi, ManagedAddress<DebugApi>>::new(),
+            );
+        })
+        .assert_ok();
+
+    b_mock
+        .execute_tx(&owner, &router_wrapper, &rust_zero, |sc| {
+            sc.init(OptionalValue::Some(managed_address!(
+                pair_template_wrapper.address_ref()
+            )));
+            sc.add_fee_tier(100, 50);
+            sc.add_fee_tier(1_000, 100);
+        })
+        .assert_ok();
+
+    // users follow the same rules as for createPair
+    b_mock
+        .execute_tx(&user, &router_wrapper, &rust_zero, |sc| {
+            let _ = sc.create_pair_with_fee_tier_endpoint(
+                managed_token_id!(CUSTOM_TOKEN_ID),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_address!(&user),
+                100,
+                MultiValueEncoded::new(),
+            );
+        })
+        .assert_user_error("Pair creation is disabled");
+
+    b_mock
+        .execute_tx(&owner, &router_wrapper, &rust_zero, |sc| {
+            sc.set_pair_creation_enabled(true);
+        })
+        .assert_ok();
+
+    // unknown tier
+    b_mock
+        .execute_tx(&user, &router_wrapper, &rust_zero, |sc| {
+            let _ = sc.create_pair_with_fee_tier_endpoint(
+                managed_token_id!(CUSTOM_TOKEN_ID),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_address!(&user),
+                300,
+                MultiValueEncoded::new(),
+            );
+        })
+        .assert_user_error("Unknown fee tier");
+
+    let low_fee_pair_wrapper =
+        b_mock.prepare_deploy_from_sc(router_wrapper.address_ref(), pair::contract_obj);
+    b_mock
+        .execute_tx(&user, &router_wrapper, &rust_zero, |sc| {
+            let _ = sc.create_pair_with_fee_tier_endpoint(
+                managed_token_id!(CUSTOM_TOKEN_ID),
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_address!(&user),
+                100,
+                MultiValueEncoded::new(),
+            );
+        })
+        .assert_ok();
+
+    let high_fee_pair_wrapper =
+        b_mock.prepare_deploy_from_sc(router_wrapper.address_ref(), pair::contract_obj);
+    b_mock
+        .execute_tx(&user, &router_wrapper, &rust_zero, |sc| {
+            let _ = sc.create_pair_with_fee_tier_endpoint(
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_token_id!(CUSTOM_TOKEN_ID),
+                managed_address!(&user),
+                1_000,
+                MultiValueEncoded::new(),
+            );
+        })
+        .assert_ok();
+
+    // same tier again, in either token order
+    b_mock
+        .execute_tx(&user, &router_wrapper, &rust_zero, |sc| {
+            let _ = sc.create_pair_with_fee_tier_endpoint(
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_token_id!(CUSTOM_TOKEN_ID),
+                managed_address!(&user),
+                100,
+                MultiValueEncoded::new(),
+            );
+        })
+        .assert_user_error("Pair already exists");
+
+    b_mock
+        .execute_query(&low_fee_pair_wrapper, |sc| {
+            assert_eq!(sc.total_fee_percent().get(), 100);
+            assert_eq!(sc.special_fee_percent().get(), 50);
+        })
+        .assert_ok();
+
+    b_mock
+        .execute_query(&high_fee_pair_wrapper, |sc| {
+            assert_eq!(sc.total_fee_percent().get(), 1_000);
+            assert_eq!(sc.special_fee_percent().get(), 100);
+        })
+        .assert_ok();
+
+    b_mock
+        .execute_query(&router_wrapper, |sc| {
+            let low_fee_pair_address = managed_address!(low_fee_pair_wrapper.address_ref());
+            let high_fee_pair_address = managed_address!(high_fee_pair_wrapper.address_ref());
+
+            // the first pair created is the default one
+            assert_eq!(
+                sc.get_pair(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_token_id!(CUSTOM_TOKEN_ID)
+                ),
+                low_fee_pair_address
+            );
+            assert_eq!(
+                sc.get_pair_by_fee_tier(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_token_id!(CUSTOM_TOKEN_ID),
+                    100
+                ),
+                low_fee_pair_address
+            );
+            assert_eq!(
+                sc.get_pair_by_fee_tier(
+                    managed_token_id!(CUSTOM_TOKEN_ID),
+                    managed_token_id!(USDC_TOKEN_ID),
+                    1_000
+                ),
+                high_fee_pair_address
+            );
+            assert_eq!(sc.pair_fee_tier(&high_fee_pair_address).get(), 1_000);
+
+            sc.check_is_pair_sc(&low_fee_pair_address);
+            sc.check_is_pair_sc(&high_fee_pair_address);
+
+            let all_pairs = sc.get_all_pairs_addresses().to_vec();
+            assert_eq!(all_pairs.len(), 2);
+            assert_eq!(*all_pairs.get(0), low_fee_pair_address);
+            assert_eq!(*all_pairs.get(1), high_fee_pair_address);
+
+            assert_eq!(sc.get_pairs_count(), 2);
+            assert_eq!(sc.get_pair_registry_size(), 3);
+            // the fee tier entry of the default pair is only scanned
+            assert!(sc.get_pairs_addresses_paginated(1, 1).is_empty());
+            let next_page = sc.get_pairs_addresses_paginated(1, 10).to_vec();
+            assert_eq!(next_page.len(), 1);
+            assert_eq!(*next_page.get(0), high_fee_pair_address);
+        })
+        .assert_ok();
+
+    b_mock
+        .execute_tx(&owner, &router_wrapper, &rust_zero, |sc| {
+            let _ = sc.remove_pair_by_fee_tier(
+                managed_token_id!(CUSTOM_TOKEN_ID),
+                managed_token_id!(USDC_TOKEN_ID),
+                1_000,
+            );
+        })
+        .assert_ok();
+
+    b_mock
+        .execute_query(&router_wrapper, |sc| {
+            sc.check_is_pair_sc(&managed_address!(high_fee_pair_wrapper.address_ref()));
+        })
+        .assert_user_error("Not a pair SC");
+
+    // removing the default pair makes the remaining pair with the lowest fee tier the default
+    let new_high_fee_pair_wrapper =
+        b_mock.prepare_deploy_from_sc(router_wrapper.address_ref(), pair::contract_obj);
+    b_mock
+        .execute_tx(&user, &router_wrapper, &rust_zero, |sc| {
+            let _ = sc.create_pair_with_fee_tier_endpoint(
+                managed_token_id!(USDC_TOKEN_ID),
+                managed_token_id!(CUSTOM_TOKEN_ID),
+                managed_address!(&user),
+                1_000,
+                MultiValueEncoded::new(),
+            );
+        })
+        .assert_ok();
+
+    b_mock
+        .execute_tx(&owner, &router_wrapper, &rust_zero, |sc| {
+            let removed_pair_address = sc.remove_pair(
+                managed_token_id!(CUSTOM_TOKEN_ID),
+                managed_token_id!(USDC_TOKEN_ID),
+            );
+            assert_eq!(
+                removed_pair_address,
+                managed_address!(low_fee_pair_wrapper.address_ref())
+            );
+        })
+        .assert_ok();
+
+    b_mock
+        .execute_query(&router_wrapper, |sc| {
+            let new_high_fee_pair_address =
+                managed_address!(new_high_fee_pair_wrapper.address_ref());
+            assert_eq!(
+                sc.get_pair(
+                    managed_token_id!(CUSTOM_TOKEN_ID),
+                    managed_token_id!(USDC_TOKEN_ID)
+                ),
+                new_high_fee_pair_address
+            );
+            assert_eq!(
+                sc.get_pair_by_fee_tier(
+                    managed_token_id!(CUSTOM_TOKEN_ID),
+                    managed_token_id!(USDC_TOKEN_ID),
+                    100
+                ),
+                ManagedAddress::zero()
+            );
+
+            let all_pairs = sc.get_all_pairs_addresses().to_vec();
+            assert_eq!(all_pairs.len(), 1);
+            assert_eq!(sc.get_pairs_count(), 1);
+            assert_eq!(*all_pairs.get(0), new_high_fee_pair_address);
+        })
+        .assert_ok();
+
+    // a fee tier can only be removed once none of its pairs is left
+    b_mock
+        .execute_tx(&owner, &router_wrapper, &rust_zero, |sc| {
+            sc.remove_fee_tier(1_000);
+        })
+        .assert_user_error("Fee tier still has pairs");
+
+    b_mock
+        .execute_tx(&owner, &router_wrapper, &rust_zero, |sc| {
+            sc.remove_fee_tier(100);
+            assert_eq!(sc.fee_tier_pairs_count(100).get(), 0);
+            assert_eq!(sc.fee_tier_pairs_count(1_000).get(), 1);
+        })
+        .assert_ok();
+}
+
 #[test]
 fn test_multi_pair_swap() {
     let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
@@ -181,6 +462,636 @@ fn test_multi_pair_swap() {
     );
 }
 
+#[test]
+fn test_get_best_path_fixed_input() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+
+    router_setup.add_liquidity();
+
+    let mex_pair_address = router_setup.mex_pair_wrapper.address_ref().clone();
+    let usdc_pair_address = router_setup.usdc_pair_wrapper.address_ref().clone();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.router_wrapper, |sc| {
+            let route = sc.get_best_path_fixed_input(
+                managed_token_id!(MEX_TOKEN_ID),
+                managed_biguint!(100_000),
+                managed_token_id!(USDC_TOKEN_ID),
+                3,
+            );
+            assert_eq!(route.amount_out, managed_biguint!(82_909));
+            assert_eq!(route.hops.len(), 2);
+
+            let first_hop = route.hops.get(0);
+            assert_eq!(first_hop.pair_address, managed_address!(&mex_pair_address));
+            assert_eq!(first_hop.token_in, managed_token_id!(MEX_TOKEN_ID));
+            assert_eq!(first_hop.token_out, managed_token_id!(WEGLD_TOKEN_ID));
+            assert_eq!(first_hop.amount_out, managed_biguint!(90_669));
+
+            let second_hop = route.hops.get(1);
+            assert_eq!(
+                second_hop.pair_address,
+                managed_address!(&usdc_pair_address)
+            );
+            assert_eq!(second_hop.token_in, managed_token_id!(WEGLD_TOKEN_ID));
+            assert_eq!(second_hop.token_out, managed_token_id!(USDC_TOKEN_ID));
+            assert_eq!(second_hop.amount_out, managed_biguint!(82_909));
+        })
+        .assert_ok();
+
+    // no direct MEX - USDC pair
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.router_wrapper, |sc| {
+            let _ = sc.get_best_path_fixed_input(
+                managed_token_id!(MEX_TOKEN_ID),
+                managed_biguint!(100_000),
+                managed_token_id!(USDC_TOKEN_ID),
+                1,
+            );
+        })
+        .assert_user_error("No swap route found");
+}
+
+#[test]
+fn test_pair_registry_paginated_views() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+    router_setup.add_liquidity();
+
+    let mex_pair_address = router_setup.mex_pair_wrapper.address_ref().clone();
+    let usdc_pair_address = router_setup.usdc_pair_wrapper.address_ref().clone();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &router_setup.owner_address,
+            &router_setup.usdc_pair_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.state().set(State::Inactive);
+            },
+        )
+        .assert_ok();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(
+            &router_setup.owner_address,
+            &router_setup.router_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.pair_creation_timestamp(&managed_address!(&usdc_pair_address))
+                    .set(100);
+            },
+        )
+        .assert_ok();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.router_wrapper, |sc| {
+            assert_eq!(sc.get_pairs_count(), 2);
+
+            let first_page = sc
+                .get_pairs_addresses_paginated(0, 1)
+                .into_iter()
+                .collect::<Vec<_>>();
+            assert_eq!(first_page.len(), 1);
+            assert_eq!(first_page[0], managed_address!(&mex_pair_address));
+
+            let second_page = sc
+                .get_pairs_addresses_paginated(1, 10)
+                .into_iter()
+                .collect::<Vec<_>>();
+            assert_eq!(second_page.len(), 1);
+            assert_eq!(second_page[0], managed_address!(&usdc_pair_address));
+
+            assert!(sc.get_pairs_addresses_paginated(2, 10).is_empty());
+
+            let pairs_metadata = sc
+                .get_pair_contract_metadata_paginated(1, 1)
+                .into_iter()
+                .collect::<Vec<_>>();
+            assert!(
+                pairs_metadata[0]
+                    == PairContractMetadata {
+                        first_token_id: managed_token_id!(WEGLD_TOKEN_ID),
+                        second_token_id: managed_token_id!(USDC_TOKEN_ID),
+                        address: managed_address!(&usdc_pair_address),
+                    }
+            );
+
+            let usdc_pairs = sc
+                .get_pairs_metadata_paginated(
+                    0,
+                    10,
+                    0,
+                    OptionalValue::Some(managed_token_id!(USDC_TOKEN_ID)),
+                    OptionalValue::None,
+                )
+                .into_iter()
+                .collect::<Vec<_>>();
+            assert_eq!(usdc_pairs.len(), 1);
+            assert_eq!(
+                usdc_pairs[0],
+                PairRegistryEntry {
+                    address: managed_address!(&usdc_pair_address),
+                    first_token_id: managed_token_id!(WEGLD_TOKEN_ID),
+                    second_token_id: managed_token_id!(USDC_TOKEN_ID),
+                    state: State::Inactive,
+                    first_token_reserve: managed_biguint!(ADD_LIQUIDITY_TOKENS),
+                    second_token_reserve: managed_biguint!(ADD_LIQUIDITY_TOKENS),
+                    lp_token_supply: managed_biguint!(ADD_LIQUIDITY_TOKENS),
+                    total_fee_percent: 300,
+                    special_fee_percent: 50,
+                    creation_timestamp: 100,
+                }
+            );
+
+            let active_pairs = sc
+                .get_pairs_metadata_paginated(
+                    0,
+                    10,
+                    0,
+                    OptionalValue::Some(managed_token_id!(WEGLD_TOKEN_ID)),
+                    OptionalValue::Some(State::Active),
+                )
+                .into_iter()
+                .collect::<Vec<_>>();
+            assert_eq!(active_pairs.len(), 1);
+            assert_eq!(active_pairs[0].address, managed_address!(&mex_pair_address));
+
+            let recent_pairs = sc
+                .get_pairs_metadata_paginated(0, 10, 50, OptionalValue::None, OptionalValue::None)
+                .into_iter()
+                .collect::<Vec<_>>();
+            assert_eq!(recent_pairs.len(), 1);
+            assert_eq!(
+                recent_pairs[0].address,
+                managed_address!(&usdc_pair_address)
+            );
+
+            let wegld_pairs = sc
+                .get_pairs_metadata_paginated(
+                    1,
+                    10,
+                    0,
+                    OptionalValue::Some(managed_token_id!(WEGLD_TOKEN_ID)),
+                    OptionalValue::None,
+                )
+                .into_iter()
+                .collect::<Vec<_>>();
+            assert_eq!(wegld_pairs.len(), 1);
+        })
+        .assert_ok();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.router_wrapper, |sc| {
+            let _ = sc.get_pairs_addresses_paginated(0, 0);
+        })
+        .assert_user_error("Invalid page size");
+}
+
+#[test]
+fn test_batch_pause_and_resume_pairs() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+    let rust_zero = rust_biguint!(0);
+    let owner = router_setup.owner_address.clone();
+    let router_address = router_setup.router_wrapper.address_ref().clone();
+    let mex_pair_address = router_setup.mex_pair_wrapper.address_ref().clone();
+
+    for pair_wrapper in [
+        &router_setup.mex_pair_wrapper,
+        &router_setup.usdc_pair_wrapper,
+    ] {
+        router_setup
+            .blockchain_wrapper
+            .execute_tx(&owner, pair_wrapper, &rust_zero, |sc| {
+                let mut address_list = MultiValueEncoded::new();
+                address_list.push(managed_address!(&router_address));
+                sc.add_to_pause_whitelist(address_list);
+            })
+            .assert_ok();
+    }
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(&owner, &router_setup.router_wrapper, &rust_zero, |sc| {
+            let paused_pairs = sc.pause_pairs(PairsSelection::ContainingToken(
+                managed_token_id!(WEGLD_TOKEN_ID),
+                0,
+                10,
+            ));
+            assert_eq!(paused_pairs.len(), 2);
+        })
+        .assert_ok();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(&owner, &router_setup.router_wrapper, &rust_zero, |sc| {
+            let mut pair_addresses = ManagedVec::new();
+            pair_addresses.push(managed_address!(&mex_pair_address));
+            let resumed_pairs = sc.resume_pairs(PairsSelection::Addresses(pair_addresses));
+            assert_eq!(resumed_pairs.len(), 1);
+        })
+        .assert_ok();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.mex_pair_wrapper, |sc| {
+            assert_eq!(sc.state().get(), State::Active);
+        })
+        .assert_ok();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.usdc_pair_wrapper, |sc| {
+            assert_eq!(sc.state().get(), State::Inactive);
+        })
+        .assert_ok();
+
+    // the whole batch fails if one of the addresses is not a pair
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(&owner, &router_setup.router_wrapper, &rust_zero, |sc| {
+            let mut pair_addresses = ManagedVec::new();
+            pair_addresses.push(managed_address!(&mex_pair_address));
+            pair_addresses.push(managed_address!(&owner));
+            let _ = sc.pause_pairs(PairsSelection::Addresses(pair_addresses));
+        })
+        .assert_user_error("Not a pair SC");
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(&owner, &router_setup.router_wrapper, &rust_zero, |sc| {
+            let _ = sc.pause_pairs(PairsSelection::ContainingToken(
+                managed_token_id!(CUSTOM_TOKEN_ID),
+                0,
+                10,
+            ));
+        })
+        .assert_user_error("No pairs selected");
+
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.mex_pair_wrapper, |sc| {
+            assert_eq!(sc.state().get(), State::Active);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn test_batch_upgrade_pairs() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+    router_setup.add_liquidity();
+
+    let rust_zero = rust_biguint!(0);
+    let owner = router_setup.owner_address.clone();
+
+    // the code the whitebox framework registered the pairs with
+    let interpreter_context = InterpreterContext::default()
+        .with_dir(std::env::current_dir().unwrap())
+        .with_allowed_missing_files();
+    let pair_code = interpret_string(&format!("file:../{PAIR_WASM_PATH}"), &interpreter_context);
+
+    // the second registry entry is the USDC pair
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(&owner, &router_setup.router_wrapper, &rust_zero, |sc| {
+            let upgraded_pairs = sc.upgrade_pairs(
+                managed_buffer!(&pair_code),
+                PairsSelection::ContainingToken(managed_token_id!(WEGLD_TOKEN_ID), 1, 1),
+            );
+            assert_eq!(upgraded_pairs.len(), 1);
+        })
+        .assert_ok();
+
+    router_setup
+        .blockchain_wrapper
+        .execute_tx(&owner, &router_setup.router_wrapper, &rust_zero, |sc| {
+            let mut pair_addresses = ManagedVec::new();
+            pair_addresses.push(managed_address!(router_setup
+                .mex_pair_wrapper
+                .address_ref()));
+            pair_addresses.push(managed_address!(router_setup
+                .usdc_pair_wrapper
+                .address_ref()));
+            let upgraded_pairs = sc.upgrade_pairs(
+                managed_buffer!(&pair_code),
+                PairsSelection::Addresses(pair_addresses),
+            );
+            assert_eq!(upgraded_pairs.len(), 2);
+        })
+        .assert_ok();
+
+    // the pairs keep their state through the upgrade
+    router_setup
+        .blockchain_wrapper
+        .execute_query(&router_setup.usdc_pair_wrapper, |sc| {
+            assert_eq!(
+                sc.pair_reserve(&managed_token_id!(USDC_TOKEN_ID)).get(),
+                managed_biguint!(ADD_LIQUIDITY_TOKENS)
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn test_multi_pair_swap_with_deadline() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+
+    router_setup.add_liquidity();
+
+    let user_address = router_setup.user_address.clone();
+    let mex_pair_address = router_setup.mex_pair_wrapper.address_ref().clone();
+    let usdc_pair_address = router_setup.usdc_pair_wrapper.address_ref().clone();
+
+    let build_swap_operations = || {
+        let mut swap_operations = MultiValueEncoded::<DebugApi, _>::new();
+        swap_operations.push(MultiValue4::from((
+            managed_address!(&mex_pair_address),
+            managed_buffer!(SWAP_TOKENS_FIXED_INPUT_FUNC_NAME),
+            managed_token_id!(WEGLD_TOKEN_ID),
+            managed_biguint!(1),
+        )));
+        swap_operations.push(MultiValue4::from((
+            managed_address!(&usdc_pair_address),
+            managed_buffer!(SWAP_TOKENS_FIXED_INPUT_FUNC_NAME),
+            managed_token_id!(USDC_TOKEN_ID),
+            managed_biguint!(1),
+        )));
+        swap_operations
+    };
+
+    router_setup.blockchain_wrapper.set_block_timestamp(1_000);
+
+    // deadline in the past
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let _ = sc.multi_pair_swap_with_deadline(
+                    managed_biguint!(1),
+                    999,
+                    build_swap_operations(),
+                );
+            },
+        )
+        .assert_user_error("Deadline exceeded");
+
+    // final amount below the minimum
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let _ = sc.multi_pair_swap_with_deadline(
+                    managed_biguint!(82_910),
+                    1_000,
+                    build_swap_operations(),
+                );
+            },
+        )
+        .assert_user_error("Slippage exceeded");
+
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let payments = sc.multi_pair_swap_with_deadline(
+                    managed_biguint!(82_909),
+                    1_000,
+                    build_swap_operations(),
+                );
+                assert_eq!(payments.len(), 1);
+                assert_eq!(payments.get(0).amount, managed_biguint!(82_909));
+            },
+        )
+        .assert_ok();
+
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        USDC_TOKEN_ID,
+        &rust_biguint!(5_000_082_909),
+    );
+}
+
+#[test]
+fn test_multi_pair_swap_split() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+
+    router_setup.add_liquidity();
+
+    let user_address = router_setup.user_address.clone();
+    let mex_pair_address = router_setup.mex_pair_wrapper.address_ref().clone();
+    let usdc_pair_address = router_setup.usdc_pair_wrapper.address_ref().clone();
+
+    let build_split_routes = || {
+        let mut swap_operations = ManagedVec::<DebugApi, SwapOperation<DebugApi>>::new();
+        swap_operations.push(SwapOperation {
+            pair_address: managed_address!(&mex_pair_address),
+            function: managed_buffer!(SWAP_TOKENS_FIXED_INPUT_FUNC_NAME),
+            token_wanted: managed_token_id!(WEGLD_TOKEN_ID),
+            amount_wanted: managed_biguint!(1),
+        });
+        swap_operations.push(SwapOperation {
+            pair_address: managed_address!(&usdc_pair_address),
+            function: managed_buffer!(SWAP_TOKENS_FIXED_INPUT_FUNC_NAME),
+            token_wanted: managed_token_id!(USDC_TOKEN_ID),
+            amount_wanted: managed_biguint!(1),
+        });
+
+        let mut split_routes = MultiValueEncoded::<DebugApi, SplitRouteType<DebugApi>>::new();
+        split_routes.push(MultiValue2::from((
+            managed_biguint!(60_000),
+            swap_operations.clone(),
+        )));
+        split_routes.push(MultiValue2::from((
+            managed_biguint!(40_000),
+            swap_operations,
+        )));
+        split_routes
+    };
+
+    // aggregated minimum not reached
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let _ = sc.multi_pair_swap_split(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(82_900),
+                    build_split_routes(),
+                );
+            },
+        )
+        .assert_user_error("Slippage exceeded");
+
+    // route amounts must cover the whole payment
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_001),
+            |sc| {
+                let _ = sc.multi_pair_swap_split(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(1),
+                    build_split_routes(),
+                );
+            },
+        )
+        .assert_user_error("Route amounts do not match the payment");
+
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let payments = sc.multi_pair_swap_split(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(82_899),
+                    build_split_routes(),
+                );
+                assert_eq!(payments.len(), 1);
+
+                let output_payment = payments.get(0);
+                assert_eq!(
+                    output_payment.token_identifier,
+                    managed_token_id!(USDC_TOKEN_ID)
+                );
+                assert_eq!(output_payment.amount, managed_biguint!(82_899));
+            },
+        )
+        .assert_ok();
+
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(4_999_900_000),
+    );
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        USDC_TOKEN_ID,
+        &rust_biguint!(5_000_082_899),
+    );
+}
+
+#[test]
+fn test_swap_auto_routed() {
+    let mut router_setup = RouterSetup::new(router::contract_obj, pair::contract_obj);
+
+    router_setup.add_liquidity();
+
+    let user_address = router_setup.user_address.clone();
+
+    // no route without the intermediate token
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let _ = sc.swap_auto_routed(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(1),
+                    3,
+                    MultiValueEncoded::new(),
+                );
+            },
+        )
+        .assert_user_error("No swap route found");
+
+    // end-to-end minimum not reached
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let mut intermediate_tokens = MultiValueEncoded::new();
+                intermediate_tokens.push(managed_token_id!(WEGLD_TOKEN_ID));
+                let _ = sc.swap_auto_routed(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(82_910),
+                    3,
+                    intermediate_tokens,
+                );
+            },
+        )
+        .assert_user_error("Slippage exceeded");
+
+    router_setup
+        .blockchain_wrapper
+        .execute_esdt_transfer(
+            &user_address,
+            &router_setup.router_wrapper,
+            MEX_TOKEN_ID,
+            0,
+            &rust_biguint!(100_000),
+            |sc| {
+                let mut intermediate_tokens = MultiValueEncoded::new();
+                intermediate_tokens.push(managed_token_id!(WEGLD_TOKEN_ID));
+                let output_payment = sc.swap_auto_routed(
+                    managed_token_id!(USDC_TOKEN_ID),
+                    managed_biguint!(82_909),
+                    3,
+                    intermediate_tokens,
+                );
+                assert_eq!(
+                    output_payment.token_identifier,
+                    managed_token_id!(USDC_TOKEN_ID)
+                );
+                assert_eq!(output_payment.amount, managed_biguint!(82_909));
+            },
+        )
+        .assert_ok();
+
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        MEX_TOKEN_ID,
+        &rust_biguint!(4_999_900_000),
+    );
+    router_setup.blockchain_wrapper.check_esdt_balance(
+        &user_address,
+        USDC_TOKEN_ID,
+        &rust_biguint!(5_000_082_909),
+    );
+}
+
 #[test]
 fn user_enable_pair_swaps_through_router_test() {
     let rust_zero = rust_biguint!(0u64);
@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           29
+// Endpoints:                           54
 // Async Callback:                       1
-// Total number of exported functions:  32
+// Total number of exported functions:  57
 
 #![no_std]
 
@@ -23,12 +23,19 @@ multiversx_sc_wasm_adapter::endpoints! {
         pause => pause
         resume => resume
         createPair => create_pair_endpoint
+        createPairWithFeeTier => create_pair_with_fee_tier_endpoint
+        createStablePair => create_stable_pair_endpoint
         upgradePair => upgrade_pair_endpoint
         issueLpToken => issue_lp_token
         setLocalRoles => set_local_roles
         removePair => remove_pair
+        removePairByFeeTier => remove_pair_by_fee_tier
+        addFeeTier => add_fee_tier
+        removeFeeTier => remove_fee_tier
+        getFeeTiers => get_fee_tiers
         setFeeOn => set_fee_on
         setFeeOff => set_fee_off
+        setPairEnergyFactoryAddress => set_pair_energy_factory_address
         setPairCreationEnabled => set_pair_creation_enabled
         getPairCreationEnabled => pair_creation_enabled
         getState => state
@@ -37,13 +44,31 @@ multiversx_sc_wasm_adapter::endpoints! {
         setPairTemplateAddress => set_pair_template_address
         getPairTemplateAddress => pair_template_address
         getTemporaryOwnerPeriod => temporary_owner_period
+        getPairFeeTier => pair_fee_tier
+        getFeeTierPairsCount => fee_tier_pairs_count
+        getPairCreationTimestamp => pair_creation_timestamp
         getCommonTokensForUserPairs => common_tokens_for_user_pairs
         getAllPairsManagedAddresses => get_all_pairs_addresses
         getAllPairTokens => get_all_token_pairs
         getAllPairContractMetadata => get_all_pair_contract_metadata
         getPair => get_pair
+        getPairByFeeTier => get_pair_by_fee_tier
         clearPairTemporaryOwnerStorage => clear_pair_temporary_owner_storage
         multiPairSwap => multi_pair_swap
+        multiPairSwapWithDeadline => multi_pair_swap_with_deadline
+        multiPairSwapSplit => multi_pair_swap_split
+        swapAutoRouted => swap_auto_routed
+        getBestPathFixedInput => get_best_path_fixed_input
+        getPairsAddressesPaginated => get_pairs_addresses_paginated
+        getPairContractMetadataPaginated => get_pair_contract_metadata_paginated
+        getPairsMetadataPaginated => get_pairs_metadata_paginated
+        getPairsCount => get_pairs_count
+        getPairRegistrySize => get_pair_registry_size
+        pausePairs => pause_pairs
+        resumePairs => resume_pairs
+        setFeeOnForPairs => set_fee_on_for_pairs
+        setFeeOffForPairs => set_fee_off_for_pairs
+        upgradePairs => upgrade_pairs
         configEnableByUserParameters => config_enable_by_user_parameters
         addCommonTokensForUserPairs => add_common_tokens_for_user_pairs
         removeCommonTokensForUserPairs => remove_common_tokens_for_user_pairs
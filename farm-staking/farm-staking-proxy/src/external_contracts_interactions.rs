@@ -5,8 +5,13 @@ use farm::{
     EnterFarmResultType, ExitFarmWithPartialPosResultType,
 };
 use farm_staking::{
-    claim_stake_farm_rewards::ProxyTrait as _, stake_farm::ProxyTrait as _,
-    unstake_farm::ProxyTrait as _,
+    claim_stake_farm_rewards::{
+        ClaimRewardsResultType as StakingFarmClaimRewardsResultType, ProxyTrait as _,
+    },
+    stake_farm::ProxyTrait as _,
+    unstake_farm::{
+        ExitFarmWithPartialPosResultType as StakingFarmExitResultType, ProxyTrait as _,
+    },
 };
 use farm_with_locked_rewards::ProxyTrait as _;
 use pair::{
@@ -37,11 +42,13 @@ pub trait ExternalContractsInteractionsModule:
             .claim_rewards_endpoint(OptionalValue::Some(orig_caller))
             .with_esdt_transfer((lp_farm_token_id, lp_farm_token_nonce, lp_farm_token_amount))
             .execute_on_dest_context();
-        let (new_lp_farm_tokens, lp_farm_rewards) = lp_farm_result.into_tuple();
+        let (new_lp_farm_tokens, lp_farm_rewards, lp_farm_extra_rewards) =
+            lp_farm_result.into_tuple();
 
         LpFarmClaimRewardsResult {
             new_lp_farm_tokens,
             lp_farm_rewards,
+            lp_farm_extra_rewards,
         }
     }
 
@@ -58,11 +65,12 @@ pub trait ExternalContractsInteractionsModule:
             .exit_farm_endpoint(OptionalValue::Some(orig_caller))
             .with_esdt_transfer((lp_farm_token_id, lp_farm_token_nonce, lp_farm_token_amount))
             .execute_on_dest_context();
-        let (lp_tokens, lp_farm_rewards) = exit_farm_result.into_tuple();
+        let (lp_tokens, lp_farm_rewards, lp_farm_extra_rewards) = exit_farm_result.into_tuple();
 
         LpFarmExitResult {
             lp_tokens,
             lp_farm_rewards,
+            lp_farm_extra_rewards,
         }
     }
 
@@ -118,7 +126,7 @@ pub trait ExternalContractsInteractionsModule:
         new_staking_farm_value: BigUint,
     ) -> StakingFarmClaimRewardsResult<Self::Api> {
         let staking_farm_address = self.staking_farm_address().get();
-        let staking_farm_result: ClaimRewardsResultType<Self::Api> = self
+        let staking_farm_result: StakingFarmClaimRewardsResultType<Self::Api> = self
             .staking_farm_proxy_obj(staking_farm_address)
             .claim_rewards_with_new_value(new_staking_farm_value, orig_caller)
             .with_esdt_transfer((
@@ -151,7 +159,7 @@ pub trait ExternalContractsInteractionsModule:
         ));
 
         let staking_farm_address = self.staking_farm_address().get();
-        let unstake_result: ExitFarmWithPartialPosResultType<Self::Api> = self
+        let unstake_result: StakingFarmExitResultType<Self::Api> = self
             .staking_farm_proxy_obj(staking_farm_address)
             .unstake_farm_through_proxy(orig_caller)
             .with_multi_token_transfer(payments)
@@ -1,4 +1,7 @@
-use crate::{dual_yield_token::DualYieldTokenAttributes, result_types::ClaimDualYieldResult};
+use crate::{
+    dual_yield_token::DualYieldTokenAttributes,
+    result_types::{ClaimDualYieldResult, PaymentsVec},
+};
 
 multiversx_sc::imports!();
 
@@ -6,6 +9,7 @@ pub struct InternalClaimResult<M: ManagedTypeApi> {
     pub lp_farm_rewards: EsdtTokenPayment<M>,
     pub staking_farm_rewards: EsdtTokenPayment<M>,
     pub new_dual_yield_attributes: DualYieldTokenAttributes<M>,
+    pub lp_farm_extra_rewards: PaymentsVec<M>,
 }
 
 #[multiversx_sc::module]
@@ -47,6 +51,7 @@ pub trait ProxyClaimModule:
             lp_farm_rewards: internal_claim_result.lp_farm_rewards,
             staking_farm_rewards: internal_claim_result.staking_farm_rewards,
             new_dual_yield_tokens,
+            lp_farm_extra_rewards: internal_claim_result.lp_farm_extra_rewards,
         };
 
         dual_yield_token_mapper.nft_burn(payment.token_nonce, &payment.amount);
@@ -98,6 +103,7 @@ pub trait ProxyClaimModule:
             lp_farm_rewards: lp_farm_claim_rewards_result.lp_farm_rewards,
             staking_farm_rewards: staking_farm_claim_rewards_result.staking_farm_rewards,
             new_dual_yield_attributes: new_attributes,
+            lp_farm_extra_rewards: lp_farm_claim_rewards_result.lp_farm_extra_rewards,
         }
     }
 }
@@ -8,11 +8,13 @@ pub type PaymentsVec<M> = ManagedVec<M, EsdtTokenPayment<M>>;
 pub struct LpFarmClaimRewardsResult<M: ManagedTypeApi> {
     pub new_lp_farm_tokens: EsdtTokenPayment<M>,
     pub lp_farm_rewards: EsdtTokenPayment<M>,
+    pub lp_farm_extra_rewards: PaymentsVec<M>,
 }
 
 pub struct LpFarmExitResult<M: ManagedTypeApi> {
     pub lp_tokens: EsdtTokenPayment<M>,
     pub lp_farm_rewards: EsdtTokenPayment<M>,
+    pub lp_farm_extra_rewards: PaymentsVec<M>,
 }
 
 // staking farm
@@ -67,6 +69,7 @@ pub struct ClaimDualYieldResult<M: ManagedTypeApi> {
     pub lp_farm_rewards: EsdtTokenPayment<M>,
     pub staking_farm_rewards: EsdtTokenPayment<M>,
     pub new_dual_yield_tokens: EsdtTokenPayment<M>,
+    pub lp_farm_extra_rewards: PaymentsVec<M>,
 }
 
 impl<M: ManagedTypeApi> ClaimDualYieldResult<M> {
@@ -79,6 +82,7 @@ impl<M: ManagedTypeApi> ClaimDualYieldResult<M> {
         payments.push(self.lp_farm_rewards.clone());
         payments.push(self.staking_farm_rewards.clone());
         payments.push(self.new_dual_yield_tokens.clone());
+        payments.append_vec(self.lp_farm_extra_rewards.clone());
 
         sc.send_multiple_tokens_if_not_zero(to, &payments);
 
@@ -92,6 +96,7 @@ pub struct UnstakeResult<M: ManagedTypeApi> {
     pub lp_farm_rewards: EsdtTokenPayment<M>,
     pub staking_rewards: EsdtTokenPayment<M>,
     pub unbond_staking_farm_token: EsdtTokenPayment<M>,
+    pub lp_farm_extra_rewards: PaymentsVec<M>,
 }
 
 impl<M: ManagedTypeApi> UnstakeResult<M> {
@@ -105,6 +110,7 @@ impl<M: ManagedTypeApi> UnstakeResult<M> {
         payments.push(self.lp_farm_rewards.clone());
         payments.push(self.staking_rewards.clone());
         payments.push(self.unbond_staking_farm_token.clone());
+        payments.append_vec(self.lp_farm_extra_rewards.clone());
 
         sc.send_multiple_tokens_if_not_zero(to, &payments);
 
@@ -489,7 +489,7 @@ where
                 farm_token_nonce,
                 &rust_biguint!(farm_token_amount),
                 |sc| {
-                    let (_lp_tokens, _boosted_rewards_payment) =
+                    let (_lp_tokens, _boosted_rewards_payment, _extra_rewards) =
                         sc.exit_farm_endpoint(OptionalValue::None).into_tuple();
                 },
             )
@@ -512,7 +512,7 @@ where
                 farm_token_nonce,
                 &rust_biguint!(farm_token_amount),
                 |sc| {
-                    let (output_farm_token, boosted_rewards_payment) =
+                    let (output_farm_token, boosted_rewards_payment, _extra_rewards) =
                         sc.claim_rewards_endpoint(OptionalValue::None).into_tuple();
                     assert_eq!(output_farm_token.amount, farm_token_amount);
                     assert_eq!(boosted_rewards_payment.amount, expected_lp_farm_rewards);
@@ -1,9 +1,11 @@
 multiversx_sc::imports!();
 
-use farm::base_functions::ClaimRewardsResultType;
+use farm::base_functions::DoubleMultiPayment;
 
 use crate::base_impl_wrapper::FarmStakingWrapper;
 
+pub type ClaimRewardsResultType<M> = DoubleMultiPayment<M>;
+
 #[multiversx_sc::module]
 pub trait ClaimStakeFarmRewardsModule:
     crate::custom_rewards::CustomRewardsModule
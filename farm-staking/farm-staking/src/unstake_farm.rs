@@ -1,9 +1,11 @@
 multiversx_sc::imports!();
 
-use farm::ExitFarmWithPartialPosResultType;
+use farm::base_functions::DoubleMultiPayment;
 
 use crate::{base_impl_wrapper::FarmStakingWrapper, token_attributes::UnbondSftAttributes};
 
+pub type ExitFarmWithPartialPosResultType<M> = DoubleMultiPayment<M>;
+
 #[multiversx_sc::module]
 pub trait UnstakeFarmModule:
     crate::custom_rewards::CustomRewardsModule
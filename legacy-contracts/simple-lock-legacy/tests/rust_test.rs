@@ -409,6 +409,8 @@ fn exit_farm_test() {
         compounded_reward: managed_biguint!(0u64),
         current_farm_amount: managed_biguint!(2_000u64),
         original_owner: managed_address!(&user_addr),
+        unlock_epoch: 0,
+        reward_boost_percent: 0,
     };
     b_mock.set_nft_balance(
         sc_wrapper.address_ref(),
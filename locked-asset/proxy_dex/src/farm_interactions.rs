@@ -1,5 +1,6 @@
 multiversx_sc::imports!();
 
+use common_structs::PaymentsVec;
 use farm::{
     base_functions::{ClaimRewardsResultType, ClaimRewardsResultWrapper},
     EnterFarmResultType, ExitFarmWithPartialPosResultType,
@@ -14,6 +15,7 @@ pub struct EnterFarmResultWrapper<M: ManagedTypeApi> {
 pub struct ExitFarmResultWrapper<M: ManagedTypeApi> {
     pub farming_tokens: EsdtTokenPayment<M>,
     pub reward_tokens: EsdtTokenPayment<M>,
+    pub extra_rewards: PaymentsVec<M>,
 }
 
 #[multiversx_sc::module]
@@ -50,11 +52,12 @@ pub trait FarmInteractionsModule {
             .exit_farm_endpoint(OptionalValue::Some(user))
             .with_esdt_transfer(farm_token)
             .execute_on_dest_context();
-        let (farming_tokens, reward_tokens) = raw_result.into_tuple();
+        let (farming_tokens, reward_tokens, extra_rewards) = raw_result.into_tuple();
 
         ExitFarmResultWrapper {
             farming_tokens,
             reward_tokens,
+            extra_rewards,
         }
     }
 
@@ -69,11 +72,12 @@ pub trait FarmInteractionsModule {
             .claim_rewards_endpoint(OptionalValue::Some(user))
             .with_esdt_transfer(farm_token)
             .execute_on_dest_context();
-        let (new_farm_token, rewards) = raw_result.into_tuple();
+        let (new_farm_token, rewards, extra_rewards) = raw_result.into_tuple();
 
         ClaimRewardsResultWrapper {
             new_farm_token,
             rewards,
+            extra_rewards,
         }
     }
 
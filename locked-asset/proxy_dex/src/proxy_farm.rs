@@ -3,7 +3,7 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
-use common_structs::Epoch;
+use common_structs::{Epoch, PaymentsVec};
 use fixed_supply_token::FixedSupplyToken;
 
 use crate::{
@@ -19,8 +19,11 @@ pub struct EnterFarmResult<M: ManagedTypeApi> {
 }
 
 pub type EnterFarmProxyResultType<M> = MultiValue2<EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
-pub type ExitFarmProxyResultType<M> = MultiValue2<EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
-pub type ClaimRewardsFarmProxyResultType<M> = MultiValue2<EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
+/// The farm's additional token and campaign rewards come last
+pub type ExitFarmProxyResultType<M> =
+    MultiValue3<EsdtTokenPayment<M>, EsdtTokenPayment<M>, PaymentsVec<M>>;
+pub type ClaimRewardsFarmProxyResultType<M> =
+    MultiValue3<EsdtTokenPayment<M>, EsdtTokenPayment<M>, PaymentsVec<M>>;
 
 #[multiversx_sc::module]
 pub trait ProxyFarmModule:
@@ -220,6 +223,7 @@ pub trait ProxyFarmModule:
 
         self.send_payment_non_zero(&caller, &initial_proxy_farming_tokens);
         self.send_payment_non_zero(&caller, &exit_result.reward_tokens);
+        self.send_multiple_tokens_if_not_zero(&caller, &exit_result.extra_rewards);
 
         wrapped_farm_token_mapper.nft_burn(payment.token_nonce, &payment.amount);
 
@@ -231,7 +235,12 @@ pub trait ProxyFarmModule:
             exit_result.reward_tokens.clone(),
         );
 
-        (initial_proxy_farming_tokens, exit_result.reward_tokens).into()
+        (
+            initial_proxy_farming_tokens,
+            exit_result.reward_tokens,
+            exit_result.extra_rewards,
+        )
+            .into()
     }
 
     fn handle_farm_penalty_and_get_output_proxy_farming_token(
@@ -323,6 +332,7 @@ pub trait ProxyFarmModule:
 
         self.send_payment_non_zero(&caller, &new_wrapped_token);
         self.send_payment_non_zero(&caller, &claim_result.rewards);
+        self.send_multiple_tokens_if_not_zero(&caller, &claim_result.extra_rewards);
 
         // Burn farm token
         wrapped_farm_token_mapper.nft_burn(payment.token_nonce, &payment.amount);
@@ -337,7 +347,12 @@ pub trait ProxyFarmModule:
             claim_result.rewards.clone(),
         );
 
-        (new_wrapped_token, claim_result.rewards).into()
+        (
+            new_wrapped_token,
+            claim_result.rewards,
+            claim_result.extra_rewards,
+        )
+            .into()
     }
 
     #[payable("*")]
@@ -1,4 +1,4 @@
-use common_structs::{RawResultWrapper, RawResultsType};
+use common_structs::{PaymentsVec, RawResultWrapper, RawResultsType};
 
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
@@ -6,13 +6,13 @@ multiversx_sc::derive_imports!();
 type EnterFarmResultType<BigUint> =
     MultiValue2<EsdtTokenPayment<BigUint>, EsdtTokenPayment<BigUint>>;
 type ExitFarmResultType<BigUint> =
-    MultiValue2<EsdtTokenPayment<BigUint>, EsdtTokenPayment<BigUint>>;
+    MultiValue3<EsdtTokenPayment<BigUint>, EsdtTokenPayment<BigUint>, PaymentsVec<BigUint>>;
 type ClaimRewardsResultType<BigUint> =
-    MultiValue2<EsdtTokenPayment<BigUint>, EsdtTokenPayment<BigUint>>;
+    MultiValue3<EsdtTokenPayment<BigUint>, EsdtTokenPayment<BigUint>, PaymentsVec<BigUint>>;
 
 const ENTER_FARM_RESULTS_LEN: usize = 2;
-const EXIT_FARM_RESULTS_LEN: usize = 2;
-const CLAIM_REWARDS_RESULTS_LEN: usize = 2;
+const EXIT_FARM_RESULTS_LEN: usize = 3;
+const CLAIM_REWARDS_RESULTS_LEN: usize = 3;
 
 pub struct EnterFarmResultWrapper<M: ManagedTypeApi> {
     pub farm_tokens: EsdtTokenPayment<M>,
@@ -22,11 +22,13 @@ pub struct EnterFarmResultWrapper<M: ManagedTypeApi> {
 pub struct ExitFarmResultWrapper<M: ManagedTypeApi> {
     pub initial_farming_tokens: EsdtTokenPayment<M>,
     pub reward_tokens: EsdtTokenPayment<M>,
+    pub extra_reward_tokens: PaymentsVec<M>,
 }
 
 pub struct FarmClaimRewardsResultWrapper<M: ManagedTypeApi> {
     pub new_farm_tokens: EsdtTokenPayment<M>,
     pub reward_tokens: EsdtTokenPayment<M>,
+    pub extra_reward_tokens: PaymentsVec<M>,
 }
 
 pub struct FarmCompoundRewardsResultWrapper<M: ManagedTypeApi> {
@@ -121,10 +123,12 @@ pub trait FarmInteractionsModule {
 
         let initial_farming_tokens = results_wrapper.decode_next_result();
         let reward_tokens = results_wrapper.decode_next_result();
+        let extra_reward_tokens = results_wrapper.decode_next_result();
 
         ExitFarmResultWrapper {
             initial_farming_tokens,
             reward_tokens,
+            extra_reward_tokens,
         }
     }
 
@@ -151,10 +155,12 @@ pub trait FarmInteractionsModule {
 
         let new_farm_tokens = results_wrapper.decode_next_result();
         let reward_tokens = results_wrapper.decode_next_result();
+        let extra_reward_tokens = results_wrapper.decode_next_result();
 
         FarmClaimRewardsResultWrapper {
             new_farm_tokens,
             reward_tokens,
+            extra_reward_tokens,
         }
     }
 
@@ -1,6 +1,8 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
+use common_structs::PaymentsVec;
+
 use crate::{error_messages::*, proxy_lp::LpProxyTokenAttributes};
 
 #[derive(
@@ -21,9 +23,10 @@ pub struct FarmProxyTokenAttributes<M: ManagedTypeApi> {
 }
 
 pub type EnterFarmThroughProxyResultType<M> = MultiValue2<EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
-pub type ExitFarmThroughProxyResultType<M> = MultiValue2<EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
+pub type ExitFarmThroughProxyResultType<M> =
+    MultiValue3<EsdtTokenPayment<M>, EsdtTokenPayment<M>, PaymentsVec<M>>;
 pub type FarmClaimRewardsThroughProxyResultType<M> =
-    MultiValue2<EsdtTokenPayment<M>, EsdtTokenPayment<M>>;
+    MultiValue3<EsdtTokenPayment<M>, EsdtTokenPayment<M>, PaymentsVec<M>>;
 pub type FarmCompoundRewardsThroughProxyResultType<M> = EsdtTokenPayment<M>;
 
 #[multiversx_sc::module]
@@ -197,6 +200,7 @@ pub trait ProxyFarmModule:
     /// Output Payments:
     /// - original farming tokens
     /// - farm reward tokens
+    /// - the farm's additional reward tokens
     #[payable("*")]
     #[endpoint(exitFarmLockedToken)]
     fn exit_farm_locked_token(&self) -> ExitFarmThroughProxyResultType<Self::Api> {
@@ -254,7 +258,17 @@ pub trait ProxyFarmModule:
             );
         }
 
-        (lp_proxy_token_payment, exit_farm_result.reward_tokens).into()
+        if !exit_farm_result.extra_reward_tokens.is_empty() {
+            self.send()
+                .direct_multi(&caller, &exit_farm_result.extra_reward_tokens);
+        }
+
+        (
+            lp_proxy_token_payment,
+            exit_farm_result.reward_tokens,
+            exit_farm_result.extra_reward_tokens,
+        )
+            .into()
     }
 
     /// Claim rewards from a previously entered farm.
@@ -266,6 +280,7 @@ pub trait ProxyFarmModule:
     /// Output payments:
     /// - a new FARM_PROXY token
     /// - farm reward tokens
+    /// - the farm's additional reward tokens
     #[payable("*")]
     #[endpoint(farmClaimRewardsLockedToken)]
     fn farm_claim_rewards_locked_token(&self) -> FarmClaimRewardsThroughProxyResultType<Self::Api> {
@@ -309,7 +324,17 @@ pub trait ProxyFarmModule:
             );
         }
 
-        (new_proxy_token_payment, claim_rewards_result.reward_tokens).into()
+        if !claim_rewards_result.extra_reward_tokens.is_empty() {
+            self.send()
+                .direct_multi(&caller, &claim_rewards_result.extra_reward_tokens);
+        }
+
+        (
+            new_proxy_token_payment,
+            claim_rewards_result.reward_tokens,
+            claim_rewards_result.extra_reward_tokens,
+        )
+            .into()
     }
 
     fn try_get_farm_address(